@@ -458,10 +458,33 @@ const SettingsModal: FC<SettingsModalProps> = ({
         return [];
       };
 
+      // Prefer SteamVR's localized origin names, fall back to binding paths
+      // SteamVR のローカライズ済み表示名を優先し、無ければ割り当てパスを表示
+      const toBindingLabels = (origins: unknown, bindings: unknown): string[] => {
+        const originNames = toStringArray(origins);
+        if (originNames.length > 0) return originNames;
+        if (!Array.isArray(bindings)) return toStringArray(bindings);
+        return bindings.flatMap((binding: unknown) => {
+          if (typeof binding === 'string') return binding.length > 0 ? [binding] : [];
+          if (!binding || typeof binding !== 'object') return [];
+          const { devicePath, inputPath, mode, slot } = binding as Partial<SteamVrBindingInfo>;
+          const label = [devicePath, inputPath, mode, slot]
+            .filter((part): part is string => typeof part === 'string' && part.length > 0)
+            .join(' ');
+          return label ? [label] : [];
+        });
+      };
+
       setInitialized(Boolean(result.bindings.initialized));
-      setToggleBindings(toStringArray(result.bindings.toggleOverlay));
-      setTriggerBindings(toStringArray(result.bindings.triggerBindings));
-      setGripBindings(toStringArray(result.bindings.gripBindings));
+      setToggleBindings(
+        toBindingLabels(result.bindings.toggleOverlayOrigins, result.bindings.toggleOverlay),
+      );
+      setTriggerBindings(
+        toBindingLabels(result.bindings.triggerOrigins, result.bindings.triggerBindings),
+      );
+      setGripBindings(
+        toBindingLabels(result.bindings.gripOrigins, result.bindings.gripBindings),
+      );
       setTriggerBound(Boolean(result.bindings.triggerBound));
       setGripBound(Boolean(result.bindings.gripBound));
     } catch {
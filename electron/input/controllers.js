@@ -2,26 +2,17 @@ import { state } from './state.js';
 import { endDrag, processGripDrag } from './drag.js';
 import { handleTriggerInput } from './trigger.js';
 
-export function computeHitFromPose(poseMatrix, overlayHandle) {
+/**
+ * Intersect a controller's ray with the overlay in the native module.
+ * Returns { raw, smoothed, timestamp } or null; `smoothed` runs through the native 1€ filter,
+ * which is reset whenever the ray leaves the overlay.
+ */
+export function computeControllerHit(controllerId, overlayHandle) {
   try {
     if (!state.overlayManager) return null;
-    // Extract position and forward direction
-    // Position (Tx, Ty, Tz)
-    const px = poseMatrix[3];
-    const py = poseMatrix[7];
-    const pz = poseMatrix[11];
-
-    // Forward vector (-Z axis column of rotation)
-    const dirX = -poseMatrix[2];
-    const dirY = -poseMatrix[6];
-    const dirZ = -poseMatrix[10];
-
-    // Raycast Intersection (Click / Cursor)
-    // Call standard OpenVR intersection
-    return state.overlayManager.computeOverlayIntersection(
+    return state.overlayManager.computeControllerIntersection(
       overlayHandle,
-      [px, py, pz],
-      [dirX, dirY, dirZ],
+      controllerId,
     );
   } catch (e) {
     console.error('Controller hit test error:', e);
@@ -19,6 +19,8 @@ export const state = {
   // Per-controller trigger press state:
   // { startU, startV, lastU, lastV, dragging, moved }
   triggerDragState: {},
+  // Controllers held down according to the native trigger state machine
+  triggerHeld: {},
   windowSize: { width: 0, height: 0 },
   windowScale: { zoomFactor: 1 },
   drag: {
@@ -27,5 +29,4 @@ export const state = {
     startControllerInverse: mat4.create(),
     startOverlayTransform: mat4.create(),
   },
-  inputSmoothers: {},
 };
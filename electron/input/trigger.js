@@ -7,6 +7,31 @@ import {
 import { sendClickEvent, sendScrollEvent } from './events.js';
 import { state } from './state.js';
 
+/**
+ * Drain edges from the native trigger state machine (pollTriggerEvents), which applies
+ * hysteresis and debouncing to the analog axis, legacy button and SteamVR Input action.
+ * Returns the controllers pressed during this poll, so a press and release that arrive
+ * together still count as down for one frame.
+ */
+export function pollTriggerEdges() {
+  const pressedThisPoll = new Set();
+  if (!state.overlayManager) return pressedThisPoll;
+  for (const event of state.overlayManager.pollTriggerEvents()) {
+    const controllerId = event.controllerIndex;
+    if (event.kind === 'pressed') {
+      state.triggerHeld[controllerId] = true;
+      pressedThisPoll.add(controllerId);
+    } else if (event.kind === 'released') {
+      delete state.triggerHeld[controllerId];
+    }
+  }
+  return pressedThisPoll;
+}
+
+export function isTriggerDown(controllerId, pressedThisPoll) {
+  return !!state.triggerHeld[controllerId] || pressedThisPoll.has(controllerId);
+}
+
 export function handleTriggerInput(controllerId, controllerState, hit) {
   if (!controllerState) return;
   const pressed = !!controllerState.triggerPressed;
@@ -71,4 +96,5 @@ export function releaseTriggerForController(
   _clickCountOverride = null,
 ) {
   delete state.triggerDragState[controllerId];
+  delete state.triggerHeld[controllerId];
 }
@@ -125,3 +125,46 @@ describe('handleTriggerInput', () => {
     expect(state.triggerDragState[7]).toBeUndefined();
   });
 });
+
+describe('pollTriggerEdges', () => {
+  beforeEach(() => {
+    vi.resetModules();
+    vi.clearAllMocks();
+  });
+
+  it('tracks held controllers from native pressed and released edges', async () => {
+    const { isTriggerDown, pollTriggerEdges } = await import('./trigger.js');
+    const { state } = await import('./state.js');
+    const pollTriggerEvents = vi
+      .fn()
+      .mockReturnValueOnce([
+        { kind: 'pressed', controllerIndex: 1, timestamp: 1, value: 0.9 },
+        { kind: 'longPress', controllerIndex: 1, timestamp: 1.6, value: 0.9 },
+      ])
+      .mockReturnValueOnce([])
+      .mockReturnValueOnce([
+        { kind: 'released', controllerIndex: 1, timestamp: 2, value: 0.1 },
+      ]);
+    state.overlayManager = { pollTriggerEvents };
+
+    expect(isTriggerDown(1, pollTriggerEdges())).toBe(true);
+    expect(isTriggerDown(1, pollTriggerEdges())).toBe(true);
+    expect(isTriggerDown(1, pollTriggerEdges())).toBe(false);
+  });
+
+  it('keeps a press and release from the same poll down for one frame', async () => {
+    const { isTriggerDown, pollTriggerEdges } = await import('./trigger.js');
+    const { state } = await import('./state.js');
+    const pollTriggerEvents = vi
+      .fn()
+      .mockReturnValueOnce([
+        { kind: 'pressed', controllerIndex: 2, timestamp: 1, value: 0.9 },
+        { kind: 'released', controllerIndex: 2, timestamp: 1.03, value: 0.1 },
+      ])
+      .mockReturnValueOnce([]);
+    state.overlayManager = { pollTriggerEvents };
+
+    expect(isTriggerDown(2, pollTriggerEdges())).toBe(true);
+    expect(isTriggerDown(2, pollTriggerEdges())).toBe(false);
+  });
+});
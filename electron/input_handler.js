@@ -12,11 +12,14 @@ import {
   sendMouseMoveEvent,
   sendTriggerStateEvent,
 } from './input/events.js';
-import { computeHitFromPose, processController } from './input/controllers.js';
+import { computeControllerHit, processController } from './input/controllers.js';
 import { endDrag } from './input/drag.js';
 import { state } from './input/state.js';
-import { PointerStabilizer } from './input/smoothing.js';
-import { releaseTriggerForController } from './input/trigger.js';
+import {
+  isTriggerDown,
+  pollTriggerEdges,
+  releaseTriggerForController,
+} from './input/trigger.js';
 
 export { updateWindowSize } from './input/mapping.js';
 
@@ -111,7 +114,7 @@ export function stopInputLoop() {
     ...Object.keys(state.lastMoveAtByController),
     ...Object.keys(state.lastTriggerPressedState),
     ...Object.keys(state.triggerDragState),
-    ...Object.keys(state.inputSmoothers),
+    ...Object.keys(state.triggerHeld),
   ]);
   for (const key of knownControllerIds) {
     const controllerId = Number(key);
@@ -125,7 +128,7 @@ export function stopInputLoop() {
   state.lastMoveAtByController = {};
   state.lastTriggerPressedState = {};
   state.triggerDragState = {};
-  state.inputSmoothers = {};
+  state.triggerHeld = {};
   state.lastMouseHit = false;
   state.lastMouseControllerId = null;
   state.lastMousePosition = { x: 0, y: 0 };
@@ -148,6 +151,7 @@ function updateInput() {
     const hitCandidates = [];
     const observedControllerIds = new Set();
     const now = Date.now();
+    const pressedThisPoll = pollTriggerEdges();
     // 2. Process each controller
     for (const id of controllerIds) {
       if (id === 0) continue; // Skip HMD
@@ -165,29 +169,28 @@ function updateInput() {
         cleanupControllerRuntimeState(id);
         continue;
       }
-      const pressedNow = !!controllerState.triggerPressed;
+      // Trigger state comes from the native state machine, not the raw button bit
+      // トリガー状態は生のボタンではなくネイティブの状態機械から得る
+      const pressedNow = isTriggerDown(id, pressedThisPoll);
       if (state.lastTriggerPressedState[id] !== pressedNow) {
         sendTriggerStateEvent(id, pressedNow);
         state.lastTriggerPressedState[id] = pressedNow;
       }
-      // Use absolute tracking pose directly with ComputeOverlayIntersection
-      // ComputeOverlayIntersectionは絶対座標を受け取るため、変換不要
-      const hit = computeHitFromPose(poseData, activeHandle);
-      processController(id, poseData, activeHandle, controllerState, hit);
+      // Raw hits drive trigger and drag; the cursor follows the natively smoothed hit
+      // トリガーとドラッグは生のヒット、カーソルはネイティブで平滑化したヒットを使う
+      const intersection = computeControllerHit(id, activeHandle);
+      const hit = intersection?.raw ?? null;
+      processController(
+        id,
+        poseData,
+        activeHandle,
+        { ...controllerState, triggerPressed: pressedNow },
+        hit,
+      );
       if (hit) {
-        // --- Smoothing Logic Start ---
-        if (!state.inputSmoothers[id]) {
-          // Initialize smoothing filter for this controller
-          // Parameters (minCutoff, beta, dcutoff) need tuning.
-          // minCutoff=0.1: Very strong smoothing at low speed
-          // beta=5.0: Quick response at high speed
-          state.inputSmoothers[id] = new PointerStabilizer(0.1, 5.0, 1.0);
-        }
-        const smoothed = state.inputSmoothers[id].update(hit.u, hit.v, now);
-        // Use smoothed coordinates for cursor events
-        sendCursorEvent(id, smoothed.x, smoothed.y);
-        hitCandidates.push({ controllerId: id, u: smoothed.x, v: smoothed.y });
-        // --- Smoothing Logic End ---
+        const smoothed = intersection.smoothed;
+        sendCursorEvent(id, smoothed.u, smoothed.v);
+        hitCandidates.push({ controllerId: id, u: smoothed.u, v: smoothed.v });
 
         const previous = state.lastHitByController[id];
         if (
@@ -204,10 +207,6 @@ function updateInput() {
         state.lastCursorHitState[id] = false;
         delete state.lastHitByController[id];
         delete state.lastMoveAtByController[id];
-        // Reset smoother when invalid
-        if (state.inputSmoothers[id]) {
-          state.inputSmoothers[id].reset();
-        }
       }
     }
     cleanupStaleControllers(observedControllerIds);
@@ -276,7 +275,7 @@ function cleanupStaleControllers(observedControllerIds) {
     ...Object.keys(state.lastMoveAtByController),
     ...Object.keys(state.lastTriggerPressedState),
     ...Object.keys(state.triggerDragState),
-    ...Object.keys(state.inputSmoothers),
+    ...Object.keys(state.triggerHeld),
   ]);
 
   for (const key of knownIds) {
@@ -294,10 +293,6 @@ function cleanupControllerRuntimeState(controllerId) {
   if (state.lastTriggerPressedState[controllerId]) {
     sendTriggerStateEvent(controllerId, false);
   }
-  if (state.inputSmoothers[controllerId]) {
-    state.inputSmoothers[controllerId].reset();
-    delete state.inputSmoothers[controllerId];
-  }
   if (controllerId === state.drag.draggingControllerId) {
     endDrag();
   }
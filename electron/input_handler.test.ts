@@ -7,6 +7,7 @@ const overlayManagerMock = {
   getControllerIds: vi.fn(),
   getControllerPose: vi.fn(),
   getControllerState: vi.fn(),
+  pollTriggerEvents: vi.fn(() => []),
 };
 
 vi.mock('./overlay.js', () => ({
@@ -19,7 +20,7 @@ vi.mock('./overlay.js', () => ({
 }));
 
 vi.mock('./input/controllers.js', () => ({
-  computeHitFromPose: vi.fn(),
+  computeControllerHit: vi.fn(),
   processController: vi.fn(),
 }));
 
@@ -49,7 +50,7 @@ describe('input_handler cleanup behavior', () => {
   it('hides cursor and cleans per-controller state when controller disappears', async () => {
     const { state } = await import('./input/state.js');
     const { startInputLoop, stopInputLoop } = await import('./input_handler.js');
-    const { computeHitFromPose } = await import('./input/controllers.js');
+    const { computeControllerHit } = await import('./input/controllers.js');
     const { sendCursorHideEvent, sendTriggerStateEvent } = await import('./input/events.js');
 
     let activeControllers = [1];
@@ -64,7 +65,11 @@ describe('input_handler cleanup behavior', () => {
       triggerPressed: false,
       gripPressed: false,
     }));
-    vi.mocked(computeHitFromPose).mockReturnValue({ u: 0.2, v: 0.3 });
+    vi.mocked(computeControllerHit).mockReturnValue({
+      raw: { u: 0.2, v: 0.3 },
+      smoothed: { u: 0.2, v: 0.3 },
+      timestamp: 0,
+    });
 
     startInputLoop(120, {} as Electron.WebContents);
     expect(captureFrameListener).toBeTypeOf('function');
@@ -84,7 +89,6 @@ describe('input_handler cleanup behavior', () => {
     expect(state.lastMoveAtByController[1]).toBeUndefined();
     expect(state.lastTriggerPressedState[1]).toBeUndefined();
     expect(state.triggerDragState[1]).toBeUndefined();
-    expect(state.inputSmoothers[1]).toBeUndefined();
 
     stopInputLoop();
   });
@@ -113,9 +117,6 @@ describe('input_handler cleanup behavior', () => {
         moved: true,
       },
     };
-    state.inputSmoothers = {
-      2: { reset: vi.fn() },
-    };
     state.captureSyncUnsubscribe = unsubscribeMock;
     state.drag.isDragging = true;
     state.drag.draggingControllerId = 2;
@@ -134,13 +135,12 @@ describe('input_handler cleanup behavior', () => {
     expect(state.lastMoveAtByController).toEqual({});
     expect(state.lastTriggerPressedState).toEqual({});
     expect(state.triggerDragState).toEqual({});
-    expect(state.inputSmoothers).toEqual({});
   });
 
   it('cleans controller runtime state when pose is temporarily unavailable', async () => {
     const { state } = await import('./input/state.js');
     const { startInputLoop, stopInputLoop } = await import('./input_handler.js');
-    const { computeHitFromPose } = await import('./input/controllers.js');
+    const { computeControllerHit } = await import('./input/controllers.js');
     const { sendCursorHideEvent, sendTriggerStateEvent } = await import('./input/events.js');
 
     let poseAvailable = true;
@@ -159,7 +159,14 @@ describe('input_handler cleanup behavior', () => {
       triggerPressed: true,
       gripPressed: false,
     });
-    vi.mocked(computeHitFromPose).mockReturnValue({ u: 0.2, v: 0.3 });
+    overlayManagerMock.pollTriggerEvents.mockReturnValueOnce([
+      { kind: 'pressed', controllerIndex: 1, timestamp: 0, value: 1 },
+    ]);
+    vi.mocked(computeControllerHit).mockReturnValue({
+      raw: { u: 0.2, v: 0.3 },
+      smoothed: { u: 0.2, v: 0.3 },
+      timestamp: 0,
+    });
 
     startInputLoop(120, {} as Electron.WebContents);
     captureFrameListener?.();
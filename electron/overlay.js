@@ -34,7 +34,7 @@ function assertOverlayApi(manager) {
     'createOverlay',
     'destroyOverlay',
     'setOverlayWidth',
-    'setOverlayFromFile',
+    'setOverlayFromImage',
     'showOverlay',
     'hideOverlay',
     'setOverlayTransformAbsolute',
@@ -274,24 +274,21 @@ export function initOverlay() {
     const texturePath = getAssetPath(path.join('img', 'logo.png'));
     console.log(`Setting overlay texture from: ${texturePath}`);
 
-    try {
-      state.overlayManager.setOverlayFromFile(state.overlayHandle, texturePath);
-    } catch (e) {
-      console.error(`Failed to set initial texture from ${texturePath}:`, e);
-    }
+    state.overlayManager
+      .setOverlayFromImage(state.overlayHandle, texturePath)
+      .catch((e) => {
+        console.error(`Failed to set initial texture from ${texturePath}:`, e);
+      });
 
     if (state.overlayHandleBack !== null) {
-      try {
-        state.overlayManager.setOverlayFromFile(
-          state.overlayHandleBack,
-          texturePath,
-        );
-      } catch (e) {
-        console.error(
-          `Failed to set back initial texture from ${texturePath}:`,
-          e,
-        );
-      }
+      state.overlayManager
+        .setOverlayFromImage(state.overlayHandleBack, texturePath)
+        .catch((e) => {
+          console.error(
+            `Failed to set back initial texture from ${texturePath}:`,
+            e,
+          );
+        });
     }
 
     console.log('Overlay Initial Props Set');
@@ -348,7 +345,11 @@ export function initSplash() {
 
     // Set texture
     const texturePath = getAssetPath(path.join('img', 'logo.png'));
-    state.overlayManager.setOverlayFromFile(state.splashHandle, texturePath);
+    state.overlayManager
+      .setOverlayFromImage(state.splashHandle, texturePath)
+      .catch((e) => {
+        console.error(`Failed to set splash texture from ${texturePath}:`, e);
+      });
 
     // Show
     state.overlayManager.showOverlay(state.splashHandle);
@@ -425,6 +426,32 @@ export function getOverlayManager() {
   return state.overlayManager;
 }
 
+/**
+ * Get texture pipeline statistics for the debug panel / デバッグパネル用のテクスチャパイプライン統計を取得
+ */
+export function getTextureStats() {
+  if (!state.overlayManager) return null;
+  return state.overlayManager.getTextureStats();
+}
+
+/**
+ * Save the last submitted overlay frame as a PNG / 直近に送信したオーバーレイのフレームを PNG で保存
+ */
+export function saveLastFrame(filePath) {
+  if (!state.overlayManager) return false;
+  state.overlayManager.saveLastFrame(filePath);
+  return true;
+}
+
+/**
+ * Save the next `count` submitted overlay frames as PNGs in `dir` / 次に送信する `count` 枚のフレームを `dir` に PNG で保存
+ */
+export function captureNextFrames(count, dir) {
+  if (!state.overlayManager) return false;
+  state.overlayManager.captureNextFrames(count, dir);
+  return true;
+}
+
 export function showOverlayAll() {
   if (!state.overlayManager || state.overlayHandle === null) return;
   state.overlayManager.showOverlay(state.overlayHandle);
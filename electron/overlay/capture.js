@@ -4,7 +4,9 @@ const DEFAULT_CAPTURE_FPS = 60;
 const MIN_CAPTURE_FPS = 1;
 const MAX_CAPTURE_FPS = 120;
 const SIZE_MISMATCH_LOG_INTERVAL_MS = 5000;
-const MAX_FRAME_RETENTION = 3;
+// Row alignment padding is smaller than this; anything larger means a DPI-mismatched bitmap
+// 行揃えのパディングはこれ未満。これ以上は DPI の合わないビットマップとみなす
+const MAX_ROW_PADDING_BYTES = 64;
 const INVALID_OVERLAY_HANDLE = 0;
 const captureFrameListeners = new Set();
 
@@ -18,50 +20,6 @@ function getBitmapBuffer(image) {
   return image.toBitmap();
 }
 
-function deriveSizeFromBuffer(size, bufferLength) {
-  if (bufferLength % 4 !== 0) return null;
-  const actualPixels = bufferLength / 4;
-  if (!Number.isFinite(actualPixels) || actualPixels <= 0) return null;
-
-  const aspectRatio = size.width / size.height;
-  if (!Number.isFinite(aspectRatio) || aspectRatio <= 0) return null;
-
-  const approxWidth = Math.max(
-    1,
-    Math.round(Math.sqrt(actualPixels * aspectRatio)),
-  );
-  let width = approxWidth;
-  let height = Math.max(1, Math.round(actualPixels / width));
-
-  if (width * height * 4 !== bufferLength) {
-    const heightFloor = Math.max(1, Math.floor(actualPixels / width));
-    if (width * heightFloor * 4 === bufferLength) {
-      height = heightFloor;
-    } else {
-      const widthFloor = Math.max(
-        1,
-        Math.floor(Math.sqrt(actualPixels * aspectRatio)),
-      );
-      const heightAlt = Math.max(1, Math.floor(actualPixels / widthFloor));
-      if (widthFloor * heightAlt * 4 === bufferLength) {
-        width = widthFloor;
-        height = heightAlt;
-      } else {
-        return null;
-      }
-    }
-  }
-
-  return { width, height };
-}
-
-function retainFrame(buffer, image) {
-  state.frameRetention.push({ buffer, image });
-  if (state.frameRetention.length > MAX_FRAME_RETENTION) {
-    state.frameRetention.shift();
-  }
-}
-
 function notifyCaptureFrame(info) {
   if (captureFrameListeners.size === 0) return;
   for (const listener of captureFrameListeners) {
@@ -103,46 +61,38 @@ function updateOverlayFromImage(image) {
     }
   }
 
-  const expectedSize = width * height * 4;
-  if (bgraBuffer.length !== expectedSize) {
-    const derived = deriveSizeFromBuffer(size, bgraBuffer.length);
-    if (!derived) {
-      const now = Date.now();
-      if (now - state.lastSizeMismatchTime > SIZE_MISMATCH_LOG_INTERVAL_MS) {
-        console.warn(
-          `Size mismatch: getSize()=${size.width}x${size.height}, buffer=${bgraBuffer.length} bytes (expected ${expectedSize}), skipping frame`,
-        );
-        state.lastSizeMismatchTime = now;
-      }
-      return false;
-    }
-
-    width = derived.width;
-    height = derived.height;
-
+  // Rows may carry alignment padding, but not a whole other width / 行揃えのパディングは許すが、別の幅は許さない
+  const rowPitch = bgraBuffer.length / height;
+  const rowPadding = rowPitch - width * 4;
+  if (
+    !Number.isInteger(rowPitch) ||
+    rowPadding < 0 ||
+    rowPadding >= MAX_ROW_PADDING_BYTES
+  ) {
     const now = Date.now();
     if (now - state.lastSizeMismatchTime > SIZE_MISMATCH_LOG_INTERVAL_MS) {
       console.warn(
-        `Size mismatch: getSize()=${size.width}x${size.height}, buffer=${bgraBuffer.length} bytes (expected ${expectedSize}), using calculated ${width}x${height}`,
+        `Size mismatch: getSize()=${size.width}x${size.height}, buffer=${bgraBuffer.length} bytes does not hold ${height} rows of ${width} pixels, skipping frame`,
       );
       state.lastSizeMismatchTime = now;
     }
+    return false;
   }
 
-  // Keep references alive in case native side reads asynchronously
-  state.lastFrameBuffer = bgraBuffer;
-  state.lastFrameImage = image;
-  retainFrame(bgraBuffer, image);
-
-  // Update texture directly via D3D11 shared texture / D3D11共有テクスチャ経由で直接テクスチャを更新
-  // Uses GPU memory sharing - no file I/O, minimal flickering / GPUメモリ共有を使用 - ファイルI/Oなし、点滅最小化
-  state.overlayManager.setOverlayTexturesD3D11(
-    state.overlayHandle,
-    state.overlayHandleBack ?? INVALID_OVERLAY_HANDLE,
-    bgraBuffer,
-    width,
-    height,
-  );
+  // Convert and submit on a native worker so capture never blocks the UI thread.
+  // The task holds the buffer; a frame still queued when a newer one arrives is dropped.
+  // ネイティブのワーカーで変換・送信し、キャプチャが UI スレッドを塞がないようにする。
+  // タスクがバッファを保持し、新しいフレームが届いた時点で待機中のフレームは破棄される。
+  state.overlayManager
+    .submitOverlayFrame(
+      state.overlayHandle,
+      state.overlayHandleBack ?? INVALID_OVERLAY_HANDLE,
+      bgraBuffer,
+      width,
+      height,
+      { format: 'bgra8', rowPitch },
+    )
+    .catch((error) => console.error('Overlay upload error:', error));
   notifyCaptureFrame({ width, height, timestamp: Date.now() });
   return true;
 }
@@ -323,8 +273,5 @@ export function stopCapture() {
   state.renderGoneHandler = null;
   state.captureWebContents = null;
   state.captureInProgress = false;
-  state.lastFrameBuffer = null;
-  state.lastFrameImage = null;
-  state.frameRetention.length = 0;
   console.log('Capture stopped');
 }
@@ -17,9 +17,6 @@ export const state = {
   destroyedHandler: null,
   renderGoneHandler: null,
   lastSizeMismatchTime: 0,
-  lastFrameBuffer: null,
-  lastFrameImage: null,
-  frameRetention: [],
   rendererMetrics: {
     cssWidth: 0,
     cssHeight: 0,
@@ -110,6 +110,9 @@ contextBridge.exposeInMainWorld('electronAPI', {
   // Overlay settings
   getOverlaySettings: () => ipcRenderer.invoke('get-overlay-settings'),
   setOverlaySettings: (settings) => ipcRenderer.invoke('set-overlay-settings', settings),
+  getTextureStats: () => ipcRenderer.invoke('get-texture-stats'),
+  saveOverlayFrame: () => ipcRenderer.invoke('save-overlay-frame'),
+  captureOverlayFrames: (count) => ipcRenderer.invoke('capture-overlay-frames', count),
   getSteamVrAutoLaunch: () => ipcRenderer.invoke('get-steamvr-auto-launch'),
   setSteamVrAutoLaunch: (enabled) =>
     ipcRenderer.invoke('set-steamvr-auto-launch', enabled),
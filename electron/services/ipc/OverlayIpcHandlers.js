@@ -1,8 +1,22 @@
-import { ipcMain } from 'electron';
-import { resetOverlayPosition, updateRendererMetrics } from '../../overlay.js';
+import { app, ipcMain } from 'electron';
+import fs from 'fs';
+import path from 'path';
+import {
+  captureNextFrames,
+  getTextureStats,
+  saveLastFrame,
+  resetOverlayPosition,
+  updateRendererMetrics,
+} from '../../overlay.js';
 import { updateWindowSize } from '../../input_handler.js';
 import { getOverlaySettings, setOverlaySettings } from '../WindowManager.js';
 
+// Frame snapshots for bug reports go under userData / 不具合報告用のフレームのスナップショットは userData 以下に保存
+function snapshotPath(name) {
+  const stamp = new Date().toISOString().replace(/[:.]/g, '-');
+  return path.join(app.getPath('userData'), 'frame-snapshots', `${name}-${stamp}`);
+}
+
 /**
  * Register Overlay related IPC handlers / オーバーレイ関連のIPCハンドラを登録
  */
@@ -54,4 +68,40 @@ export function registerOverlayIpcHandlers() {
     setOverlaySettings(settings);
     return { success: true, settings: getOverlaySettings() };
   });
+
+  // Texture pipeline statistics for the debug panel / デバッグパネル用のテクスチャパイプライン統計
+  ipcMain.handle('get-texture-stats', () => {
+    try {
+      return { success: true, stats: getTextureStats() };
+    } catch (error) {
+      return { success: false, error: error.message };
+    }
+  });
+
+  // Save the last submitted overlay frame as a PNG / 直近に送信したオーバーレイのフレームを PNG で保存
+  ipcMain.handle('save-overlay-frame', () => {
+    try {
+      const filePath = `${snapshotPath('frame')}.png`;
+      fs.mkdirSync(path.dirname(filePath), { recursive: true });
+      if (!saveLastFrame(filePath)) {
+        return { success: false, error: 'Overlay is not initialized' };
+      }
+      return { success: true, path: filePath };
+    } catch (error) {
+      return { success: false, error: error.message };
+    }
+  });
+
+  // Save the next submitted frames as PNGs / 次に送信するフレームを PNG で保存
+  ipcMain.handle('capture-overlay-frames', (event, count) => {
+    try {
+      const dir = snapshotPath('frames');
+      if (!captureNextFrames(count, dir)) {
+        return { success: false, error: 'Overlay is not initialized' };
+      }
+      return { success: true, path: dir };
+    } catch (error) {
+      return { success: false, error: error.message };
+    }
+  });
 }
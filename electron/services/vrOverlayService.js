@@ -91,6 +91,9 @@ export function getCurrentBindings() {
       toggleOverlay: [],
       triggerBindings: [],
       gripBindings: [],
+      toggleOverlayOrigins: [],
+      triggerOrigins: [],
+      gripOrigins: [],
       triggerBound: false,
       gripBound: false,
     };
@@ -103,6 +106,9 @@ export function getCurrentBindings() {
       toggleOverlay: [],
       triggerBindings: [],
       gripBindings: [],
+      toggleOverlayOrigins: [],
+      triggerOrigins: [],
+      gripOrigins: [],
       triggerBound: false,
       gripBound: false,
     };
@@ -54,9 +54,20 @@ describe('vrOverlayService', () => {
     const service = await import('./vrOverlayService.js');
     manager.getCurrentBindings.mockReturnValue({
       initialized: true,
-      toggleOverlay: ['Right Hand A Button'],
-      triggerBindings: ['Right Trigger Click'],
-      gripBindings: ['Right Grip Click'],
+      toggleOverlay: [
+        {
+          devicePath: '/user/hand/right',
+          inputPath: '/input/a',
+          mode: 'button',
+          slot: 'click',
+          sourceType: 'button',
+        },
+      ],
+      triggerBindings: [],
+      gripBindings: [],
+      toggleOverlayOrigins: ['Right Index Controller A Button'],
+      triggerOrigins: ['Right Index Controller Trigger'],
+      gripOrigins: [],
       triggerBound: true,
       gripBound: true,
     });
@@ -65,9 +76,11 @@ describe('vrOverlayService', () => {
     const result = service.getCurrentBindings();
 
     expect(result.initialized).toBe(true);
-    expect(result.toggleOverlay).toEqual(['Right Hand A Button']);
-    expect(result.triggerBindings).toEqual(['Right Trigger Click']);
-    expect(result.gripBindings).toEqual(['Right Grip Click']);
+    expect(result.toggleOverlay[0].inputPath).toBe('/input/a');
+    expect(result.toggleOverlayOrigins).toEqual([
+      'Right Index Controller A Button',
+    ]);
+    expect(result.triggerOrigins).toEqual(['Right Index Controller Trigger']);
     expect(result.triggerBound).toBe(true);
     expect(result.gripBound).toBe(true);
   });
@@ -97,6 +110,9 @@ describe('vrOverlayService', () => {
       toggleOverlay: [],
       triggerBindings: [],
       gripBindings: [],
+      toggleOverlayOrigins: [],
+      triggerOrigins: [],
+      gripOrigins: [],
       triggerBound: false,
       gripBound: false,
     });
@@ -10,6 +10,28 @@ export declare class OverlayManager {
   getControllerIds(): Array<number>
   getControllerPose(index: number): Array<number>
   getControllerState(controllerIndex: number): ControllerState
+  getAnalogActionState(controllerIndex: number): AnalogActionState
+  /**
+   * Pointer ray from the SteamVR Input pose action for the controller's hand.
+   * Without `predicted_seconds_from_now` the pose is predicted for the next frame.
+   * While the input thread runs this is the ray from its latest tick and the prediction is ignored.
+   * コントローラーの手に対応する SteamVR Input のポーズアクションからレイを取得する。
+   * `predicted_seconds_from_now` 未指定時は次フレーム向けの予測姿勢を使用。
+   * 入力スレッドの実行中は直近の周期のレイを返し、予測時間は無視する。
+   */
+  getPointerRay(controllerIndex: number, predictedSecondsFromNow?: number | undefined | null): PointerRay | null
+  /**
+   * Index fingertip position ([x, y, z] in standing space) for "left" or "right".
+   * "left" / "right" の人差し指先端位置 (スタンディング空間の [x, y, z])
+   */
+  getIndexFingertip(hand: string): Array<number> | null
+  /**
+   * Detect fingertips crossing the overlay plane. Call once per input poll,
+   * after poll_toggle_clicked has updated the action state.
+   * 指先がオーバーレイ平面を横切ったかを検出する。入力ポーリング毎に
+   * poll_toggle_clicked でアクション状態を更新した後に呼び出すこと。
+   */
+  pollFingerPoke(handle: number): Array<PokeEvent>
   initInput(manifestAbsPath: string): void
   pollToggleClicked(): boolean
   openBindingUi(appKey: string, showOnDesktop: boolean): void
@@ -21,15 +43,335 @@ export declare class OverlayManager {
   toggleOverlay(handle: number): void
   setOverlayWidth(handle: number, widthMeters: number): void
   setOverlayTextureBounds(handle: number, uMin: number, vMin: number, uMax: number, vMax: number): void
+  /**
+   * Show the `[uMin, vMin]`-`[uMax, vMax]` region of the texture submitted to `atlasHandle`
+   * on `handle`. Frames set with setOverlayTexturesD3D11 or submitOverlayFrame for
+   * `atlasHandle` are then set on `handle` too, so one capture can drive several panels.
+   * `atlasHandle` に送信したテクスチャの `[uMin, vMin]`-`[uMax, vMax]` の領域を `handle` に表示する。
+   * 以降 setOverlayTexturesD3D11 や submitOverlayFrame で `atlasHandle` に設定したフレームは
+   * `handle` にも設定され、1 つのキャプチャで複数のパネルを描ける。
+   */
+  registerAtlasOverlay(atlasHandle: number, handle: number, uMin: number, vMin: number, uMax: number, vMax: number): void
+  /**
+   * Stop `handle` following its atlas and reset its texture bounds to the whole texture.
+   * Returns whether it was registered.
+   * `handle` をアトラスから外し、テクスチャ範囲をテクスチャ全体に戻す。登録されていたかを返す。
+   */
+  unregisterAtlasOverlay(handle: number): boolean
   computeOverlayIntersection(handle: number, source: Array<number>, direction: Array<number>): IntersectionResult | null
   setOverlayFromFile(handle: number, filePath: string): void
-  setOverlayRaw(handle: number, buffer: Buffer, width: number, height: number): void
-  setOverlayTexturesD3D11(frontHandle: number, backHandle: number, buffer: Buffer, width: number, height: number): void
+  /**
+   * Decode a PNG, JPEG, WebP or SVG file in Rust and set it on `handle` with SetOverlayRaw.
+   * The image is resized to `options.width` x `options.height` with a Lanczos filter (SVGs
+   * are rendered at that size), keeping the aspect ratio when only one is given. Reading,
+   * decoding and resizing run on a worker thread; a frame set on `handle` before the
+   * promise settles wins over the image. Unlike setOverlayFromFile, failures name the file
+   * and what went wrong.
+   * PNG・JPEG・WebP・SVG ファイルを Rust でデコードし、SetOverlayRaw で `handle` に設定する。
+   * 画像は Lanczos フィルタで `options.width` x `options.height` にリサイズし (SVG はそのサイズで描画)、
+   * 片方のみの指定なら縦横比を保つ。読み込み・デコード・リサイズはワーカースレッドで行い、
+   * Promise の確定前に `handle` に設定されたフレームが画像より優先される。
+   * setOverlayFromFile と異なり、失敗時はファイルと原因を示す。
+   */
+  setOverlayFromImage(handle: number, filePath: string, options?: ImageOptions | undefined | null): Promise<ImageInfo>
+  /**
+   * Upload pixels with SetOverlayRaw. `descriptor` describes the buffer (format, row pitch,
+   * premultiplied alpha) and the rectangle to upload; formats other than packed RGBA8 are
+   * converted on the CPU.
+   * SetOverlayRaw でピクセルをアップロードする。`descriptor` はバッファ (形式、行ピッチ、
+   * 乗算済みアルファ) とアップロードする矩形を表す。詰めた RGBA8 以外は CPU で変換する。
+   */
+  setOverlayRaw(handle: number, buffer: Buffer, width: number, height: number, descriptor?: TextureDescriptor | undefined | null): void
+  /**
+   * Upload a frame to the front (and back) overlay through the D3D11 conversion pass,
+   * or on the CPU when D3D11 is unavailable. `descriptor` is as for setOverlayRaw, except
+   * that the format defaults to BGRA8. Only tiles that changed since the previous frame
+   * are uploaded, and nothing is submitted when the frame is unchanged. Overlays registered
+   * with registerAtlasOverlay on the front overlay get the same texture.
+   * フレームを D3D11 の変換パス (D3D11 が使えない場合は CPU) で前面 (と背面) の
+   * オーバーレイにアップロードする。`descriptor` は setOverlayRaw と同じだが、形式の既定は BGRA8。
+   * 前フレームから変わったタイルだけをアップロードし、変化が無ければ何も送信しない。
+   * registerAtlasOverlay で前面オーバーレイに登録したオーバーレイにも同じテクスチャを設定する。
+   */
+  setOverlayTexturesD3D11(frontHandle: number, backHandle: number, buffer: Buffer, width: number, height: number, descriptor?: TextureDescriptor | undefined | null): TextureUpdate
+  /**
+   * Queue a frame for setOverlayTexturesD3D11's conversion and submission on a worker
+   * thread. A frame still waiting when a newer one for the same front overlay is queued
+   * is dropped; the promise reports which happened and the running totals.
+   * The buffer must not be modified until the promise settles.
+   * setOverlayTexturesD3D11 と同じ変換と送信をワーカースレッドで行うようにフレームを積む。
+   * 同じ前面オーバーレイに新しいフレームが積まれた時点で待機中のフレームは破棄する。
+   * Promise はどちらになったかと累計を返す。Promise が確定するまでバッファを変更しないこと。
+   */
+  submitOverlayFrame(frontHandle: number, backHandle: number, buffer: Buffer, width: number, height: number, descriptor?: TextureDescriptor | undefined | null): Promise<FrameSubmission>
+  /**
+   * Update how textures are submitted; omitted fields keep their value. `colorSpace` only
+   * applies to the D3D11 path, as SetOverlayRaw takes no color space. With `srgb`, the D3D11
+   * path uses `_SRGB` formats and both paths apply alpha in linear light. `outputTextures`
+   * sets how many output textures the D3D11 path rotates through. Each front overlay keeps
+   * showing its last one, and a texture whose previous pass is still running on the GPU is
+   * skipped; a frame finding none ready is dropped and counted in `busyFrames`.
+   * テクスチャの送信方法を更新 (省略した項目は現在値を維持)。SetOverlayRaw は色空間を受け取らないため、
+   * `colorSpace` は D3D11 パスにのみ適用される。`srgb` では D3D11 パスが `_SRGB` 形式を使い、
+   * 両パスとも線形光でアルファを適用する。`outputTextures` は D3D11 パスが順に使う出力テクスチャの枚数。
+   * 各前面オーバーレイは最後の出力を表示し続け、前回のパスが GPU で実行中の出力は飛ばす。
+   * 使える出力が無いフレームは破棄し、`busyFrames` に数える。
+   */
+  configureTextureOutput(settings: TextureOutputSettings): void
+  /**
+   * Rolling timings (over the last 240 frames) and counters of texture submission, for
+   * diagnosing CPU and GPU cost.
+   * テクスチャ送信の直近 240 フレームの所要時間とカウンター (CPU・GPU 負荷の診断用)
+   */
+  getTextureStats(): TextureStats
+  /**
+   * Write the most recent frame submitted by setOverlayRaw, setOverlayTexturesD3D11 or
+   * submitOverlayFrame to `path` as a PNG, after the conversion applied for upload. On the
+   * D3D11 path the output texture is read back from the GPU, so the file shows exactly
+   * what SteamVR was given, channel order included.
+   * setOverlayRaw、setOverlayTexturesD3D11、submitOverlayFrame で直近に送信したフレームを、
+   * アップロード用の変換後の状態で PNG として `path` に書き出す。D3D11 パスでは出力テクスチャを
+   * GPU から読み戻すため、チャネル順も含めて SteamVR に渡した内容そのものになる。
+   */
+  saveLastFrame(path: string): void
+  /**
+   * Write each of the next `count` submitted frames to `dir` as it is submitted, named
+   * `frame-0000.png`, `frame-0001.png` and so on. Frames skipped as unchanged are not
+   * written. Replaces a capture that is still running. Each frame is copied as it is
+   * submitted and written by a background thread, so files may appear after the call
+   * that submitted them returns.
+   * 次に送信する `count` 枚のフレームを、送信のたびに `frame-0000.png`、`frame-0001.png` …
+   * として `dir` に書き出す。変化が無く省略したフレームは書き出さない。実行中のキャプチャは
+   * 置き換える。各フレームは送信時にコピーしてバックグラウンドのスレッドで書き出すため、
+   * ファイルは送信した呼び出しが戻った後に現れることがある。
+   */
+  captureNextFrames(count: number, dir: string): void
   setOverlayTransformHmd(handle: number, distance: number): void
   getOverlayTransformAbsolute(handle: number): Array<number>
   setOverlayTransformAbsolute(handle: number, matrix: Array<number>): void
   getOverlayTransformType(handle: number): number
   getOverlayTransformRelative(handle: number): OverlayRelativeTransform
+  triggerHapticVibration(controllerIndex: number, durationSeconds: number, frequency: number, amplitude: number): void
+  /**
+   * Play a named pattern: click, hoverEnter, errorBuzz or longPressConfirm.
+   * Without the vibration action, later pulses of a pattern play from the next
+   * pollToggleClicked or pollTriggerEvents, or from the input thread while it runs.
+   * 名前付きパターンを再生: click, hoverEnter, errorBuzz, longPressConfirm
+   * 振動アクションが無い場合、パターンの後続のパルスは次の pollToggleClicked か
+   * pollTriggerEvents で (入力スレッドの実行中はスレッドで) 再生する。
+   */
+  playHapticPattern(controllerIndex: number, pattern: string, options?: HapticOptions | undefined | null): void
+  /**
+   * Update poke thresholds and the controller tip offset; omitted fields keep their value.
+   * ポークの閾値とコントローラー先端オフセットを更新 (省略した項目は現在値を維持)
+   */
+  configurePoke(settings: PokeSettings): void
+  /**
+   * "Drumstick" typing: detect controller tips crossing the overlay plane.
+   * Controllers that disappear release any press they were holding.
+   * ドラムスティック入力: コントローラー先端がオーバーレイ平面を横切ったかを検出する。
+   * 消えたコントローラーは押下中であれば解放される。
+   */
+  pollControllerPoke(handle: number): Array<PokeEvent>
+  /**
+   * Update 1€ pointer filter parameters; omitted fields keep their value.
+   * ポインター用 1€ フィルタのパラメータを更新 (省略した項目は現在値を維持)
+   */
+  configurePointerSmoothing(settings: SmoothingSettings): void
+  /**
+   * Intersect the controller ray with the overlay and return raw and smoothed hits.
+   * The ray comes from the pointer pose action, or the controller's pose when it is unbound.
+   * コントローラーのレイとオーバーレイの交点を、生の値と平滑化済みの値で返す。
+   * レイはポーズアクションから取得し、未バインド時はコントローラーの姿勢を使う。
+   */
+  computeControllerIntersection(handle: number, controllerIndex: number): ControllerIntersection | null
+  /**
+   * Update trigger thresholds and timings; omitted fields keep their value.
+   * トリガーの閾値と時間を更新 (省略した項目は現在値を維持)
+   */
+  configureTrigger(settings: TriggerSettings): void
+  /**
+   * Sample every controller's trigger and return queued edge events.
+   * Controllers that disappeared are released. Edges also start and finish swipe trails.
+   * 全コントローラーのトリガーをサンプリングし、溜まったエッジイベントを返す。
+   * 消えたコントローラーは解放される。エッジはスワイプの軌跡の開始・終了にも使われる。
+   * While the input thread runs they are delivered as "trigger" events and this returns nothing.
+   * 入力スレッドの実行中は "trigger" イベントとして届き、この関数は何も返さない。
+   */
+  pollTriggerEvents(): Array<TriggerEvent>
+  /**
+   * Poll input on a native thread and deliver toggle, trigger, hit, device, gaze, radial and swipe events to `callback`.
+   * Call after init_input. Settings configured while it runs apply from its next tick.
+   * While running, the poll methods it replaces return nothing, queries that read controllers
+   * answer from the thread's latest tick, SteamVR Input calls run on the thread, and haptics
+   * are played by the thread.
+   * Events wait in a bounded queue: while JS is busy each controller has at most one hit
+   * queued, and other events are dropped once the queue is full.
+   * ネイティブスレッドで入力をポーリングし、トグル・トリガー・ヒット・デバイス・視線・放射状入力・スワイプのイベントを `callback` に渡す。
+   * init_input の後に呼ぶこと。実行中に変更した設定は次の周期から反映される。
+   * 実行中は、置き換えられる poll 系メソッドは何も返さず、コントローラーを読む問い合わせは
+   * スレッドの直近の周期の値で答え、SteamVR Input の呼び出しはスレッド上で実行し、振動はスレッドが再生する。
+   * イベントは上限付きのキューで待つ。JS が忙しい間はコントローラーごとに最大 1 件のヒットだけが
+   * キューに入り、キューが満杯になると他のイベントは破棄される。
+   */
+  startInputThread(callback: ((arg: InputThreadEvent) => void), options?: InputThreadOptions | undefined | null): void
+  /**
+   * Stop and join the input thread; no-op if it is not running.
+   * 入力スレッドを停止して join する (未実行なら何もしない)
+   */
+  stopInputThread(): void
+  /**
+   * Change the overlay the input thread hit-tests; null stops hit events.
+   * 入力スレッドが交差判定するオーバーレイを変更 (null でヒット通知を停止)
+   */
+  setInputThreadOverlay(handle?: number | undefined | null): void
+  isInputThreadRunning(): boolean
+  /**
+   * Update toggle gesture mode and timings; omitted fields keep their value.
+   * トグルのジェスチャーモードと時間を更新 (省略した項目は現在値を維持)
+   */
+  configureToggleGestures(settings: ToggleGestureSettings): void
+  /**
+   * Update SteamVR Input and return toggle gestures recognized since the last poll.
+   * Use instead of poll_toggle_clicked, not alongside it.
+   * SteamVR Input を更新し、前回のポーリング以降に認識したトグルジェスチャーを返す。
+   * poll_toggle_clicked の代わりに使い、併用しないこと。
+   */
+  pollToggleGestures(): Array<ToggleGestureEvent>
+  /**
+   * Generate actions.json and bindings/<controller>.json into `dir`.
+   * Fails without writing anything if the generated manifest does not validate.
+   * `dir` に actions.json と bindings/<controller>.json を生成する。
+   * 生成結果が検証に通らない場合は何も書き込まずに失敗する。
+   */
+  writeActionManifest(dir: string): ActionManifestFiles
+  /**
+   * Check an actions.json and its binding files; does not require SteamVR.
+   * actions.json とバインディングファイルを検証する (SteamVR 不要)
+   */
+  validateActionManifest(manifestPath: string): Array<ActionManifestIssue>
+  /**
+   * Connection, role and battery changes since the last call.
+   * While the input thread runs they are delivered as "device" events instead.
+   * Only the latest 64 are kept between calls.
+   * 前回の呼び出し以降の接続・ロール・バッテリーの変化。
+   * 入力スレッドの実行中は代わりに "device" イベントとして届く。
+   * 呼び出しの間に保持するのは直近の 64 件まで。
+   */
+  pollDeviceEvents(): Array<DeviceEvent>
+  /** Currently connected tracked devices / 現在接続中のトラッキングデバイス */
+  getTrackedDevices(): Array<TrackedDeviceInfo>
+  /**
+   * Update gaze pointer settings; omitted fields keep their value.
+   * 視線ポインターの設定を更新 (省略した項目は現在値を維持)
+   */
+  configureGaze(settings: GazeSettings): void
+  /**
+   * Replace the dwell regions (keys) of an overlay, in UV space; the first match wins.
+   * オーバーレイの注視領域 (キー) を UV 空間で置き換える (先に一致したものを優先)
+   */
+  setGazeRegions(handle: number, regions: Array<GazeRegion>): void
+  /**
+   * Cast the head ray at the overlay and advance the dwell timer.
+   * Completed dwells are returned as trigger clicks on the HMD index (0).
+   * 頭部のレイをオーバーレイに当てて注視タイマーを進める。
+   * 注視が完了すると HMD の番号 (0) のトリガークリックとして返す。
+   * While the input thread runs it reports "gaze" events and this returns an empty update.
+   * 入力スレッドの実行中は "gaze" イベントとして届き、この関数は空の更新を返す。
+   */
+  pollGaze(handle: number): GazeUpdate
+  /**
+   * Update radial typing deadzones; omitted fields keep their value.
+   * 放射状入力のデッドゾーンを更新 (省略した項目は現在値を維持)
+   */
+  configureRadialTyping(settings: RadialTypingSettings): void
+  /**
+   * Register the sector layout of a language, replacing any previous one.
+   * The first registered layout becomes active.
+   * 言語のセクター配置を登録する (既存のものは置き換え)。最初に登録したものが有効になる。
+   */
+  setRadialLayout(layout: RadialTypingLayout): void
+  /**
+   * Switch to a registered layout; current highlights are cleared.
+   * 登録済みの配置に切り替える (現在のハイライトは消える)
+   */
+  selectRadialLayout(language: string): void
+  /**
+   * Sample every controller's joystick and return highlight and commit events.
+   * While the input thread runs they are delivered as "radial" events instead.
+   * 全コントローラーのスティックをサンプリングし、ハイライトと確定のイベントを返す。
+   * 入力スレッドの実行中は代わりに "radial" イベントとして届く。
+   */
+  pollRadialEvents(): Array<RadialTypingEvent>
+  /**
+   * Update swipe typing settings; omitted fields keep their value.
+   * スワイプ入力の設定を更新 (省略した項目は現在値を維持)
+   */
+  configureSwipe(settings: SwipeSettings): void
+  /**
+   * Set the keys of a keyboard overlay in UV space; an empty list stops swiping on it.
+   * Only keys with a single-character label are used for decoding.
+   * キーボードオーバーレイのキーを UV 空間で設定する (空のリストでスワイプを無効化)。
+   * 復号には 1 文字のラベルのキーだけを使う。
+   */
+  setSwipeLayout(handle: number, keys: Array<SwipeKey>): void
+  /**
+   * Load the word list from a plain-text file of `word [frequency]` lines.
+   * Returns the number of distinct words.
+   * `単語 [頻度]` の行からなるテキストファイルから単語リストを読み込む。
+   * 異なる単語の数を返す。
+   */
+  loadSwipeLexicon(path: string): number
+  /**
+   * Swipes finished since the last call, with ranked candidate words.
+   * Trails shorter than one key pitch are taps and are not reported.
+   * Trails are fed by compute_controller_intersection and poll_trigger_events;
+   * while the input thread runs they are delivered as "swipe" events instead.
+   * 前回の呼び出し以降に終了したスワイプと、順位付けした候補の単語。
+   * キー 1 つ分の間隔より短い軌跡はタップとみなし、報告しない。
+   * 軌跡は compute_controller_intersection と poll_trigger_events から入力され、
+   * 入力スレッドの実行中は代わりに "swipe" イベントとして届く。
+   */
+  pollSwipeResults(): Array<SwipeResult>
+}
+
+export interface ActionManifestFiles {
+  actionsPath: string
+  bindingPaths: Array<string>
+}
+
+export interface ActionManifestIssue {
+  severity: string
+  message: string
+}
+
+export interface AnalogActionData {
+  active: boolean
+  x: number
+  y: number
+  deltaX: number
+  deltaY: number
+  originDeviceIndex?: number
+}
+
+export interface AnalogActionState {
+  trigger: AnalogActionData
+  joystick: AnalogActionData
+}
+
+export interface BindingInfo {
+  devicePath: string
+  inputPath: string
+  mode: string
+  slot: string
+  sourceType: string
+}
+
+export interface ControllerIntersection {
+  raw: IntersectionResult
+  smoothed: IntersectionResult
+  /** Pose snapshot time in seconds / ポーズスナップショットの時刻 (秒) */
+  timestamp: number
 }
 
 export interface ControllerState {
@@ -46,13 +388,105 @@ export interface ControllerState {
 
 export interface CurrentBindings {
   initialized: boolean
-  toggleOverlay: Array<string>
-  triggerBindings: Array<string>
-  gripBindings: Array<string>
+  toggleOverlay: Array<BindingInfo>
+  triggerBindings: Array<BindingInfo>
+  gripBindings: Array<BindingInfo>
+  toggleOverlayOrigins: Array<string>
+  triggerOrigins: Array<string>
+  gripOrigins: Array<string>
   triggerBound: boolean
   gripBound: boolean
 }
 
+export interface DeviceEvent {
+  kind: string
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+  index: number
+  previousIndex?: number
+  device: TrackedDeviceInfo
+}
+
+export interface DurationStats {
+  samples: number
+  p50: number
+  p95: number
+  p99: number
+  max: number
+}
+
+export interface FrameSubmission {
+  dropped: boolean
+  submitted: boolean
+  dirtyRatio: number
+  dirtyRects: number
+  submittedFrames: number
+  droppedFrames: number
+}
+
+export interface GazeRegion {
+  id: string
+  u: number
+  v: number
+  width: number
+  height: number
+  dwellSeconds?: number
+}
+
+export interface GazeSettings {
+  enabled?: boolean
+  dwellSeconds?: number
+  exitGraceSeconds?: number
+}
+
+export interface GazeUpdate {
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+  hit?: IntersectionResult
+  regionId?: string
+  progress: number
+  clicks: Array<TriggerEvent>
+}
+
+export interface HapticOptions {
+  amplitude?: number
+  frequency?: number
+}
+
+export interface ImageInfo {
+  format: string
+  sourceWidth: number
+  sourceHeight: number
+  width: number
+  height: number
+}
+
+export interface ImageOptions {
+  width?: number
+  height?: number
+  alpha?: string
+}
+
+export interface InputThreadEvent {
+  kind: string
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+  controllerIndex?: number
+  trigger?: TriggerEvent
+  gesture?: ToggleGestureEvent
+  hit?: IntersectionResult
+  controllerIds?: Array<number>
+  device?: DeviceEvent
+  gaze?: GazeUpdate
+  radial?: RadialTypingEvent
+  swipe?: SwipeResult
+}
+
+export interface InputThreadOptions {
+  rateHz?: number
+  overlayHandle?: number
+}
+
 export interface IntersectionResult {
   x: number
   y: number
@@ -66,3 +500,176 @@ export interface OverlayRelativeTransform {
   trackedDeviceIndex: number
   transform: Array<number>
 }
+
+export interface PixelRect {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+
+export interface PointerRay {
+  origin: Array<number>
+  direction: Array<number>
+  transform: Array<number>
+  originDeviceIndex?: number
+}
+
+export interface PokeEvent {
+  kind: string
+  source: string
+  hand?: string
+  controllerIndex?: number
+  u: number
+  v: number
+  depth: number
+  x: number
+  y: number
+  z: number
+}
+
+export interface PokeSettings {
+  tipOffset?: Array<number>
+  pressDepth?: number
+  releaseDepth?: number
+  hoverDistance?: number
+  maxBehindDistance?: number
+  minPressSpeed?: number
+  maxLateralRatio?: number
+}
+
+export interface RadialTypingEvent {
+  kind: string
+  controllerIndex: number
+  hand: string
+  sector?: number
+  character?: string
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+}
+
+export interface RadialTypingLayout {
+  language: string
+  /** Sector characters clockwise from straight up / 真上から時計回りのセクターの文字 */
+  left: Array<string>
+  right: Array<string>
+}
+
+export interface RadialTypingSettings {
+  enabled?: boolean
+  deadzone?: number
+  releaseDeadzone?: number
+  hysteresisDegrees?: number
+}
+
+export interface SmoothingSettings {
+  enabled?: boolean
+  minCutoff?: number
+  beta?: number
+  derivativeCutoff?: number
+}
+
+export interface SwipeKey {
+  label: string
+  u: number
+  v: number
+  width: number
+  height: number
+}
+
+export interface SwipeResult {
+  controllerIndex: number
+  overlayHandle: number
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  startedAt: number
+  endedAt: number
+  pointCount: number
+  candidates: Array<SwipeWord>
+}
+
+export interface SwipeSettings {
+  enabled?: boolean
+  minSampleDistance?: number
+  tolerance?: number
+  maxCandidates?: number
+}
+
+export interface SwipeWord {
+  word: string
+  score: number
+}
+
+export interface TextureDescriptor {
+  format?: string
+  rowPitch?: number
+  sourceRect?: PixelRect
+  premultiplied?: boolean
+  alpha?: string
+}
+
+export interface TextureOutputSettings {
+  colorSpace?: string
+  srgb?: boolean
+  outputTextures?: number
+}
+
+export interface TextureStats {
+  d3d11: boolean
+  swapRedBlue?: boolean
+  outputTextures: number
+  frame: DurationStats
+  upload: DurationStats
+  convert: DurationStats
+  submit: DurationStats
+  bytesUploaded: number
+  submittedFrames: number
+  skippedFrames: number
+  busyFrames: number
+  droppedFrames: number
+  resourceRecreations: number
+}
+
+export interface TextureUpdate {
+  submitted: boolean
+  dirtyRatio: number
+  dirtyRects: number
+}
+
+export interface ToggleGestureEvent {
+  kind: string
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+  action?: string
+}
+
+export interface ToggleGestureSettings {
+  mode?: string
+  releaseDebounceSeconds?: number
+  doubleClickSeconds?: number
+  longPressSeconds?: number
+}
+
+export interface TrackedDeviceInfo {
+  index: number
+  deviceClass: string
+  role?: string
+  serial: string
+  battery?: number
+  charging?: boolean
+}
+
+export interface TriggerEvent {
+  kind: string
+  controllerIndex: number
+  /** Seconds on the manager clock / マネージャー時計での秒 */
+  timestamp: number
+  value: number
+}
+
+export interface TriggerSettings {
+  pressThreshold?: number
+  releaseThreshold?: number
+  minHoldSeconds?: number
+  longPressSeconds?: number
+  doubleClickSeconds?: number
+}
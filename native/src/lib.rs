@@ -1,7 +1,45 @@
 mod overlay;
 
+pub use overlay::ActionManifestFiles;
+pub use overlay::ActionManifestIssue;
+pub use overlay::AnalogActionData;
+pub use overlay::AnalogActionState;
+pub use overlay::BindingInfo;
+pub use overlay::ControllerIntersection;
 pub use overlay::ControllerState;
 pub use overlay::CurrentBindings;
+pub use overlay::DeviceEvent;
+pub use overlay::DurationStats;
+pub use overlay::FrameSubmission;
+pub use overlay::GazeRegion;
+pub use overlay::GazeSettings;
+pub use overlay::GazeUpdate;
+pub use overlay::HapticOptions;
+pub use overlay::ImageInfo;
+pub use overlay::ImageOptions;
+pub use overlay::InputThreadEvent;
+pub use overlay::InputThreadOptions;
 pub use overlay::IntersectionResult;
 pub use overlay::OverlayManager;
 pub use overlay::OverlayRelativeTransform;
+pub use overlay::PixelRect;
+pub use overlay::PointerRay;
+pub use overlay::PokeEvent;
+pub use overlay::PokeSettings;
+pub use overlay::RadialTypingEvent;
+pub use overlay::RadialTypingLayout;
+pub use overlay::RadialTypingSettings;
+pub use overlay::SmoothingSettings;
+pub use overlay::SwipeKey;
+pub use overlay::SwipeResult;
+pub use overlay::SwipeSettings;
+pub use overlay::SwipeWord;
+pub use overlay::TextureDescriptor;
+pub use overlay::TextureOutputSettings;
+pub use overlay::TextureStats;
+pub use overlay::TextureUpdate;
+pub use overlay::ToggleGestureEvent;
+pub use overlay::ToggleGestureSettings;
+pub use overlay::TrackedDeviceInfo;
+pub use overlay::TriggerEvent;
+pub use overlay::TriggerSettings;
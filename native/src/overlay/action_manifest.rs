@@ -0,0 +1,729 @@
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+pub(super) const APP_KEY: &str = "VRChat-OSC-Keyboard";
+pub(super) const ACTION_SET_PATH: &str = "/actions/vrkb2";
+pub(super) const TOGGLE_ACTION_PATH: &str = "/actions/vrkb2/in/toggle_overlay";
+pub(super) const TRIGGER_ACTION_PATH: &str = "/actions/vrkb2/in/trigger_click";
+pub(super) const GRIP_ACTION_PATH: &str = "/actions/vrkb2/in/grip_click";
+pub(super) const TRIGGER_VALUE_ACTION_PATH: &str = "/actions/vrkb2/in/trigger_value";
+pub(super) const SCROLL_ACTION_PATH: &str = "/actions/vrkb2/in/scroll";
+pub(super) const POINTER_ACTION_PATH: &str = "/actions/vrkb2/in/pointer";
+pub(super) const SKELETON_LEFT_ACTION_PATH: &str = "/actions/vrkb2/in/skeleton_left";
+pub(super) const SKELETON_RIGHT_ACTION_PATH: &str = "/actions/vrkb2/in/skeleton_right";
+pub(super) const HAPTIC_ACTION_PATH: &str = "/actions/vrkb2/out/haptic";
+pub(super) const LEFT_HAND_PATH: &str = "/user/hand/left";
+pub(super) const RIGHT_HAND_PATH: &str = "/user/hand/right";
+
+pub(super) const ACTIONS_FILE_NAME: &str = "actions.json";
+pub(super) const BINDINGS_DIR_NAME: &str = "bindings";
+const MANIFEST_VERSION: u32 = 2;
+const LANGUAGE_TAG_KEY: &str = "language_tag";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ActionType {
+    Boolean,
+    Vector1,
+    Vector2,
+    Pose,
+    Skeleton,
+    Vibration,
+}
+
+impl ActionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            ActionType::Boolean => "boolean",
+            ActionType::Vector1 => "vector1",
+            ActionType::Vector2 => "vector2",
+            ActionType::Pose => "pose",
+            ActionType::Skeleton => "skeleton",
+            ActionType::Vibration => "vibration",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "boolean" => Some(ActionType::Boolean),
+            "vector1" => Some(ActionType::Vector1),
+            "vector2" => Some(ActionType::Vector2),
+            "pose" => Some(ActionType::Pose),
+            "skeleton" => Some(ActionType::Skeleton),
+            "vibration" => Some(ActionType::Vibration),
+            _ => None,
+        }
+    }
+
+    /// Type a source input slot writes to, if known / 入力スロットが出力する型 (既知の場合)
+    fn for_input_slot(slot: &str) -> Option<Self> {
+        match slot {
+            "click" | "touch" | "double" | "long" | "held" => Some(ActionType::Boolean),
+            "pull" | "value" => Some(ActionType::Vector1),
+            "position" => Some(ActionType::Vector2),
+            _ => None,
+        }
+    }
+}
+
+struct ActionSpec {
+    path: &'static str,
+    action_type: ActionType,
+    skeleton: Option<&'static str>,
+    en_us: &'static str,
+    ja_jp: &'static str,
+}
+
+const ACTION_SET_NAMES: (&str, &str) = ("VRKB", "VRKB");
+
+/// Every action init_input looks up; a manifest missing any of them fails with NameNotFound.
+/// init_input が参照する全アクション。欠けているとマニフェスト読込時に NameNotFound になる
+const ACTIONS: [ActionSpec; 9] = [
+    ActionSpec {
+        path: TOGGLE_ACTION_PATH,
+        action_type: ActionType::Boolean,
+        skeleton: None,
+        en_us: "Toggle Overlay",
+        ja_jp: "オーバーレイ表示切替",
+    },
+    ActionSpec {
+        path: TRIGGER_ACTION_PATH,
+        action_type: ActionType::Boolean,
+        skeleton: None,
+        en_us: "Trigger Click",
+        ja_jp: "トリガークリック",
+    },
+    ActionSpec {
+        path: GRIP_ACTION_PATH,
+        action_type: ActionType::Boolean,
+        skeleton: None,
+        en_us: "Grip Click",
+        ja_jp: "グリップクリック",
+    },
+    ActionSpec {
+        path: TRIGGER_VALUE_ACTION_PATH,
+        action_type: ActionType::Vector1,
+        skeleton: None,
+        en_us: "Trigger Pull",
+        ja_jp: "トリガー引き量",
+    },
+    ActionSpec {
+        path: SCROLL_ACTION_PATH,
+        action_type: ActionType::Vector2,
+        skeleton: None,
+        en_us: "Scroll",
+        ja_jp: "スクロール",
+    },
+    ActionSpec {
+        path: POINTER_ACTION_PATH,
+        action_type: ActionType::Pose,
+        skeleton: None,
+        en_us: "Pointer",
+        ja_jp: "ポインター",
+    },
+    ActionSpec {
+        path: SKELETON_LEFT_ACTION_PATH,
+        action_type: ActionType::Skeleton,
+        skeleton: Some("/skeleton/hand/left"),
+        en_us: "Left Hand Skeleton",
+        ja_jp: "左手スケルトン",
+    },
+    ActionSpec {
+        path: SKELETON_RIGHT_ACTION_PATH,
+        action_type: ActionType::Skeleton,
+        skeleton: Some("/skeleton/hand/right"),
+        en_us: "Right Hand Skeleton",
+        ja_jp: "右手スケルトン",
+    },
+    ActionSpec {
+        path: HAPTIC_ACTION_PATH,
+        action_type: ActionType::Vibration,
+        skeleton: None,
+        en_us: "Haptic Feedback",
+        ja_jp: "振動フィードバック",
+    },
+];
+
+/// Controller-specific parts of the default bindings / 既定バインディングのコントローラー固有部分
+struct ControllerProfile {
+    controller_type: &'static str,
+    description: &'static str,
+    /// Right-hand button that toggles the overlay / オーバーレイ切替に使う右手のボタン
+    toggle_input: &'static str,
+    scroll_input: &'static str,
+    scroll_mode: &'static str,
+}
+
+const CONTROLLERS: [ControllerProfile; 5] = [
+    ControllerProfile {
+        controller_type: "pico_controller",
+        description: "Default binding for Pico controllers",
+        toggle_input: "joystick",
+        scroll_input: "joystick",
+        scroll_mode: "joystick",
+    },
+    ControllerProfile {
+        controller_type: "knuckles",
+        description: "Default binding for Valve Index controllers",
+        toggle_input: "a",
+        scroll_input: "thumbstick",
+        scroll_mode: "joystick",
+    },
+    ControllerProfile {
+        controller_type: "vive_controller",
+        description: "Default binding for HTC Vive controllers",
+        toggle_input: "trackpad",
+        scroll_input: "trackpad",
+        scroll_mode: "trackpad",
+    },
+    ControllerProfile {
+        controller_type: "oculus_touch",
+        description: "Default binding for Oculus Touch controllers",
+        toggle_input: "joystick",
+        scroll_input: "joystick",
+        scroll_mode: "joystick",
+    },
+    ControllerProfile {
+        controller_type: "holographic_controller",
+        description: "Default binding for Windows MR controllers",
+        toggle_input: "menu",
+        scroll_input: "joystick",
+        scroll_mode: "joystick",
+    },
+];
+
+pub(super) struct BindingFile {
+    pub controller_type: String,
+    pub content: Value,
+}
+
+pub(super) struct ActionManifest {
+    pub actions: Value,
+    pub bindings: Vec<BindingFile>,
+}
+
+pub(super) fn binding_url(controller_type: &str) -> String {
+    format!("{BINDINGS_DIR_NAME}/{controller_type}.json")
+}
+
+/// Pretty JSON with a trailing newline, matching the checked-in files
+/// リポジトリ内のファイルと同じく末尾改行付きの整形 JSON
+pub(super) fn to_json_text(value: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(value).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+fn localization(
+    language_tag: &str,
+    set_name: &str,
+    name_of: impl Fn(&ActionSpec) -> &str,
+) -> Value {
+    let mut strings = Map::new();
+    strings.insert(LANGUAGE_TAG_KEY.to_string(), json!(language_tag));
+    if !set_name.is_empty() {
+        strings.insert(ACTION_SET_PATH.to_string(), json!(set_name));
+    }
+    for action in &ACTIONS {
+        // Left out so validation reports the gap / 検証で欠落を報告させるため出力しない
+        let name = name_of(action);
+        if !name.is_empty() {
+            strings.insert(action.path.to_string(), json!(name));
+        }
+    }
+    Value::Object(strings)
+}
+
+fn source(hand: &str, input: &str, mode: &str, slot: &str, output: &str) -> Value {
+    let mut inputs = Map::new();
+    inputs.insert(slot.to_string(), json!({ "output": output }));
+    json!({
+        "path": format!("{hand}/input/{input}"),
+        "mode": mode,
+        "inputs": inputs,
+    })
+}
+
+fn controller_bindings(profile: &ControllerProfile) -> Value {
+    let hands = [RIGHT_HAND_PATH, LEFT_HAND_PATH];
+    let mut sources = vec![source(
+        RIGHT_HAND_PATH,
+        profile.toggle_input,
+        "button",
+        "click",
+        TOGGLE_ACTION_PATH,
+    )];
+    for hand in hands {
+        sources.push(source(
+            hand,
+            "trigger",
+            "button",
+            "click",
+            TRIGGER_ACTION_PATH,
+        ));
+    }
+    for hand in hands {
+        sources.push(source(hand, "grip", "button", "click", GRIP_ACTION_PATH));
+    }
+    for hand in hands {
+        sources.push(source(
+            hand,
+            "trigger",
+            "trigger",
+            "pull",
+            TRIGGER_VALUE_ACTION_PATH,
+        ));
+    }
+    for hand in hands {
+        sources.push(source(
+            hand,
+            profile.scroll_input,
+            profile.scroll_mode,
+            "position",
+            SCROLL_ACTION_PATH,
+        ));
+    }
+
+    let mut set = Map::new();
+    set.insert(
+        ACTION_SET_PATH.to_string(),
+        json!({
+            "sources": sources,
+            "poses": [
+                { "output": POINTER_ACTION_PATH, "path": format!("{LEFT_HAND_PATH}/pose/tip") },
+                { "output": POINTER_ACTION_PATH, "path": format!("{RIGHT_HAND_PATH}/pose/tip") },
+            ],
+            "haptics": [
+                { "output": HAPTIC_ACTION_PATH, "path": format!("{LEFT_HAND_PATH}/output/haptic") },
+                { "output": HAPTIC_ACTION_PATH, "path": format!("{RIGHT_HAND_PATH}/output/haptic") },
+            ],
+            "skeleton": [
+                {
+                    "output": SKELETON_LEFT_ACTION_PATH,
+                    "path": format!("{LEFT_HAND_PATH}/input/skeleton/left"),
+                },
+                {
+                    "output": SKELETON_RIGHT_ACTION_PATH,
+                    "path": format!("{RIGHT_HAND_PATH}/input/skeleton/right"),
+                },
+            ],
+        }),
+    );
+
+    json!({
+        "action_manifest_version": MANIFEST_VERSION,
+        "app_key": APP_KEY,
+        "controller_type": profile.controller_type,
+        "description": profile.description,
+        "bindings": set,
+    })
+}
+
+/// Build actions.json and the default binding files from the action description above.
+/// 上のアクション定義から actions.json と既定バインディングファイルを生成
+pub(super) fn generate_action_manifest() -> ActionManifest {
+    let actions: Vec<Value> = ACTIONS
+        .iter()
+        .map(|action| {
+            let mut entry = Map::new();
+            entry.insert("name".to_string(), json!(action.path));
+            entry.insert("type".to_string(), json!(action.action_type.as_str()));
+            if let Some(skeleton) = action.skeleton {
+                entry.insert("skeleton".to_string(), json!(skeleton));
+            }
+            entry.insert("requirement".to_string(), json!("optional"));
+            Value::Object(entry)
+        })
+        .collect();
+
+    let default_bindings: Vec<Value> = CONTROLLERS
+        .iter()
+        .map(|profile| {
+            json!({
+                "controller_type": profile.controller_type,
+                "binding_url": binding_url(profile.controller_type),
+            })
+        })
+        .collect();
+
+    let manifest = json!({
+        "version": MANIFEST_VERSION,
+        "app_key": APP_KEY,
+        "category": "steamvr_input",
+        "actions": actions,
+        "action_sets": [{ "name": ACTION_SET_PATH, "usage": "single" }],
+        "default_bindings": default_bindings,
+        "localization": [
+            localization("en_US", ACTION_SET_NAMES.0, |action| action.en_us),
+            localization("ja_JP", ACTION_SET_NAMES.1, |action| action.ja_jp),
+        ],
+    });
+
+    ActionManifest {
+        actions: manifest,
+        bindings: CONTROLLERS
+            .iter()
+            .map(|profile| BindingFile {
+                controller_type: profile.controller_type.to_string(),
+                content: controller_bindings(profile),
+            })
+            .collect(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum IssueSeverity {
+    /// SteamVR rejects the manifest or init_input fails / SteamVR が拒否するか init_input が失敗する
+    Error,
+    /// Loads, but shows up wrong in the binding UI / 読み込めるがバインディング UI の表示が崩れる
+    Warning,
+}
+
+impl IssueSeverity {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ManifestIssue {
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Issues(Vec<ManifestIssue>);
+
+impl Issues {
+    fn error(&mut self, message: String) {
+        self.0.push(ManifestIssue {
+            severity: IssueSeverity::Error,
+            message,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.0.push(ManifestIssue {
+            severity: IssueSeverity::Warning,
+            message,
+        });
+    }
+}
+
+fn entries<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn string_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Check actions.json and its binding files for mistakes SteamVR only reports at runtime.
+/// SteamVR が実行時にしか報告しないマニフェストとバインディングの誤りを検出
+pub(super) fn validate_action_manifest(
+    actions: &Value,
+    bindings: &[BindingFile],
+) -> Vec<ManifestIssue> {
+    let mut issues = Issues::default();
+
+    let mut sets = HashSet::new();
+    for set in entries(actions, "action_sets") {
+        match string_field(set, "name") {
+            Some(name) if !sets.insert(name) => {
+                issues.error(format!("Duplicate action set {name}"))
+            }
+            Some(_) => {}
+            None => issues.error("Action set without a name".to_string()),
+        }
+    }
+    if !sets.contains(ACTION_SET_PATH) {
+        issues.error(format!("Action set {ACTION_SET_PATH} is not declared"));
+    }
+
+    let mut declared: Vec<(&str, ActionType)> = Vec::new();
+    for action in entries(actions, "actions") {
+        let Some(name) = string_field(action, "name") else {
+            issues.error("Action without a name".to_string());
+            continue;
+        };
+        if declared.iter().any(|(existing, _)| *existing == name) {
+            issues.error(format!("Duplicate action {name}"));
+            continue;
+        }
+        let Some(action_type) = string_field(action, "type").and_then(ActionType::from_name) else {
+            issues.error(format!("Action {name} has a missing or unknown type"));
+            continue;
+        };
+
+        let direction = sets.iter().find_map(|set| {
+            name.strip_prefix(*set)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .and_then(|rest| rest.split('/').next())
+        });
+        match direction {
+            None => issues.error(format!("Action {name} is not inside a declared action set")),
+            Some("out") if action_type != ActionType::Vibration => {
+                issues.error(format!("Output action {name} must be of type vibration"))
+            }
+            Some("in") if action_type == ActionType::Vibration => {
+                issues.error(format!("Vibration action {name} must be under /out"))
+            }
+            Some("in") | Some("out") => {}
+            Some(_) => issues.error(format!("Action {name} must be under /in or /out")),
+        }
+        if action_type == ActionType::Skeleton && action.get("skeleton").is_none() {
+            issues.error(format!("Skeleton action {name} has no skeleton path"));
+        }
+        declared.push((name, action_type));
+    }
+
+    for path in ACTIONS.iter().map(|action| action.path) {
+        if !declared.iter().any(|(name, _)| *name == path) {
+            issues.error(format!(
+                "Action {path} is used by the native module but not declared"
+            ));
+        }
+    }
+
+    let languages = entries(actions, "localization");
+    if languages.is_empty() {
+        issues.warning("No localization entries".to_string());
+    }
+    for strings in languages {
+        let tag = string_field(strings, LANGUAGE_TAG_KEY).unwrap_or("(no language_tag)");
+        let localized = |path: &str| string_field(strings, path).is_some_and(|s| !s.is_empty());
+        for path in sets
+            .iter()
+            .copied()
+            .chain(declared.iter().map(|(name, _)| *name))
+        {
+            if !localized(path) {
+                issues.warning(format!("Missing {tag} localization for {path}"));
+            }
+        }
+        if let Some(keys) = strings.as_object() {
+            for key in keys.keys() {
+                let known = key == LANGUAGE_TAG_KEY
+                    || sets.contains(key.as_str())
+                    || declared.iter().any(|(name, _)| name == key);
+                if !known {
+                    issues.warning(format!("{tag} localization names unknown path {key}"));
+                }
+            }
+        }
+    }
+
+    let mut controller_types = HashSet::new();
+    for entry in entries(actions, "default_bindings") {
+        match string_field(entry, "controller_type") {
+            Some(controller_type) if !controller_types.insert(controller_type) => {
+                issues.error(format!("Duplicate default binding for {controller_type}"))
+            }
+            Some(_) => {}
+            None => issues.error("Default binding without a controller_type".to_string()),
+        }
+    }
+
+    let check_output = |issues: &mut Issues,
+                        file: &str,
+                        output: &str,
+                        expected: Option<ActionType>| {
+        match declared.iter().find(|(name, _)| *name == output) {
+            None => issues.error(format!("{file} binds unknown action {output}")),
+            Some((_, actual)) => {
+                if let Some(expected) = expected.filter(|expected| expected != actual) {
+                    issues.error(format!(
+                        "{file} binds {output} ({}) to a {} input",
+                        actual.as_str(),
+                        expected.as_str()
+                    ));
+                }
+            }
+        }
+    };
+
+    for binding in bindings {
+        let file = binding.controller_type.as_str();
+        let Some(binding_sets) = binding.content.get("bindings").and_then(Value::as_object) else {
+            issues.error(format!("{file} has no bindings"));
+            continue;
+        };
+        for (set, content) in binding_sets {
+            if !sets.contains(set.as_str()) {
+                issues.error(format!("{file} binds unknown action set {set}"));
+                continue;
+            }
+            for source in entries(content, "sources") {
+                let Some(inputs) = source.get("inputs").and_then(Value::as_object) else {
+                    continue;
+                };
+                for (slot, input) in inputs {
+                    if let Some(output) = string_field(input, "output") {
+                        check_output(&mut issues, file, output, ActionType::for_input_slot(slot));
+                    }
+                }
+            }
+            for (key, action_type) in [
+                ("poses", ActionType::Pose),
+                ("haptics", ActionType::Vibration),
+                ("skeleton", ActionType::Skeleton),
+            ] {
+                for entry in entries(content, key) {
+                    if let Some(output) = string_field(entry, "output") {
+                        check_output(&mut issues, file, output, Some(action_type));
+                    }
+                }
+            }
+        }
+    }
+
+    // Each binding of an unknown action is reported once / 未知アクションの重複報告をまとめる
+    issues.0.dedup_by(|a, b| a.message == b.message);
+    issues.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checked_in_binding(controller_type: &str) -> &'static str {
+        match controller_type {
+            "pico_controller" => include_str!("../../../steamvr/bindings/pico_controller.json"),
+            "knuckles" => include_str!("../../../steamvr/bindings/knuckles.json"),
+            "vive_controller" => include_str!("../../../steamvr/bindings/vive_controller.json"),
+            "oculus_touch" => include_str!("../../../steamvr/bindings/oculus_touch.json"),
+            "holographic_controller" => {
+                include_str!("../../../steamvr/bindings/holographic_controller.json")
+            }
+            other => panic!("no checked-in binding for {other}"),
+        }
+    }
+
+    fn messages(issues: &[ManifestIssue], severity: IssueSeverity) -> Vec<&str> {
+        issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .map(|issue| issue.message.as_str())
+            .collect()
+    }
+
+    fn validate(manifest: &ActionManifest) -> Vec<ManifestIssue> {
+        validate_action_manifest(&manifest.actions, &manifest.bindings)
+    }
+
+    fn actions_mut(manifest: &mut ActionManifest) -> &mut Vec<Value> {
+        manifest.actions["actions"].as_array_mut().unwrap()
+    }
+
+    #[test]
+    fn generated_manifest_matches_checked_in_files() {
+        let manifest = generate_action_manifest();
+        assert_eq!(
+            to_json_text(&manifest.actions),
+            include_str!("../../../steamvr/actions.json"),
+            "steamvr/actions.json is stale; regenerate it with writeActionManifest"
+        );
+        for binding in &manifest.bindings {
+            assert_eq!(
+                to_json_text(&binding.content),
+                checked_in_binding(&binding.controller_type),
+                "steamvr/{} is stale; regenerate it with writeActionManifest",
+                binding_url(&binding.controller_type)
+            );
+        }
+    }
+
+    #[test]
+    fn generated_manifest_has_no_issues() {
+        let issues = validate(&generate_action_manifest());
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn duplicate_actions_sets_and_bindings_are_errors() {
+        let mut manifest = generate_action_manifest();
+        let first = actions_mut(&mut manifest)[0].clone();
+        actions_mut(&mut manifest).push(first);
+        let set = manifest.actions["action_sets"][0].clone();
+        manifest.actions["action_sets"]
+            .as_array_mut()
+            .unwrap()
+            .push(set);
+        let binding = manifest.actions["default_bindings"][0].clone();
+        manifest.actions["default_bindings"]
+            .as_array_mut()
+            .unwrap()
+            .push(binding);
+
+        let issues = validate(&manifest);
+        let errors = messages(&issues, IssueSeverity::Error);
+        assert!(errors.contains(&format!("Duplicate action {TOGGLE_ACTION_PATH}").as_str()));
+        assert!(errors.contains(&format!("Duplicate action set {ACTION_SET_PATH}").as_str()));
+        assert!(errors.contains(&"Duplicate default binding for pico_controller"));
+    }
+
+    #[test]
+    fn binding_of_unknown_action_is_reported_once_per_file() {
+        let mut manifest = generate_action_manifest();
+        actions_mut(&mut manifest).retain(|action| action["name"] != GRIP_ACTION_PATH);
+
+        let issues = validate(&manifest);
+        let errors = messages(&issues, IssueSeverity::Error);
+        let unknown = format!("knuckles binds unknown action {GRIP_ACTION_PATH}");
+        assert_eq!(
+            errors.iter().filter(|message| **message == unknown).count(),
+            1
+        );
+        assert!(errors.contains(
+            &format!("Action {GRIP_ACTION_PATH} is used by the native module but not declared")
+                .as_str()
+        ));
+    }
+
+    #[test]
+    fn binding_to_wrong_input_type_is_an_error() {
+        let mut manifest = generate_action_manifest();
+        manifest.bindings[0].content["bindings"][ACTION_SET_PATH]["sources"][0]["inputs"]
+            ["click"]["output"] = json!(SCROLL_ACTION_PATH);
+
+        let issues = validate(&manifest);
+        assert!(messages(&issues, IssueSeverity::Error).contains(
+            &format!("pico_controller binds {SCROLL_ACTION_PATH} (vector2) to a boolean input")
+                .as_str()
+        ));
+    }
+
+    #[test]
+    fn missing_localization_is_a_warning() {
+        let mut manifest = generate_action_manifest();
+        manifest.actions["localization"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove(POINTER_ACTION_PATH);
+
+        let issues = validate(&manifest);
+        assert!(messages(&issues, IssueSeverity::Error).is_empty());
+        assert_eq!(
+            messages(&issues, IssueSeverity::Warning),
+            vec![format!(
+                "Missing ja_JP localization for {POINTER_ACTION_PATH}"
+            )]
+        );
+
+        manifest
+            .actions
+            .as_object_mut()
+            .unwrap()
+            .remove("localization");
+        let issues = validate(&manifest);
+        assert_eq!(
+            messages(&issues, IssueSeverity::Warning),
+            vec!["No localization entries"]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use napi_derive::napi;
+use serde_json::Value;
+use std::path::Path;
+
+use super::action_manifest::{
+    binding_url, generate_action_manifest, to_json_text, validate_action_manifest, BindingFile,
+    IssueSeverity, ManifestIssue, ACTIONS_FILE_NAME, BINDINGS_DIR_NAME,
+};
+use super::manager::OverlayManager;
+use super::types::{ActionManifestFiles, ActionManifestIssue};
+
+const FILE_URL_PREFIX: &str = "file://";
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn read_error(message: String) -> ManifestIssue {
+    ManifestIssue {
+        severity: IssueSeverity::Error,
+        message,
+    }
+}
+
+/// Validate an actions.json on disk together with the binding files it lists.
+/// ディスク上の actions.json と、そこに記載されたバインディングファイルを検証
+pub(super) fn validate_manifest_file(manifest_path: &Path) -> Vec<ManifestIssue> {
+    let actions = match read_json(manifest_path) {
+        Ok(actions) => actions,
+        Err(message) => return vec![read_error(message)],
+    };
+    let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+
+    let mut issues = Vec::new();
+    let mut bindings = Vec::new();
+    let listed = actions
+        .get("default_bindings")
+        .and_then(Value::as_array)
+        .map_or(&[][..], Vec::as_slice);
+    for entry in listed {
+        let (Some(controller_type), Some(url)) = (
+            entry.get("controller_type").and_then(Value::as_str),
+            entry.get("binding_url").and_then(Value::as_str),
+        ) else {
+            // Reported by validate_action_manifest / validate_action_manifest 側で報告
+            continue;
+        };
+        // Relative URLs resolve against the manifest directory / 相対 URL はマニフェストのディレクトリ基準
+        let path = base_dir.join(url.strip_prefix(FILE_URL_PREFIX).unwrap_or(url));
+        match read_json(&path) {
+            Ok(content) => bindings.push(BindingFile {
+                controller_type: controller_type.to_string(),
+                content,
+            }),
+            Err(message) => issues.push(read_error(message)),
+        }
+    }
+
+    issues.extend(validate_action_manifest(&actions, &bindings));
+    issues
+}
+
+/// Log manifest issues as startup warnings before handing the manifest to SteamVR; loading
+/// goes on so a stale or hand-edited manifest still works as far as SteamVR accepts it.
+/// SteamVR に渡す前にマニフェストの問題を起動時の警告としてログに出す。古いマニフェストや手で
+/// 編集したものも SteamVR が受け付ける範囲で動くよう、読み込みは続行する
+pub(super) fn warn_manifest_issues(manifest_path: &Path) {
+    for issue in validate_manifest_file(manifest_path) {
+        eprintln!(
+            "[vr-overlay-native] action manifest {} ({}): {}",
+            manifest_path.display(),
+            issue.severity.as_str(),
+            issue.message
+        );
+    }
+}
+
+fn write_file(path: &Path, value: &Value) -> napi::Result<String> {
+    std::fs::write(path, to_json_text(value)).map_err(|e| {
+        napi::Error::from_reason(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+fn issue_object(issue: ManifestIssue) -> ActionManifestIssue {
+    ActionManifestIssue {
+        severity: issue.severity.as_str().to_string(),
+        message: issue.message,
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Generate actions.json and bindings/<controller>.json into `dir`.
+    /// Fails without writing anything if the generated manifest does not validate.
+    /// `dir` に actions.json と bindings/<controller>.json を生成する。
+    /// 生成結果が検証に通らない場合は何も書き込まずに失敗する。
+    #[napi]
+    pub fn write_action_manifest(&self, dir: String) -> napi::Result<ActionManifestFiles> {
+        let manifest = generate_action_manifest();
+        let errors: Vec<String> = validate_action_manifest(&manifest.actions, &manifest.bindings)
+            .into_iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .map(|issue| issue.message)
+            .collect();
+        if !errors.is_empty() {
+            return Err(napi::Error::from_reason(format!(
+                "Generated action manifest is invalid: {}",
+                errors.join("; ")
+            )));
+        }
+
+        let dir = Path::new(&dir);
+        std::fs::create_dir_all(dir.join(BINDINGS_DIR_NAME)).map_err(|e| {
+            napi::Error::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+
+        let actions_path = write_file(&dir.join(ACTIONS_FILE_NAME), &manifest.actions)?;
+        let mut binding_paths = Vec::with_capacity(manifest.bindings.len());
+        for binding in &manifest.bindings {
+            binding_paths.push(write_file(
+                &dir.join(binding_url(&binding.controller_type)),
+                &binding.content,
+            )?);
+        }
+
+        Ok(ActionManifestFiles {
+            actionsPath: actions_path,
+            bindingPaths: binding_paths,
+        })
+    }
+
+    /// Check an actions.json and its binding files; does not require SteamVR.
+    /// actions.json とバインディングファイルを検証する (SteamVR 不要)
+    #[napi]
+    pub fn validate_action_manifest(&self, manifest_path: String) -> Vec<ActionManifestIssue> {
+        validate_manifest_file(Path::new(&manifest_path))
+            .into_iter()
+            .map(issue_object)
+            .collect()
+    }
+}
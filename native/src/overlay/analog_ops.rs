@@ -0,0 +1,105 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::input_ops::origin_device_index;
+use super::manager::{InputActionCache, OverlayManager};
+use super::types::{AnalogActionData, AnalogActionState};
+
+fn inactive_analog_action() -> AnalogActionData {
+    AnalogActionData {
+        active: false,
+        x: 0.0,
+        y: 0.0,
+        deltaX: 0.0,
+        deltaY: 0.0,
+        originDeviceIndex: None,
+    }
+}
+
+/// Read a vector1/vector2 action from the first source that reports it active.
+/// 最初にアクティブなソースから vector1/vector2 アクションを読み取る
+pub(super) fn read_analog_action(
+    input: &vr::VR_IVRInput_FnTable,
+    action_handle: vr::VRActionHandle_t,
+    sources: &[vr::VRInputValueHandle_t],
+) -> Option<AnalogActionData> {
+    if action_handle == vr::k_ulInvalidActionHandle {
+        return None;
+    }
+    let get_analog_action_data_fn = input.GetAnalogActionData?;
+
+    for source in sources.iter().copied() {
+        let mut data: vr::InputAnalogActionData_t = unsafe { std::mem::zeroed() };
+        let err = unsafe {
+            get_analog_action_data_fn(
+                action_handle,
+                &mut data,
+                std::mem::size_of::<vr::InputAnalogActionData_t>() as u32,
+                source,
+            )
+        };
+        if err == vr::EVRInputError_VRInputError_None && data.bActive {
+            return Some(AnalogActionData {
+                active: true,
+                x: data.x as f64,
+                y: data.y as f64,
+                deltaX: data.deltaX as f64,
+                deltaY: data.deltaY as f64,
+                originDeviceIndex: origin_device_index(input, data.activeOrigin),
+            });
+        }
+    }
+    None
+}
+
+/// Trigger and joystick actions for a controller's input sources.
+/// コントローラーの入力ソースに対するトリガーとジョイスティックのアクション
+pub(super) fn read_analog_state(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+    sources: &[vr::VRInputValueHandle_t],
+) -> AnalogActionState {
+    let trigger = read_analog_action(input, cache.trigger_value_action_handle, sources)
+        .unwrap_or_else(inactive_analog_action);
+    let joystick = read_analog_action(input, cache.scroll_action_handle, sources)
+        .unwrap_or_else(inactive_analog_action);
+    AnalogActionState { trigger, joystick }
+}
+
+#[napi]
+impl OverlayManager {
+    #[napi]
+    pub fn get_analog_action_state(
+        &self,
+        controller_index: u32,
+    ) -> napi::Result<AnalogActionState> {
+        // Read from the most recent UpdateActionState, like get_controller_state
+        // get_controller_state と同様に最新の UpdateActionState の結果を読み取る
+        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+            return Err(napi::Error::from_reason("Invalid device index"));
+        }
+
+        let input = self.input()?;
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+        }
+
+        // The input thread reads it on each tick while it runs / 入力スレッドの実行中は各周期で読み取ったもの
+        let snapshot = self.read_input_snapshot(|snapshot| {
+            snapshot
+                .controllers
+                .get(&controller_index)
+                .and_then(|controller| controller.analog.clone())
+        })?;
+        if let Some(state) = snapshot {
+            return Ok(state.unwrap_or_else(|| AnalogActionState {
+                trigger: inactive_analog_action(),
+                joystick: inactive_analog_action(),
+            }));
+        }
+
+        let sources = self.action_sources_for_controller(controller_index, &cache);
+        Ok(read_analog_state(input, &cache, &sources))
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+/// Overlays showing a region of another overlay's texture, keyed by that overlay's handle.
+/// Submitting a frame to the atlas overlay also sets it on every member.
+/// 別のオーバーレイのテクスチャの一部を表示するオーバーレイ (そのオーバーレイのハンドルごと)。
+/// アトラスのオーバーレイへのフレーム送信で全メンバーにも設定する。
+#[derive(Debug, Default)]
+pub(super) struct AtlasTable {
+    members: HashMap<u64, Vec<u64>>,
+}
+
+impl AtlasTable {
+    /// Make `member` show `atlas`'s texture, moving it out of any other atlas.
+    /// `member` に `atlas` のテクスチャを表示させる (他のアトラスからは外す)
+    pub(super) fn register(&mut self, atlas: u64, member: u64) -> Result<(), String> {
+        if atlas == member {
+            return Err("An overlay cannot be a member of its own atlas".to_string());
+        }
+        if self.members.contains_key(&member) {
+            return Err(format!("Overlay {} is an atlas with members", member));
+        }
+        if self.atlas_of(atlas).is_some() {
+            return Err(format!("Overlay {} is a member of another atlas", atlas));
+        }
+        self.remove(member);
+        self.members.entry(atlas).or_default().push(member);
+        Ok(())
+    }
+
+    /// Take `member` out of its atlas; returns whether it was in one.
+    /// `member` をアトラスから外す (所属していたかを返す)
+    pub(super) fn remove(&mut self, member: u64) -> bool {
+        let Some(atlas) = self.atlas_of(member) else {
+            return false;
+        };
+        if let Some(members) = self.members.get_mut(&atlas) {
+            members.retain(|handle| *handle != member);
+            if members.is_empty() {
+                self.members.remove(&atlas);
+            }
+        }
+        true
+    }
+
+    /// Forget a destroyed overlay, both as an atlas and as a member.
+    /// 破棄されたオーバーレイをアトラスとしてもメンバーとしても忘れる
+    pub(super) fn forget(&mut self, handle: u64) {
+        self.members.remove(&handle);
+        self.remove(handle);
+    }
+
+    pub(super) fn members(&self, atlas: u64) -> &[u64] {
+        self.members.get(&atlas).map_or(&[], Vec::as_slice)
+    }
+
+    fn atlas_of(&self, member: u64) -> Option<u64> {
+        self.members
+            .iter()
+            .find(|(_, members)| members.contains(&member))
+            .map(|(atlas, _)| *atlas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_members_follow_their_atlas() {
+        let mut table = AtlasTable::default();
+        table.register(1, 10).unwrap();
+        table.register(1, 11).unwrap();
+        assert_eq!(table.members(1), &[10, 11]);
+        assert!(table.members(10).is_empty());
+        // Registering again keeps a single entry / 再登録しても 1 件のまま
+        table.register(1, 10).unwrap();
+        assert_eq!(table.members(1), &[11, 10]);
+    }
+
+    #[test]
+    fn overlay_cannot_join_its_own_atlas() {
+        let mut table = AtlasTable::default();
+        assert!(table.register(1, 1).is_err());
+        assert!(table.members(1).is_empty());
+    }
+
+    #[test]
+    fn atlases_and_members_do_not_nest() {
+        let mut table = AtlasTable::default();
+        table.register(1, 10).unwrap();
+        // An atlas with members cannot become a member / メンバーを持つアトラスはメンバーになれない
+        assert!(table.register(2, 1).unwrap_err().contains("is an atlas"));
+        // A member cannot become an atlas / メンバーはアトラスになれない
+        assert!(table
+            .register(10, 20)
+            .unwrap_err()
+            .contains("member of another"));
+        assert_eq!(table.members(1), &[10]);
+        assert!(table.members(2).is_empty() && table.members(10).is_empty());
+    }
+
+    #[test]
+    fn member_moves_between_atlases() {
+        let mut table = AtlasTable::default();
+        table.register(1, 10).unwrap();
+        table.register(1, 11).unwrap();
+        table.register(2, 10).unwrap();
+        assert_eq!(table.members(1), &[11]);
+        assert_eq!(table.members(2), &[10]);
+    }
+
+    #[test]
+    fn atlas_without_members_is_removed() {
+        let mut table = AtlasTable::default();
+        table.register(1, 10).unwrap();
+        table.register(2, 10).unwrap();
+        assert!(!table.members.contains_key(&1));
+
+        assert!(table.remove(10));
+        assert!(!table.remove(10));
+        assert!(table.members.is_empty());
+        // With no members left, the old atlas may join another / メンバーが無くなれば他のアトラスに参加できる
+        table.register(3, 2).unwrap();
+        assert_eq!(table.members(3), &[2]);
+    }
+
+    #[test]
+    fn forget_drops_an_overlay_in_either_role() {
+        let mut table = AtlasTable::default();
+        table.register(1, 10).unwrap();
+        table.register(1, 11).unwrap();
+        table.register(2, 12).unwrap();
+
+        table.forget(1);
+        assert!(table.members(1).is_empty());
+        assert!(!table.remove(10));
+        table.forget(12);
+        assert!(table.members.is_empty());
+        table.forget(99);
+    }
+}
@@ -0,0 +1,68 @@
+use napi_derive::napi;
+
+use super::handles::overlay_handle;
+use super::manager::OverlayManager;
+
+fn uv_coordinate(name: &str, value: f64) -> napi::Result<f64> {
+    if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+        return Err(napi::Error::from_reason(format!(
+            "{name} must be between 0 and 1"
+        )));
+    }
+    Ok(value)
+}
+
+#[napi]
+impl OverlayManager {
+    /// Show the `[uMin, vMin]`-`[uMax, vMax]` region of the texture submitted to `atlasHandle`
+    /// on `handle`. Frames set with setOverlayTexturesD3D11 or submitOverlayFrame for
+    /// `atlasHandle` are then set on `handle` too, so one capture can drive several panels.
+    /// `atlasHandle` に送信したテクスチャの `[uMin, vMin]`-`[uMax, vMax]` の領域を `handle` に表示する。
+    /// 以降 setOverlayTexturesD3D11 や submitOverlayFrame で `atlasHandle` に設定したフレームは
+    /// `handle` にも設定され、1 つのキャプチャで複数のパネルを描ける。
+    #[napi]
+    pub fn register_atlas_overlay(
+        &self,
+        atlas_handle: i64,
+        handle: i64,
+        u_min: f64,
+        v_min: f64,
+        u_max: f64,
+        v_max: f64,
+    ) -> napi::Result<()> {
+        let atlas = overlay_handle(atlas_handle)?.as_u64();
+        let member = overlay_handle(handle)?.as_u64();
+        let u_min = uv_coordinate("uMin", u_min)?;
+        let v_min = uv_coordinate("vMin", v_min)?;
+        let u_max = uv_coordinate("uMax", u_max)?;
+        let v_max = uv_coordinate("vMax", v_max)?;
+        if u_min == u_max || v_min == v_max {
+            return Err(napi::Error::from_reason("Atlas region must not be empty"));
+        }
+
+        self.borrow_atlas_mut()?
+            .register(atlas, member)
+            .map_err(napi::Error::from_reason)?;
+        if let Err(e) = self.set_overlay_texture_bounds(handle, u_min, v_min, u_max, v_max) {
+            self.borrow_atlas_mut()?.remove(member);
+            return Err(e);
+        }
+        // The new member needs the next frame even if it is unchanged
+        // 新しいメンバーには変化が無くても次のフレームが必要
+        self.lock_pipeline()?.frames.remove(&atlas);
+        Ok(())
+    }
+
+    /// Stop `handle` following its atlas and reset its texture bounds to the whole texture.
+    /// Returns whether it was registered.
+    /// `handle` をアトラスから外し、テクスチャ範囲をテクスチャ全体に戻す。登録されていたかを返す。
+    #[napi]
+    pub fn unregister_atlas_overlay(&self, handle: i64) -> napi::Result<bool> {
+        let member = overlay_handle(handle)?.as_u64();
+        if !self.borrow_atlas_mut()?.remove(member) {
+            return Ok(false);
+        }
+        self.set_overlay_texture_bounds(handle, 0.0, 0.0, 1.0, 1.0)?;
+        Ok(true)
+    }
+}
@@ -6,6 +6,20 @@ pub(super) const DEFAULT_SYSTEM_INTERFACE: &str = "FnTable:IVRSystem_023";
 pub(super) const DEFAULT_INPUT_INTERFACE: &str = "FnTable:IVRInput_010";
 pub(super) const BYTES_PER_PIXEL: u32 = 4;
 pub(super) const HMD_DEVICE_INDEX: u32 = 0;
+pub(super) const FALLBACK_DISPLAY_FREQUENCY_HZ: f64 = 90.0;
+
+// Input thread
+pub(super) const INPUT_EVENT_QUEUE_SIZE: usize = 256;
+
+// D3D11 output texture ring
+pub(super) const DEFAULT_OUTPUT_TEXTURES: u32 = 3;
+pub(super) const MAX_OUTPUT_TEXTURES: u32 = 8;
+
+// Frame snapshots
+pub(super) const MAX_CAPTURE_FRAMES: u32 = 300;
+
+// Images decoded in Rust
+pub(super) const MAX_IMAGE_SIZE: u32 = 4096;
 
 // Controller button bitmasks
 pub(super) const BUTTON_TRIGGER: u64 = 1u64 << 33; // k_EButton_SteamVR_Trigger
@@ -17,3 +31,8 @@ pub(super) const BUTTON_JOYSTICK: u64 = 1u64 << 34; // k_EButton_Axis2 (often jo
 pub(super) const AXIS_TRIGGER: usize = 1;
 pub(super) const AXIS_TOUCHPAD: usize = 0;
 pub(super) const AXIS_JOYSTICK: usize = 2;
+
+// SteamVR skeletal input (hand skeleton bone layout)
+pub(super) const SKELETON_BONE_COUNT: u32 = 31;
+pub(super) const BONE_INDEX_FINGER_TIP: usize = 10; // eBone_IndexFinger4
+pub(super) const FINGER_INDEX: usize = 1; // VRFinger_Index
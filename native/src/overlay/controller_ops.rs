@@ -1,12 +1,15 @@
 use napi_derive::napi;
 use openvr_sys as vr;
 
+use super::analog_ops::read_analog_action;
 use super::constants::{
     AXIS_JOYSTICK, AXIS_TOUCHPAD, AXIS_TRIGGER, BUTTON_GRIP, BUTTON_JOYSTICK, BUTTON_TOUCHPAD,
     BUTTON_TRIGGER,
 };
 use super::errors::require_fn;
-use super::manager::OverlayManager;
+use super::hand::Hand;
+use super::input_ops::read_digital_action;
+use super::manager::{InputActionCache, OverlayManager, PoseSnapshot};
 use super::math::hmd_matrix34_to_vec;
 use super::types::ControllerState;
 
@@ -24,174 +27,263 @@ fn empty_controller_state() -> ControllerState {
     }
 }
 
-#[napi]
-impl OverlayManager {
-    #[napi]
-    pub fn get_controller_ids(&self) -> napi::Result<Vec<u32>> {
-        // Get valid controller indices / 有効なコントローラーインデックスを取得
-        let mut controllers = Vec::new();
-        let system = self.system()?;
-        let get_class_fn = require_fn(system.GetTrackedDeviceClass, "GetTrackedDeviceClass")?;
-        unsafe {
-            for i in 0..vr::k_unMaxTrackedDeviceCount {
-                let device_class = get_class_fn(i);
-                if device_class == vr::ETrackedDeviceClass_TrackedDeviceClass_Controller {
-                    controllers.push(i);
+pub(super) fn controller_hand_for(
+    system: &vr::VR_IVRSystem_FnTable,
+    controller_index: u32,
+) -> Option<Hand> {
+    let get_role_fn = system.GetControllerRoleForTrackedDeviceIndex?;
+    match unsafe { get_role_fn(controller_index) } {
+        vr::ETrackedControllerRole_TrackedControllerRole_LeftHand => Some(Hand::Left),
+        vr::ETrackedControllerRole_TrackedControllerRole_RightHand => Some(Hand::Right),
+        _ => None,
+    }
+}
+
+pub(super) fn action_sources(
+    system: &vr::VR_IVRSystem_FnTable,
+    controller_index: u32,
+    cache: &InputActionCache,
+) -> Vec<vr::VRInputValueHandle_t> {
+    let preferred_source = match controller_hand_for(system, controller_index) {
+        Some(Hand::Left) => cache.left_hand_source,
+        Some(Hand::Right) => cache.right_hand_source,
+        None => vr::k_ulInvalidInputValueHandle,
+    };
+
+    if preferred_source == vr::k_ulInvalidInputValueHandle {
+        vec![vr::k_ulInvalidInputValueHandle]
+    } else {
+        vec![preferred_source, vr::k_ulInvalidInputValueHandle]
+    }
+}
+
+/// Legacy controller state with SteamVR Input actions applied on top.
+/// `input` is read from the most recent UpdateActionState.
+/// レガシーのコントローラー状態に SteamVR Input のアクションを上書きしたもの。
+/// `input` は直近の UpdateActionState の結果を読み取る。
+pub(super) fn read_controller_state(
+    system: &vr::VR_IVRSystem_FnTable,
+    input: Option<&vr::VR_IVRInput_FnTable>,
+    cache: &InputActionCache,
+    controller_index: u32,
+) -> napi::Result<ControllerState> {
+    let get_controller_state_fn = require_fn(system.GetControllerState, "GetControllerState")?;
+
+    if controller_index >= vr::k_unMaxTrackedDeviceCount {
+        return Err(napi::Error::from_reason("Invalid device index"));
+    }
+
+    unsafe {
+        let mut state: vr::VRControllerState_t = std::mem::zeroed();
+        let success = get_controller_state_fn(
+            controller_index,
+            &mut state,
+            std::mem::size_of::<vr::VRControllerState_t>() as u32,
+        );
+
+        let mut result = if !success {
+            empty_controller_state()
+        } else {
+            ControllerState {
+                triggerPressed: (state.ulButtonPressed & BUTTON_TRIGGER) != 0,
+                triggerValue: state.rAxis[AXIS_TRIGGER].x as f64,
+                gripPressed: (state.ulButtonPressed & BUTTON_GRIP) != 0,
+                touchpadPressed: (state.ulButtonPressed & BUTTON_TOUCHPAD) != 0,
+                touchpadX: state.rAxis[AXIS_TOUCHPAD].x as f64,
+                touchpadY: state.rAxis[AXIS_TOUCHPAD].y as f64,
+                joystickPressed: (state.ulButtonPressed & BUTTON_JOYSTICK) != 0,
+                joystickX: state.rAxis[AXIS_JOYSTICK].x as f64,
+                joystickY: state.rAxis[AXIS_JOYSTICK].y as f64,
+            }
+        };
+
+        // SteamVR Input integration / SteamVR Input 統合
+        if let Some(input) = input {
+            if cache.initialized {
+                let sources = action_sources(system, controller_index, cache);
+
+                if let Some(trigger) =
+                    read_digital_action(input, cache.trigger_action_handle, &sources)
+                {
+                    result.triggerPressed = trigger.bState;
+                }
+                if let Some(grip) = read_digital_action(input, cache.grip_action_handle, &sources) {
+                    result.gripPressed = grip.bState;
+                }
+
+                // Analog actions override legacy axes so user rebinding is honored
+                // アナログアクションでレガシー軸を上書きし、ユーザーのリバインドを反映
+                if let Some(trigger) =
+                    read_analog_action(input, cache.trigger_value_action_handle, &sources)
+                {
+                    result.triggerValue = trigger.x;
+                }
+                if let Some(joystick) =
+                    read_analog_action(input, cache.scroll_action_handle, &sources)
+                {
+                    result.joystickX = joystick.x;
+                    result.joystickY = joystick.y;
                 }
             }
         }
-        Ok(controllers)
+
+        Ok(result)
     }
+}
 
-    #[napi]
-    pub fn get_controller_pose(&self, index: u32) -> napi::Result<Vec<f64>> {
-        // TODO: Add frame-level caching to avoid redundant GetDeviceToAbsoluteTrackingPose calls
-        // TODO: フレーム単位のキャッシュを追加し、重複する GetDeviceToAbsoluteTrackingPose 呼び出しを避ける
-        let system = self.system()?;
-        let get_pose_fn = require_fn(
-            system.GetDeviceToAbsoluteTrackingPose,
-            "GetDeviceToAbsoluteTrackingPose",
-        )?;
+pub(super) struct DevicePose {
+    pub matrix: [[f32; 4]; 3],
+    /// Seconds on the manager clock (see now_seconds) / マネージャー時計での秒 (now_seconds 参照)
+    pub timestamp: f64,
+}
 
-        if index >= vr::k_unMaxTrackedDeviceCount {
-            return Err(napi::Error::from_reason("Invalid device index"));
-        }
+/// Refetch device poses unless the snapshot already holds the current compositor frame.
+/// スナップショットが現在のコンポジターフレームでなければデバイス姿勢を再取得
+pub(super) fn refresh_pose_snapshot(
+    system: &vr::VR_IVRSystem_FnTable,
+    snapshot: &mut PoseSnapshot,
+    now: f64,
+) -> napi::Result<()> {
+    let get_pose_fn = require_fn(
+        system.GetDeviceToAbsoluteTrackingPose,
+        "GetDeviceToAbsoluteTrackingPose",
+    )?;
 
-        let mut poses = self.borrow_poses_cache()?;
-        let pose_count = vr::k_unMaxTrackedDeviceCount as usize;
-        debug_assert_eq!(poses.len(), pose_count);
-        if poses.len() != pose_count {
-            poses.resize_with(pose_count, || unsafe { std::mem::zeroed() });
-        }
+    let mut seconds_since_vsync: f32 = 0.0; // Only the frame counter is used / フレームカウンターのみ使用
+    let mut frame_counter: u64 = 0;
+    let vsync_known = match system.GetTimeSinceLastVsync {
+        Some(get_vsync_fn) => unsafe { get_vsync_fn(&mut seconds_since_vsync, &mut frame_counter) },
+        None => false,
+    };
 
+    let pose_count = vr::k_unMaxTrackedDeviceCount as usize;
+    debug_assert_eq!(snapshot.poses.len(), pose_count);
+    if snapshot.poses.len() != pose_count {
+        snapshot
+            .poses
+            .resize_with(pose_count, || unsafe { std::mem::zeroed() });
+    }
+
+    if !vsync_known || snapshot.frame_index != Some(frame_counter) {
         unsafe {
             // Getting generic tracker pose
             // OpenVR API gets array of poses.
+            // No prediction, so the poses are for the moment of this call
+            // 予測なしのため、姿勢はこの呼び出し時点のもの
             get_pose_fn(
                 vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
                 0.0,
-                poses.as_mut_ptr(),
+                snapshot.poses.as_mut_ptr(),
                 vr::k_unMaxTrackedDeviceCount,
             );
+        }
+        snapshot.frame_index = vsync_known.then_some(frame_counter);
+        snapshot.timestamp = now;
+    }
+    Ok(())
+}
 
-            let pose = &poses[index as usize];
-            if !pose.bPoseIsValid || !pose.bDeviceIsConnected {
-                return Ok(vec![]); // Valid but not tracking/connected / 有効だが未トラッキング or 未接続
-            }
+impl PoseSnapshot {
+    pub(super) fn device_pose(&self, index: u32) -> Option<DevicePose> {
+        let pose = self.poses.get(index as usize)?;
+        if !pose.bPoseIsValid || !pose.bDeviceIsConnected {
+            return None;
+        }
+        Some(DevicePose {
+            matrix: pose.mDeviceToAbsoluteTracking.m,
+            timestamp: self.timestamp,
+        })
+    }
+}
 
-            Ok(hmd_matrix34_to_vec(&pose.mDeviceToAbsoluteTracking.m))
+impl OverlayManager {
+    /// Hand role currently assigned to a controller / コントローラーに割り当てられた手の役割
+    pub(super) fn controller_hand(&self, controller_index: u32) -> Option<Hand> {
+        let snapshot = self.read_input_snapshot(|snapshot| {
+            snapshot
+                .controllers
+                .get(&controller_index)
+                .and_then(|controller| controller.hand)
+        });
+        match snapshot {
+            Ok(Some(hand)) => hand,
+            Ok(None) => controller_hand_for(self.system().ok()?, controller_index),
+            Err(_) => None,
         }
     }
 
-    #[napi]
-    pub fn get_controller_state(&self, controller_index: u32) -> napi::Result<ControllerState> {
-        let system = self.system()?;
-        let get_controller_state_fn = require_fn(system.GetControllerState, "GetControllerState")?;
-        let get_role_fn = system.GetControllerRoleForTrackedDeviceIndex;
+    /// Input sources to query for a controller: its hand first, then any device.
+    /// コントローラーの問い合わせ先ソース: 対応する手を優先し、次に任意のデバイス
+    pub(super) fn action_sources_for_controller(
+        &self,
+        controller_index: u32,
+        cache: &InputActionCache,
+    ) -> Vec<vr::VRInputValueHandle_t> {
+        match self.system() {
+            Ok(system) => action_sources(system, controller_index, cache),
+            Err(_) => vec![vr::k_ulInvalidInputValueHandle],
+        }
+    }
 
-        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+    /// Device-to-standing pose of a tracked device, or None if not tracking.
+    /// Poses are fetched at most once per compositor frame and stamped with the time they were sampled.
+    /// While the input thread runs they come from its latest tick.
+    /// トラッキングデバイスのスタンディング空間での姿勢 (未トラッキングなら None)。
+    /// 姿勢はコンポジターのフレーム毎に最大一度だけ取得し、取得した時刻を付与する。
+    /// 入力スレッドの実行中は、その直近の周期で取得したものを使う。
+    pub(super) fn device_pose(&self, index: u32) -> napi::Result<Option<DevicePose>> {
+        let system = self.system()?;
+        if index >= vr::k_unMaxTrackedDeviceCount {
             return Err(napi::Error::from_reason("Invalid device index"));
         }
+        if let Some(pose) =
+            self.read_input_snapshot(|snapshot| snapshot.poses.device_pose(index))?
+        {
+            return Ok(pose);
+        }
 
-        unsafe {
-            let mut state: vr::VRControllerState_t = std::mem::zeroed();
-            let success = get_controller_state_fn(
-                controller_index,
-                &mut state,
-                std::mem::size_of::<vr::VRControllerState_t>() as u32,
-            );
+        let mut snapshot = self.borrow_poses_cache()?;
+        refresh_pose_snapshot(system, &mut snapshot, self.now_seconds())?;
+        Ok(snapshot.device_pose(index))
+    }
+}
 
-            let mut result = if !success {
-                empty_controller_state()
-            } else {
-                ControllerState {
-                    triggerPressed: (state.ulButtonPressed & BUTTON_TRIGGER) != 0,
-                    triggerValue: state.rAxis[AXIS_TRIGGER].x as f64,
-                    gripPressed: (state.ulButtonPressed & BUTTON_GRIP) != 0,
-                    touchpadPressed: (state.ulButtonPressed & BUTTON_TOUCHPAD) != 0,
-                    touchpadX: state.rAxis[AXIS_TOUCHPAD].x as f64,
-                    touchpadY: state.rAxis[AXIS_TOUCHPAD].y as f64,
-                    joystickPressed: (state.ulButtonPressed & BUTTON_JOYSTICK) != 0,
-                    joystickX: state.rAxis[AXIS_JOYSTICK].x as f64,
-                    joystickY: state.rAxis[AXIS_JOYSTICK].y as f64,
-                }
-            };
-
-            // SteamVR Input integration:
-            // Read action data from the most recent UpdateActionState (called by poll_toggle_clicked).
-            // SteamVR Input 統合:
-            // poll_toggle_clicked で呼ばれた最新の UpdateActionState の結果からアクションデータを読み取る。
-            if let Ok(input) = self.input() {
-                let cache = self.borrow_input_cache()?;
-                if cache.initialized {
-                    if let Some(get_digital_action_data_fn) = input.GetDigitalActionData {
-                        let preferred_source = if let Some(get_role_fn) = get_role_fn {
-                            match get_role_fn(controller_index) {
-                                vr::ETrackedControllerRole_TrackedControllerRole_LeftHand => {
-                                    cache.left_hand_source
-                                }
-                                vr::ETrackedControllerRole_TrackedControllerRole_RightHand => {
-                                    cache.right_hand_source
-                                }
-                                _ => vr::k_ulInvalidInputValueHandle,
-                            }
-                        } else {
-                            vr::k_ulInvalidInputValueHandle
-                        };
-
-                        let sources = [preferred_source, vr::k_ulInvalidInputValueHandle];
-                        let source_count = if preferred_source == vr::k_ulInvalidInputValueHandle {
-                            1
-                        } else {
-                            2
-                        };
-
-                        let mut trigger_overridden = false;
-                        let mut grip_overridden = false;
-                        for source in sources.iter().take(source_count).copied() {
-                            if !trigger_overridden {
-                                let mut trigger_data: vr::InputDigitalActionData_t =
-                                    std::mem::zeroed();
-                                let trigger_err = get_digital_action_data_fn(
-                                    cache.trigger_action_handle,
-                                    &mut trigger_data,
-                                    std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
-                                    source,
-                                );
-                                if trigger_err == vr::EVRInputError_VRInputError_None
-                                    && trigger_data.bActive
-                                {
-                                    result.triggerPressed = trigger_data.bState;
-                                    trigger_overridden = true;
-                                }
-                            }
-
-                            if !grip_overridden {
-                                let mut grip_data: vr::InputDigitalActionData_t =
-                                    std::mem::zeroed();
-                                let grip_err = get_digital_action_data_fn(
-                                    cache.grip_action_handle,
-                                    &mut grip_data,
-                                    std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
-                                    source,
-                                );
-                                if grip_err == vr::EVRInputError_VRInputError_None
-                                    && grip_data.bActive
-                                {
-                                    result.gripPressed = grip_data.bState;
-                                    grip_overridden = true;
-                                }
-                            }
-
-                            if trigger_overridden && grip_overridden {
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
+#[napi]
+impl OverlayManager {
+    #[napi]
+    pub fn get_controller_ids(&self) -> napi::Result<Vec<u32>> {
+        // Connected controllers from the device table / デバイス一覧から接続中のコントローラーを取得
+        self.refresh_devices()?;
+        Ok(self.lock_devices()?.controller_ids())
+    }
 
-            Ok(result)
+    #[napi]
+    pub fn get_controller_pose(&self, index: u32) -> napi::Result<Vec<f64>> {
+        match self.device_pose(index)? {
+            Some(pose) => Ok(hmd_matrix34_to_vec(&pose.matrix)),
+            None => Ok(vec![]), // Valid but not tracking/connected / 有効だが未トラッキング or 未接続
+        }
+    }
+
+    #[napi]
+    pub fn get_controller_state(&self, controller_index: u32) -> napi::Result<ControllerState> {
+        // Read action data from the most recent UpdateActionState (called by poll_toggle_clicked).
+        // poll_toggle_clicked で呼ばれた最新の UpdateActionState の結果からアクションデータを読み取る。
+        let system = self.system()?;
+        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+            return Err(napi::Error::from_reason("Invalid device index"));
+        }
+        let snapshot = self.read_input_snapshot(|snapshot| {
+            snapshot
+                .controllers
+                .get(&controller_index)
+                .map(|controller| controller.state.clone())
+        })?;
+        if let Some(state) = snapshot {
+            // Not a connected controller on the thread's latest tick / スレッドの直近の周期で未接続
+            return Ok(state.unwrap_or_else(empty_controller_state));
         }
+        let cache = self.borrow_input_cache()?;
+        read_controller_state(system, self.input().ok(), &cache, controller_index)
     }
 }
@@ -1,42 +1,109 @@
+use std::collections::HashMap;
 use std::ffi::c_void;
 
 use windows::core::Interface;
 use windows::core::PCSTR;
+
+use super::pixels::AlphaMode;
+use windows::Win32::Foundation::BOOL;
 use windows::Win32::Graphics::Direct3D::{
     Fxc::D3DCompile, ID3DBlob, D3D_DRIVER_TYPE_HARDWARE, D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
 };
 use windows::Win32::Graphics::Direct3D11::{
-    D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11PixelShader,
-    ID3D11RenderTargetView, ID3D11Resource, ID3D11SamplerState, ID3D11ShaderResourceView,
-    ID3D11Texture2D, ID3D11VertexShader, D3D11_BIND_RENDER_TARGET, D3D11_BIND_SHADER_RESOURCE,
+    D3D11CreateDevice, ID3D11Buffer, ID3D11Device, ID3D11DeviceContext, ID3D11PixelShader,
+    ID3D11Query, ID3D11RenderTargetView, ID3D11Resource, ID3D11SamplerState,
+    ID3D11ShaderResourceView, ID3D11Texture2D, ID3D11VertexShader, D3D11_BIND_CONSTANT_BUFFER,
+    D3D11_BIND_RENDER_TARGET, D3D11_BIND_SHADER_RESOURCE, D3D11_BOX, D3D11_BUFFER_DESC,
     D3D11_CPU_ACCESS_READ, D3D11_CPU_ACCESS_WRITE, D3D11_CREATE_DEVICE_FLAG,
     D3D11_FILTER_MIN_MAG_MIP_POINT, D3D11_MAPPED_SUBRESOURCE, D3D11_MAP_READ,
-    D3D11_MAP_WRITE_DISCARD, D3D11_SAMPLER_DESC, D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC,
-    D3D11_TEXTURE_ADDRESS_CLAMP, D3D11_USAGE_DEFAULT, D3D11_USAGE_DYNAMIC, D3D11_USAGE_STAGING,
-    D3D11_VIEWPORT,
+    D3D11_MAP_WRITE_DISCARD, D3D11_QUERY_DESC, D3D11_QUERY_EVENT, D3D11_SAMPLER_DESC,
+    D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC, D3D11_TEXTURE_ADDRESS_CLAMP, D3D11_USAGE_DEFAULT,
+    D3D11_USAGE_DYNAMIC, D3D11_USAGE_STAGING, D3D11_VIEWPORT,
 };
 use windows::Win32::Graphics::Dxgi::Common::{
-    DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_SAMPLE_DESC,
+    DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_B8G8R8A8_UNORM_SRGB, DXGI_FORMAT_R8G8B8A8_UNORM,
+    DXGI_FORMAT_R8G8B8A8_UNORM_SRGB, DXGI_SAMPLE_DESC,
 };
 
+/// One texture of the output ring. A texture is drawn into again only once its event query
+/// shows the previous pass finished on the GPU and, when possible, no overlay is showing it.
+/// 出力リングのテクスチャ 1 枚。イベントクエリで前回のパスが GPU で終わったことを確認でき、
+/// 可能な限りどのオーバーレイも表示していない場合にのみ再び描画する。
+pub struct OutputTexture {
+    pub texture: ID3D11Texture2D,
+    rtv: ID3D11RenderTargetView,
+    rendered: ID3D11Query,
+    /// `rendered` was issued and not yet seen signaled / `rendered` を発行済みでまだシグナルを確認していない
+    pending: bool,
+}
+
+/// Input texture and output ring of one size and format, created and replaced together
+/// 同じサイズと形式の入力テクスチャと出力リング (まとめて作成・置換する)
+pub struct TextureSet {
+    input: ID3D11Texture2D,
+    input_srv: ID3D11ShaderResourceView,
+    outputs: Vec<OutputTexture>,
+    /// Output each front overlay shows, by front handle; that overlay's next conversion
+    /// looks for a free output after it
+    /// 前面オーバーレイハンドルごとの表示中の出力 (そのオーバーレイの次の変換はその次から空きを探す)
+    shown: HashMap<u64, usize>,
+}
+
+impl TextureSet {
+    /// Pick the output for `front`'s next frame without waiting on the GPU: an output no
+    /// overlay shows, else the one `front` shows, else one another overlay shows. Outputs
+    /// whose previous pass is still running are skipped; None if that is all of them.
+    /// GPU を待たずに `front` の次のフレームの出力を選ぶ: どのオーバーレイも表示していない出力、
+    /// 無ければ `front` が表示中の出力、それも無ければ他のオーバーレイが表示中の出力。
+    /// 前回のパスが実行中の出力は飛ばし、すべてそうなら None。
+    fn acquire_output(
+        &mut self,
+        context: &ID3D11DeviceContext,
+        front: u64,
+    ) -> napi::Result<Option<usize>> {
+        let count = self.outputs.len();
+        let own = self.shown.get(&front).copied();
+        let start = own.map_or(0, |index| index + 1);
+        let mut fallback = None;
+        for index in (0..count).map(|step| (start + step) % count) {
+            if !output_finished(context, &mut self.outputs[index])? {
+                continue;
+            }
+            if !self.shown.values().any(|shown| *shown == index) {
+                return Ok(Some(index));
+            }
+            // The own output comes last in this order / 自身の出力はこの順序の最後に来る
+            if fallback.is_none() || own == Some(index) {
+                fallback = Some(index);
+            }
+        }
+        Ok(fallback)
+    }
+}
+
 pub struct D3D11Context {
     // Core device / コアデバイス
     pub device: ID3D11Device,
     pub context: ID3D11DeviceContext,
 
     // Texture resources / テクスチャリソース
-    pub staging_bgra_texture: Option<ID3D11Texture2D>,
-    pub output_rgba_texture: Option<ID3D11Texture2D>,
-    pub bgra_srv: Option<ID3D11ShaderResourceView>,
-    pub rgba_rtv: Option<ID3D11RenderTargetView>,
+    pub textures: Option<TextureSet>,
     pub texture_width: u32,
     pub texture_height: u32,
+    /// Textures use `_SRGB` formats, so the shader works in linear light
+    /// テクスチャは `_SRGB` 形式 (シェーダは線形光で処理する)
+    pub texture_srgb: bool,
+    /// Overlay whose frame the input texture holds; partial uploads are only valid for it
+    /// 入力テクスチャが保持しているフレームのオーバーレイ (部分更新はこれに対してのみ有効)
+    pub content_owner: Option<u64>,
 
     // Shader pipeline / シェーダパイプライン
     pub vertex_shader: Option<ID3D11VertexShader>,
     pub pixel_shader_passthrough: Option<ID3D11PixelShader>,
     pub pixel_shader_swizzle: Option<ID3D11PixelShader>,
     pub sampler_state: Option<ID3D11SamplerState>,
+    /// `Conversion` cbuffer of the pixel shaders / ピクセルシェーダの `Conversion` 定数バッファ
+    pub conversion_constants: Option<ID3D11Buffer>,
 
     // Channel probe state / チャネルプローブ状態
     pub swap_rb_required: bool,
@@ -44,48 +111,106 @@ pub struct D3D11Context {
 }
 
 impl D3D11Context {
+    /// Stop reserving the output `front` shows, as it no longer shows one.
+    /// `front` が出力を表示しなくなったため、その出力の確保をやめる
+    pub fn forget_output(&mut self, front: u64) {
+        if let Some(textures) = self.textures.as_mut() {
+            textures.shown.remove(&front);
+        }
+    }
+
+    /// Output to draw `front`'s next frame into, or None when every output is still being
+    /// drawn on the GPU and the frame should be dropped.
+    /// `front` の次のフレームの描画先の出力。すべての出力が GPU で描画中ならフレームを破棄すべきとして None
+    pub fn acquire_output(&mut self, front: u64) -> napi::Result<Option<usize>> {
+        let textures = self
+            .textures
+            .as_mut()
+            .ok_or_else(|| napi::Error::from_reason("Output textures are not initialized"))?;
+        textures.acquire_output(&self.context, front)
+    }
+
     pub fn reset_texture(&mut self) {
-        self.staging_bgra_texture = None;
-        self.output_rgba_texture = None;
-        self.bgra_srv = None;
-        self.rgba_rtv = None;
+        self.textures = None;
         self.texture_width = 0;
         self.texture_height = 0;
+        self.texture_srgb = false;
+        self.content_owner = None;
     }
 
-    pub fn ensure_resources(&mut self, width: u32, height: u32) -> napi::Result<()> {
+    /// Make sure the input texture and a ring of `output_count` output textures exist for
+    /// the size and format; they are rebuilt together when any of these changes.
+    /// Returns whether textures were created.
+    /// サイズと形式に合う入力テクスチャと `output_count` 枚の出力リングを用意する
+    /// (いずれかが変われば、まとめて作り直す)。テクスチャを作成したかを返す。
+    pub fn ensure_resources(
+        &mut self,
+        width: u32,
+        height: u32,
+        srgb: bool,
+        output_count: usize,
+    ) -> napi::Result<bool> {
         self.ensure_shaders()?;
 
-        if self.staging_bgra_texture.is_some()
-            && self.output_rgba_texture.is_some()
-            && self.bgra_srv.is_some()
-            && self.rgba_rtv.is_some()
+        if self
+            .textures
+            .as_ref()
+            .is_some_and(|textures| textures.outputs.len() == output_count)
             && self.texture_width == width
             && self.texture_height == height
+            && self.texture_srgb == srgb
         {
-            return Ok(());
+            return Ok(false);
         }
 
-        if width == 0 || height == 0 {
+        if width == 0 || height == 0 || output_count == 0 {
             self.reset_texture();
-            return Ok(());
+            return Ok(false);
         }
 
-        self.reset_texture();
+        // Build the complete set before swapping it in, so a failed rebuild never leaves
+        // a mix of old and new textures
+        // 完成したセットを作ってから差し替え、再作成の失敗で新旧のテクスチャが混在しないようにする
+        let textures = self.create_textures(width, height, srgb, output_count)?;
+        self.textures = Some(textures);
+        self.texture_width = width;
+        self.texture_height = height;
+        self.texture_srgb = srgb;
+        self.content_owner = None;
+
+        Ok(true)
+    }
 
+    fn create_textures(
+        &self,
+        width: u32,
+        height: u32,
+        srgb: bool,
+        output_count: usize,
+    ) -> napi::Result<TextureSet> {
+        // sRGB views decode on sample and encode on write / sRGB ビューはサンプル時にデコード、書き込み時にエンコードする
+        let (input_format, output_format) = if srgb {
+            (
+                DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+                DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+            )
+        } else {
+            (DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_R8G8B8A8_UNORM)
+        };
         let staging_desc = D3D11_TEXTURE2D_DESC {
             Width: width,
             Height: height,
             MipLevels: 1,
             ArraySize: 1,
-            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            Format: input_format,
             SampleDesc: DXGI_SAMPLE_DESC {
                 Count: 1,
                 Quality: 0,
             },
-            Usage: D3D11_USAGE_DYNAMIC,
+            // Default usage so dirty regions can be updated in place / 変更領域をその場で更新できるよう DEFAULT
+            Usage: D3D11_USAGE_DEFAULT,
             BindFlags: D3D11_BIND_SHADER_RESOURCE.0 as u32,
-            CPUAccessFlags: D3D11_CPU_ACCESS_WRITE.0 as u32,
+            CPUAccessFlags: 0,
             MiscFlags: 0,
         };
         let output_desc = D3D11_TEXTURE2D_DESC {
@@ -93,7 +218,7 @@ impl D3D11Context {
             Height: height,
             MipLevels: 1,
             ArraySize: 1,
-            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            Format: output_format,
             SampleDesc: DXGI_SAMPLE_DESC {
                 Count: 1,
                 Quality: 0,
@@ -105,91 +230,134 @@ impl D3D11Context {
         };
 
         let mut staging_texture: Option<ID3D11Texture2D> = None;
-        let mut output_texture: Option<ID3D11Texture2D> = None;
         unsafe {
             self.device
                 .CreateTexture2D(&staging_desc, None, Some(&mut staging_texture))
                 .map_err(|e| {
                     napi::Error::from_reason(format!("CreateTexture2D failed: {:?}", e))
                 })?;
-            self.device
-                .CreateTexture2D(&output_desc, None, Some(&mut output_texture))
-                .map_err(|e| {
-                    napi::Error::from_reason(format!("Create output texture failed: {:?}", e))
-                })?;
         }
-
         let staging_texture = staging_texture.ok_or_else(|| {
             napi::Error::from_reason("CreateTexture2D returned a null staging texture")
         })?;
-        let output_texture = output_texture.ok_or_else(|| {
-            napi::Error::from_reason("CreateTexture2D returned a null output texture")
-        })?;
-
         let staging_resource: ID3D11Resource = staging_texture.cast().map_err(|e| {
             napi::Error::from_reason(format!(
                 "Staging texture cast to ID3D11Resource failed: {:?}",
                 e
             ))
         })?;
-        let output_resource: ID3D11Resource = output_texture.cast().map_err(|e| {
-            napi::Error::from_reason(format!(
-                "Output texture cast to ID3D11Resource failed: {:?}",
-                e
-            ))
-        })?;
-
         let mut bgra_srv: Option<ID3D11ShaderResourceView> = None;
-        let mut rgba_rtv: Option<ID3D11RenderTargetView> = None;
         unsafe {
             self.device
                 .CreateShaderResourceView(&staging_resource, None, Some(&mut bgra_srv))
                 .map_err(|e| {
                     napi::Error::from_reason(format!("CreateShaderResourceView failed: {:?}", e))
                 })?;
-            self.device
-                .CreateRenderTargetView(&output_resource, None, Some(&mut rgba_rtv))
-                .map_err(|e| {
-                    napi::Error::from_reason(format!("CreateRenderTargetView failed: {:?}", e))
-                })?;
         }
+        let bgra_srv = bgra_srv
+            .ok_or_else(|| napi::Error::from_reason("CreateShaderResourceView returned null"))?;
 
-        self.staging_bgra_texture = Some(staging_texture);
-        self.output_rgba_texture = Some(output_texture);
-        self.bgra_srv = bgra_srv;
-        self.rgba_rtv = rgba_rtv;
-        self.texture_width = width;
-        self.texture_height = height;
+        let query_desc = D3D11_QUERY_DESC {
+            Query: D3D11_QUERY_EVENT,
+            MiscFlags: 0,
+        };
+        let mut outputs = Vec::with_capacity(output_count);
+        for _ in 0..output_count {
+            let mut output_texture: Option<ID3D11Texture2D> = None;
+            unsafe {
+                self.device
+                    .CreateTexture2D(&output_desc, None, Some(&mut output_texture))
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Create output texture failed: {:?}", e))
+                    })?;
+            }
+            let output_texture = output_texture.ok_or_else(|| {
+                napi::Error::from_reason("CreateTexture2D returned a null output texture")
+            })?;
+            let output_resource: ID3D11Resource = output_texture.cast().map_err(|e| {
+                napi::Error::from_reason(format!(
+                    "Output texture cast to ID3D11Resource failed: {:?}",
+                    e
+                ))
+            })?;
 
-        Ok(())
-    }
+            let mut rgba_rtv: Option<ID3D11RenderTargetView> = None;
+            let mut rendered: Option<ID3D11Query> = None;
+            unsafe {
+                self.device
+                    .CreateRenderTargetView(&output_resource, None, Some(&mut rgba_rtv))
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("CreateRenderTargetView failed: {:?}", e))
+                    })?;
+                self.device
+                    .CreateQuery(&query_desc, Some(&mut rendered))
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("CreateQuery failed: {:?}", e))
+                    })?;
+            }
+            outputs.push(OutputTexture {
+                texture: output_texture,
+                rtv: rgba_rtv.ok_or_else(|| {
+                    napi::Error::from_reason("CreateRenderTargetView returned null")
+                })?,
+                rendered: rendered
+                    .ok_or_else(|| napi::Error::from_reason("CreateQuery returned null"))?,
+                pending: false,
+            });
+        }
 
-    pub fn output_texture(&self) -> Option<&ID3D11Texture2D> {
-        self.output_rgba_texture.as_ref()
+        Ok(TextureSet {
+            input: staging_texture,
+            input_srv: bgra_srv,
+            outputs,
+            shown: HashMap::new(),
+        })
     }
 
-    pub fn upload_bgra_buffer(
+    /// Copy the `[x, y, width, height]` pixel region of a BGRA buffer into the same region
+    /// of the input texture with UpdateSubresource; the rest of the texture is kept.
+    /// BGRA バッファの `[x, y, width, height]` の領域を UpdateSubresource で入力テクスチャの
+    /// 同じ領域にコピーする (それ以外の部分は保持される)。
+    pub fn upload_bgra_region(
         &self,
-        src: *const u8,
-        src_len: usize,
+        src: &[u8],
         src_row_pitch: usize,
-        width: u32,
-        height: u32,
+        [x, y, width, height]: [u32; 4],
     ) -> napi::Result<()> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        if x + width > self.texture_width || y + height > self.texture_height {
+            return Err(napi::Error::from_reason(format!(
+                "Upload region {}x{}+{}+{} exceeds the {}x{} texture",
+                width, height, x, y, self.texture_width, self.texture_height
+            )));
+        }
+
         // Validate source buffer bounds / ソースバッファの境界を検証
-        let required_len = (height as usize)
+        let offset = (y as usize)
             .checked_mul(src_row_pitch)
+            .and_then(|v| v.checked_add(x as usize * 4))
             .ok_or_else(|| napi::Error::from_reason("Source buffer size overflow"))?;
-        if src_len < required_len {
+        let required_len = (height as usize - 1)
+            .checked_mul(src_row_pitch)
+            .and_then(|v| v.checked_add(width as usize * 4))
+            .and_then(|v| v.checked_add(offset))
+            .ok_or_else(|| napi::Error::from_reason("Source buffer size overflow"))?;
+        if src.len() < required_len {
             return Err(napi::Error::from_reason(format!(
                 "Source buffer too small: need {} bytes, got {}",
-                required_len, src_len
+                required_len,
+                src.len()
             )));
         }
+        let src_row_pitch = u32::try_from(src_row_pitch)
+            .map_err(|_| napi::Error::from_reason("Row pitch is too large"))?;
 
         let texture = self
-            .staging_bgra_texture
+            .textures
             .as_ref()
+            .map(|textures| &textures.input)
             .ok_or_else(|| napi::Error::from_reason("Staging BGRA texture is not initialized"))?;
         let resource: ID3D11Resource = texture.cast().map_err(|e| {
             napi::Error::from_reason(format!(
@@ -198,65 +366,57 @@ impl D3D11Context {
             ))
         })?;
 
+        let region = D3D11_BOX {
+            left: x,
+            top: y,
+            front: 0,
+            right: x + width,
+            bottom: y + height,
+            back: 1,
+        };
         unsafe {
-            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
-            self.context
-                .Map(&resource, 0, D3D11_MAP_WRITE_DISCARD, 0, Some(&mut mapped))
-                .map_err(|e| napi::Error::from_reason(format!("Map failed: {:?}", e)))?;
-
-            let dst = mapped.pData as *mut u8;
-            if dst.is_null() {
-                self.context.Unmap(&resource, 0);
-                return Err(napi::Error::from_reason("Mapped texture pointer is null"));
-            }
-            let dst_pitch = mapped.RowPitch as usize;
-            if dst_pitch < src_row_pitch {
-                self.context.Unmap(&resource, 0);
-                return Err(napi::Error::from_reason(
-                    "Mapped row pitch is smaller than source row size",
-                ));
-            }
-
-            let copy_row_bytes = (width as usize)
-                .checked_mul(4)
-                .ok_or_else(|| napi::Error::from_reason("Row byte size overflow"))?;
-
-            for y in 0..height {
-                let src_row = src.add((y as usize) * src_row_pitch);
-                let dst_row = dst.add((y as usize) * dst_pitch);
-                std::ptr::copy_nonoverlapping(src_row, dst_row, copy_row_bytes);
-            }
-
-            self.context.Unmap(&resource, 0);
+            self.context.UpdateSubresource(
+                &resource,
+                0,
+                Some(&region),
+                src.as_ptr().add(offset) as *const c_void,
+                src_row_pitch,
+                0,
+            );
         }
 
         Ok(())
     }
 
+    /// Convert the input texture into output `output_index` (from acquire_output), which
+    /// `front` shows from now on, and return it. `rgb_source` marks pixels uploaded in RGB
+    /// order, which the shader swizzles; `alpha` is applied in the shader.
+    /// 入力テクスチャを出力 `output_index` (acquire_output で選んだもの) に変換して返す
+    /// (以後 `front` が表示する)。`rgb_source` は RGB 順でアップロードされたピクセル
+    /// (シェーダで並べ替える)、`alpha` はシェーダで適用する。
+    ///
     /// WARNING: This method modifies D3D11 pipeline state (IA topology, VS, PS,
-    /// sampler, viewport, RTV). No save/restore is performed. If other D3D11
+    /// sampler, viewport, RTV, constant buffer). No save/restore is performed. If other D3D11
     /// consumers are added in the future, a state save/restore pattern should
     /// be introduced.
     /// 警告: このメソッドはD3D11パイプラインステート (IA topology, VS, PS, sampler,
-    /// viewport, RTV) を変更する。保存・復元は行わない。将来他のD3D11利用者が
+    /// viewport, RTV, 定数バッファ) を変更する。保存・復元は行わない。将来他のD3D11利用者が
     /// 追加された場合、ステート保存・復元パターンを導入すること。
-    pub fn convert_bgra_to_rgba(&self, width: u32, height: u32) -> napi::Result<()> {
-        let rtv = self
-            .rgba_rtv
-            .as_ref()
-            .ok_or_else(|| napi::Error::from_reason("Output RTV is not initialized"))?
-            .clone();
-        let srv = self
-            .bgra_srv
-            .as_ref()
-            .ok_or_else(|| napi::Error::from_reason("Input SRV is not initialized"))?
-            .clone();
+    pub fn convert_bgra_to_rgba(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgb_source: bool,
+        alpha: AlphaMode,
+        front: u64,
+        output_index: usize,
+    ) -> napi::Result<ID3D11Texture2D> {
         let vs = self
             .vertex_shader
             .as_ref()
             .ok_or_else(|| napi::Error::from_reason("Vertex shader is not initialized"))?
             .clone();
-        let ps = if self.swap_rb_required {
+        let ps = if self.swap_rb_required != rgb_source {
             self.pixel_shader_swizzle
                 .as_ref()
                 .ok_or_else(|| napi::Error::from_reason("Swizzle pixel shader is not initialized"))?
@@ -284,8 +444,20 @@ impl D3D11Context {
             MaxDepth: 1.0,
         };
 
+        self.set_conversion_constants(alpha)?;
+        let textures = self
+            .textures
+            .as_mut()
+            .ok_or_else(|| napi::Error::from_reason("Output textures are not initialized"))?;
+        textures.shown.insert(front, output_index);
+        let srv = textures.input_srv.clone();
+        let output = textures
+            .outputs
+            .get_mut(output_index)
+            .ok_or_else(|| napi::Error::from_reason("Output texture index is out of range"))?;
         unsafe {
-            self.context.OMSetRenderTargets(Some(&[Some(rtv)]), None);
+            self.context
+                .OMSetRenderTargets(Some(&[Some(output.rtv.clone())]), None);
             self.context.RSSetViewports(Some(&[viewport]));
             self.context
                 .IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
@@ -299,8 +471,101 @@ impl D3D11Context {
             // Unbind RTV to prevent stale references / RTV をアンバインドして古参照を防止
             self.context.PSSetShaderResources(0, Some(&[None]));
             self.context.OMSetRenderTargets(None, None);
+
+            // Signaled once the GPU has finished this pass / GPU がこのパスを終えるとシグナルされる
+            self.context.End(&output.rendered);
         }
+        output.pending = true;
 
+        Ok(output.texture.clone())
+    }
+
+    /// Copy an output texture back to the CPU as tightly packed RGBA, byte for byte what
+    /// SetOverlayTexture was given.
+    /// 出力テクスチャを詰めた RGBA として CPU に読み戻す (SetOverlayTexture に渡した内容そのまま)。
+    pub fn read_output_rgba(&self, texture: &ID3D11Texture2D) -> napi::Result<Vec<u8>> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe { texture.GetDesc(&mut desc) };
+        let readback_desc = D3D11_TEXTURE2D_DESC {
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+            ..desc
+        };
+        let mut readback_tex: Option<ID3D11Texture2D> = None;
+        unsafe {
+            self.device
+                .CreateTexture2D(&readback_desc, None, Some(&mut readback_tex))
+                .map_err(|e| {
+                    napi::Error::from_reason(format!("Readback texture creation failed: {:?}", e))
+                })?;
+        }
+        let readback_tex =
+            readback_tex.ok_or_else(|| napi::Error::from_reason("Readback texture is null"))?;
+        let source: ID3D11Resource = texture.cast().map_err(|e| {
+            napi::Error::from_reason(format!("Readback source cast failed: {:?}", e))
+        })?;
+        let readback: ID3D11Resource = readback_tex
+            .cast()
+            .map_err(|e| napi::Error::from_reason(format!("Readback cast failed: {:?}", e)))?;
+
+        let row_bytes = desc.Width as usize * 4;
+        let mut pixels = Vec::with_capacity(row_bytes * desc.Height as usize);
+        unsafe {
+            // Map waits for the copy, which is queued after every pass drawn so far
+            // Map はコピーの完了を待つ (コピーはこれまでに描画した全パスの後に積まれる)
+            self.context.CopyResource(&readback, &source);
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(&readback, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                .map_err(|e| napi::Error::from_reason(format!("Readback map failed: {:?}", e)))?;
+            let src = mapped.pData as *const u8;
+            if src.is_null() {
+                self.context.Unmap(&readback, 0);
+                return Err(napi::Error::from_reason("Readback pointer is null"));
+            }
+            for row in 0..desc.Height as usize {
+                let line = src.add(row * mapped.RowPitch as usize);
+                pixels.extend_from_slice(std::slice::from_raw_parts(line, row_bytes));
+            }
+            self.context.Unmap(&readback, 0);
+        }
+        Ok(pixels)
+    }
+
+    /// Write and bind the `Conversion` cbuffer / `Conversion` 定数バッファを書き込んでバインドする
+    fn set_conversion_constants(&self, alpha: AlphaMode) -> napi::Result<()> {
+        let buffer = self
+            .conversion_constants
+            .as_ref()
+            .ok_or_else(|| napi::Error::from_reason("Conversion constants are not initialized"))?;
+        let resource: ID3D11Resource = buffer.cast().map_err(|e| {
+            napi::Error::from_reason(format!(
+                "Constant buffer cast to ID3D11Resource failed: {:?}",
+                e
+            ))
+        })?;
+        // Matches alphaMode in the pixel shaders / ピクセルシェーダの alphaMode と対応
+        let alpha_mode: u32 = match alpha {
+            AlphaMode::Straight => 0,
+            AlphaMode::Premultiply => 1,
+            AlphaMode::Unpremultiply => 2,
+            AlphaMode::Opaque => 3,
+        };
+        let constants = [alpha_mode, 0, 0, 0];
+        unsafe {
+            self.context.UpdateSubresource(
+                &resource,
+                0,
+                None,
+                constants.as_ptr() as *const c_void,
+                0,
+                0,
+            );
+            self.context
+                .PSSetConstantBuffers(0, Some(&[Some(buffer.clone())]));
+        }
         Ok(())
     }
 
@@ -311,6 +576,7 @@ impl D3D11Context {
             && self.pixel_shader_passthrough.is_some()
             && self.pixel_shader_swizzle.is_some()
             && self.sampler_state.is_some()
+            && self.conversion_constants.is_some()
             && self.channel_probe_done
         {
             return Ok(());
@@ -337,18 +603,42 @@ VSOut main(uint vid : SV_VertexID) {
 Texture2D inputTex : register(t0);
 SamplerState samp0 : register(s0);
 
+cbuffer Conversion : register(b0) {
+    uint alphaMode; // 0 straight, 1 premultiply, 2 unpremultiply, 3 opaque
+    uint3 padding;
+};
+
+float4 applyAlpha(float4 c) {
+    if (alphaMode == 1) c.rgb *= c.a;
+    else if (alphaMode == 2) c.rgb = c.a > 0.0 ? min(c.rgb / c.a, 1.0) : 0.0;
+    else if (alphaMode == 3) c.a = 1.0;
+    return c;
+}
+
 float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
     float4 c = inputTex.Sample(samp0, uv);
-    return c;
+    return applyAlpha(c);
 }
 "#;
         let pixel_shader_swizzle_source = br#"
 Texture2D inputTex : register(t0);
 SamplerState samp0 : register(s0);
 
+cbuffer Conversion : register(b0) {
+    uint alphaMode; // 0 straight, 1 premultiply, 2 unpremultiply, 3 opaque
+    uint3 padding;
+};
+
+float4 applyAlpha(float4 c) {
+    if (alphaMode == 1) c.rgb *= c.a;
+    else if (alphaMode == 2) c.rgb = c.a > 0.0 ? min(c.rgb / c.a, 1.0) : 0.0;
+    else if (alphaMode == 3) c.a = 1.0;
+    return c;
+}
+
 float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
     float4 c = inputTex.Sample(samp0, uv);
-    return c.bgra;
+    return applyAlpha(c.bgra);
 }
 "#;
 
@@ -360,6 +650,7 @@ float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
         let mut pixel_shader_passthrough: Option<ID3D11PixelShader> = None;
         let mut pixel_shader_swizzle: Option<ID3D11PixelShader> = None;
         let mut sampler_state: Option<ID3D11SamplerState> = None;
+        let mut conversion_constants: Option<ID3D11Buffer> = None;
 
         let sampler_desc = D3D11_SAMPLER_DESC {
             Filter: D3D11_FILTER_MIN_MAG_MIP_POINT,
@@ -373,6 +664,14 @@ float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
             MinLOD: 0.0,
             MaxLOD: f32::MAX,
         };
+        let constants_desc = D3D11_BUFFER_DESC {
+            ByteWidth: 16,
+            Usage: D3D11_USAGE_DEFAULT,
+            BindFlags: D3D11_BIND_CONSTANT_BUFFER.0 as u32,
+            CPUAccessFlags: 0,
+            MiscFlags: 0,
+            StructureByteStride: 0,
+        };
 
         unsafe {
             self.device
@@ -399,12 +698,16 @@ float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
                 .map_err(|e| {
                     napi::Error::from_reason(format!("CreateSamplerState failed: {:?}", e))
                 })?;
+            self.device
+                .CreateBuffer(&constants_desc, None, Some(&mut conversion_constants))
+                .map_err(|e| napi::Error::from_reason(format!("CreateBuffer failed: {:?}", e)))?;
         }
 
         self.vertex_shader = vertex_shader;
         self.pixel_shader_passthrough = pixel_shader_passthrough;
         self.pixel_shader_swizzle = pixel_shader_swizzle;
         self.sampler_state = sampler_state;
+        self.conversion_constants = conversion_constants;
         self.swap_rb_required = self.probe_channel_swizzle()?;
         self.channel_probe_done = true;
 
@@ -537,6 +840,7 @@ float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
         }
         let probe_srv = probe_srv.ok_or_else(|| napi::Error::from_reason("Probe SRV is null"))?;
         let probe_rtv = probe_rtv.ok_or_else(|| napi::Error::from_reason("Probe RTV is null"))?;
+        self.set_conversion_constants(AlphaMode::Straight)?;
 
         unsafe {
             let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
@@ -616,6 +920,33 @@ float4 main(float4 pos : SV_POSITION, float2 uv : TEXCOORD0) : SV_TARGET {
     }
 }
 
+/// Whether the GPU has finished the last pass drawn into `output`, without waiting for it.
+/// `output` に最後に描画したパスを GPU が終えたか (待たずに確認する)
+fn output_finished(
+    context: &ID3D11DeviceContext,
+    output: &mut OutputTexture,
+) -> napi::Result<bool> {
+    if !output.pending {
+        return Ok(true);
+    }
+    let mut done = BOOL(0);
+    unsafe {
+        // S_FALSE (still running) leaves `done` unset / S_FALSE (実行中) では `done` は設定されない
+        context
+            .GetData(
+                &output.rendered,
+                Some(&mut done as *mut BOOL as *mut c_void),
+                std::mem::size_of::<BOOL>() as u32,
+                0,
+            )
+            .map_err(|e| napi::Error::from_reason(format!("GetData failed: {:?}", e)))?;
+    }
+    if done.as_bool() {
+        output.pending = false;
+    }
+    Ok(!output.pending)
+}
+
 fn compile_shader(source: &[u8], entry: &[u8], target: &[u8]) -> napi::Result<Vec<u8>> {
     let mut shader_blob: Option<ID3DBlob> = None;
     let mut error_blob: Option<ID3DBlob> = None;
@@ -684,31 +1015,32 @@ pub fn init() -> napi::Result<D3D11Context> {
         Ok(D3D11Context {
             device,
             context,
-            staging_bgra_texture: None,
-            output_rgba_texture: None,
-            bgra_srv: None,
-            rgba_rtv: None,
+            textures: None,
             vertex_shader: None,
             pixel_shader_passthrough: None,
             pixel_shader_swizzle: None,
             swap_rb_required: false,
             channel_probe_done: false,
             sampler_state: None,
+            conversion_constants: None,
             texture_width: 0,
             texture_height: 0,
+            texture_srgb: false,
+            content_owner: None,
         })
     }
 }
 
 impl Drop for D3D11Context {
     fn drop(&mut self) {
-        // Release GPU resources in safe order: views -> textures -> shaders -> sampler
-        // GPU リソースを安全な順序で解放: ビュー -> テクスチャ -> シェーダ -> サンプラー
+        // Release GPU resources in safe order: views -> textures -> shaders -> sampler -> constants
+        // GPU リソースを安全な順序で解放: ビュー -> テクスチャ -> シェーダ -> サンプラー -> 定数
         self.reset_texture();
         self.vertex_shader = None;
         self.pixel_shader_passthrough = None;
         self.pixel_shader_swizzle = None;
         self.sampler_state = None;
+        self.conversion_constants = None;
         // device and context are dropped last by Rust's struct drop order
         // device と context は Rust の構造体ドロップ順序で最後に解放される
     }
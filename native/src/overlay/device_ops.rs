@@ -0,0 +1,271 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+use std::ffi::c_char;
+use std::sync::{Mutex, MutexGuard};
+
+use super::controller_ops::controller_hand_for;
+use super::devices::{DeviceChange, DeviceClass, DeviceTable, TrackedDevice};
+use super::errors::require_fn;
+use super::manager::OverlayManager;
+use super::types::{DeviceEvent, TrackedDeviceInfo};
+
+const SERIAL_BUFFER_SIZE: usize = 256;
+
+pub(super) fn lock_device_table(
+    devices: &Mutex<DeviceTable>,
+) -> napi::Result<MutexGuard<'_, DeviceTable>> {
+    devices
+        .lock()
+        .map_err(|_| napi::Error::from_reason("device table lock poisoned"))
+}
+
+pub(super) fn float_property(
+    system: &vr::VR_IVRSystem_FnTable,
+    index: u32,
+    prop: vr::ETrackedDeviceProperty,
+) -> Option<f32> {
+    let get_float_property_fn = system.GetFloatTrackedDeviceProperty?;
+    let mut err = vr::ETrackedPropertyError_TrackedProp_Success;
+    let value = unsafe { get_float_property_fn(index, prop, &mut err) };
+    (err == vr::ETrackedPropertyError_TrackedProp_Success).then_some(value)
+}
+
+fn bool_property(
+    system: &vr::VR_IVRSystem_FnTable,
+    index: u32,
+    prop: vr::ETrackedDeviceProperty,
+) -> Option<bool> {
+    let get_bool_property_fn = system.GetBoolTrackedDeviceProperty?;
+    let mut err = vr::ETrackedPropertyError_TrackedProp_Success;
+    let value = unsafe { get_bool_property_fn(index, prop, &mut err) };
+    (err == vr::ETrackedPropertyError_TrackedProp_Success).then_some(value)
+}
+
+fn string_property(
+    system: &vr::VR_IVRSystem_FnTable,
+    index: u32,
+    prop: vr::ETrackedDeviceProperty,
+) -> Option<String> {
+    let get_string_property_fn = system.GetStringTrackedDeviceProperty?;
+    let mut buf: [c_char; SERIAL_BUFFER_SIZE] = [0; SERIAL_BUFFER_SIZE];
+    let mut err = vr::ETrackedPropertyError_TrackedProp_Success;
+    unsafe {
+        get_string_property_fn(index, prop, buf.as_mut_ptr(), buf.len() as u32, &mut err);
+    }
+    if err != vr::ETrackedPropertyError_TrackedProp_Success {
+        return None;
+    }
+    let bytes: Vec<u8> = buf
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn device_class(class: vr::ETrackedDeviceClass) -> DeviceClass {
+    match class {
+        vr::ETrackedDeviceClass_TrackedDeviceClass_HMD => DeviceClass::Hmd,
+        vr::ETrackedDeviceClass_TrackedDeviceClass_Controller => DeviceClass::Controller,
+        vr::ETrackedDeviceClass_TrackedDeviceClass_GenericTracker => DeviceClass::Tracker,
+        vr::ETrackedDeviceClass_TrackedDeviceClass_TrackingReference => {
+            DeviceClass::TrackingReference
+        }
+        _ => DeviceClass::Other,
+    }
+}
+
+/// Read a device slot; None when nothing is connected there / デバイス番号を読み取る (未接続なら None)
+fn read_tracked_device(
+    system: &vr::VR_IVRSystem_FnTable,
+    index: u32,
+) -> napi::Result<Option<TrackedDevice>> {
+    let is_connected_fn = require_fn(system.IsTrackedDeviceConnected, "IsTrackedDeviceConnected")?;
+    let get_class_fn = require_fn(system.GetTrackedDeviceClass, "GetTrackedDeviceClass")?;
+    if !unsafe { is_connected_fn(index) } {
+        return Ok(None);
+    }
+
+    let has_battery = bool_property(
+        system,
+        index,
+        vr::ETrackedDeviceProperty_Prop_DeviceProvidesBatteryStatus_Bool,
+    )
+    .unwrap_or(false);
+    let (battery, charging) = if has_battery {
+        (
+            float_property(
+                system,
+                index,
+                vr::ETrackedDeviceProperty_Prop_DeviceBatteryPercentage_Float,
+            ),
+            bool_property(
+                system,
+                index,
+                vr::ETrackedDeviceProperty_Prop_DeviceIsCharging_Bool,
+            ),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(Some(TrackedDevice {
+        class: device_class(unsafe { get_class_fn(index) }),
+        role: controller_hand_for(system, index),
+        serial: string_property(
+            system,
+            index,
+            vr::ETrackedDeviceProperty_Prop_SerialNumber_String,
+        )
+        .unwrap_or_default(),
+        battery,
+        charging,
+    }))
+}
+
+fn refresh_device(
+    system: &vr::VR_IVRSystem_FnTable,
+    table: &mut DeviceTable,
+    index: u32,
+    timestamp: f64,
+) -> napi::Result<()> {
+    match read_tracked_device(system, index)? {
+        Some(device) => table.update(index, device, timestamp),
+        None => table.disconnect(index, timestamp),
+    }
+    Ok(())
+}
+
+/// Apply pending SteamVR device events to `table` (seeding it on first use).
+/// Returns (previous index, new index) for controllers that reconnected under a new index.
+/// Only one caller may pump system events at a time, since PollNextEvent consumes them.
+/// 保留中の SteamVR デバイスイベントを `table` に反映する (初回はスキャンで初期化)。
+/// 別の番号で再接続したコントローラーの (以前の番号, 新しい番号) を返す。
+/// PollNextEvent はイベントを消費するため、同時に呼び出せるのは一箇所のみ。
+pub(super) fn pump_device_events(
+    system: &vr::VR_IVRSystem_FnTable,
+    table: &mut DeviceTable,
+    now: f64,
+) -> napi::Result<Vec<(u32, u32)>> {
+    if !table.is_seeded() {
+        let mut devices = Vec::new();
+        for index in 0..vr::k_unMaxTrackedDeviceCount {
+            if let Some(device) = read_tracked_device(system, index)? {
+                devices.push((index, device));
+            }
+        }
+        table.seed(devices);
+    }
+
+    let poll_next_event_fn = require_fn(system.PollNextEvent, "PollNextEvent")?;
+    let mut remaps = Vec::new();
+    let mut event: vr::VREvent_t = unsafe { std::mem::zeroed() };
+    while unsafe { poll_next_event_fn(&mut event, std::mem::size_of::<vr::VREvent_t>() as u32) } {
+        let index = event.trackedDeviceIndex;
+        let timestamp = now - event.eventAgeSeconds as f64;
+        match event.eventType as vr::EVREventType {
+            vr::EVREventType_VREvent_TrackedDeviceActivated => {
+                if let Some(device) = read_tracked_device(system, index)? {
+                    if let Some(previous) = table.connect(index, device, timestamp) {
+                        remaps.push((previous, index));
+                    }
+                }
+            }
+            vr::EVREventType_VREvent_TrackedDeviceDeactivated => {
+                table.disconnect(index, timestamp);
+            }
+            vr::EVREventType_VREvent_TrackedDeviceRoleChanged => {
+                // Not tied to one device; swapping hands changes two at once
+                // 特定のデバイスに紐付かない (左右の入れ替えでは二つ同時に変わる)
+                for index in table.indices() {
+                    refresh_device(system, table, index, timestamp)?;
+                }
+            }
+            vr::EVREventType_VREvent_TrackedDeviceUpdated => {
+                refresh_device(system, table, index, timestamp)?;
+            }
+            vr::EVREventType_VREvent_PropertyChanged => {
+                let prop = unsafe { event.data.property.prop };
+                if prop == vr::ETrackedDeviceProperty_Prop_DeviceBatteryPercentage_Float
+                    || prop == vr::ETrackedDeviceProperty_Prop_DeviceIsCharging_Bool
+                    || prop == vr::ETrackedDeviceProperty_Prop_ControllerRoleHint_Int32
+                {
+                    refresh_device(system, table, index, timestamp)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(remaps)
+}
+
+fn device_info(index: u32, device: &TrackedDevice) -> TrackedDeviceInfo {
+    TrackedDeviceInfo {
+        index,
+        deviceClass: device.class.as_str().to_string(),
+        role: device.role.map(|hand| hand.as_str().to_string()),
+        serial: device.serial.clone(),
+        battery: device.battery.map(|level| level as f64),
+        charging: device.charging,
+    }
+}
+
+pub(super) fn device_event(change: DeviceChange) -> DeviceEvent {
+    DeviceEvent {
+        kind: change.kind.as_str().to_string(),
+        timestamp: change.timestamp,
+        index: change.index,
+        previousIndex: change.previous_index,
+        device: device_info(change.index, &change.device),
+    }
+}
+
+impl OverlayManager {
+    /// Pump device events unless the input thread owns them, and move per-controller
+    /// state of reconnected controllers to their new index.
+    /// 入力スレッドが担当していなければデバイスイベントを処理し、
+    /// 再接続したコントローラーの状態を新しい番号に移す。
+    pub(super) fn refresh_devices(&self) -> napi::Result<()> {
+        if self.input_thread_running()? {
+            return Ok(());
+        }
+        let system = self.system()?;
+        let remaps = pump_device_events(system, &mut *self.lock_devices()?, self.now_seconds())?;
+        let state = self.shared_input_state();
+        for (previous, index) in remaps {
+            state.remap_controller(previous, index)?;
+        }
+        Ok(())
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Connection, role and battery changes since the last call.
+    /// While the input thread runs they are delivered as "device" events instead.
+    /// Only the latest 64 are kept between calls.
+    /// 前回の呼び出し以降の接続・ロール・バッテリーの変化。
+    /// 入力スレッドの実行中は代わりに "device" イベントとして届く。
+    /// 呼び出しの間に保持するのは直近の 64 件まで。
+    #[napi]
+    pub fn poll_device_events(&self) -> napi::Result<Vec<DeviceEvent>> {
+        self.refresh_devices()?;
+        Ok(self
+            .lock_devices()?
+            .drain_changes()
+            .into_iter()
+            .map(device_event)
+            .collect())
+    }
+
+    /// Currently connected tracked devices / 現在接続中のトラッキングデバイス
+    #[napi]
+    pub fn get_tracked_devices(&self) -> napi::Result<Vec<TrackedDeviceInfo>> {
+        self.refresh_devices()?;
+        Ok(self
+            .lock_devices()?
+            .devices()
+            .map(|(index, device)| device_info(index, device))
+            .collect())
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::hand::Hand;
+
+/// Battery changes smaller than this are not reported / これ未満のバッテリー変化は通知しない
+const BATTERY_REPORT_STEP: f32 = 0.01;
+/// Undrained changes kept when nobody polls; the oldest are dropped first
+/// ポーリングされない場合に保持する未取得の変化の数 (古いものから破棄)
+const MAX_PENDING_CHANGES: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum DeviceClass {
+    Hmd,
+    Controller,
+    Tracker,
+    TrackingReference,
+    Other,
+}
+
+impl DeviceClass {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            DeviceClass::Hmd => "hmd",
+            DeviceClass::Controller => "controller",
+            DeviceClass::Tracker => "tracker",
+            DeviceClass::TrackingReference => "trackingReference",
+            DeviceClass::Other => "other",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct TrackedDevice {
+    pub class: DeviceClass,
+    pub role: Option<Hand>,
+    /// Empty when the driver does not report one / ドライバーが報告しない場合は空
+    pub serial: String,
+    /// 0..1, None when the device has no battery / 0..1 (バッテリー非搭載なら None)
+    pub battery: Option<f32>,
+    pub charging: Option<bool>,
+}
+
+impl TrackedDevice {
+    fn battery_changed(&self, previous: &TrackedDevice) -> bool {
+        let level_changed = match (self.battery, previous.battery) {
+            (Some(level), Some(previous)) => (level - previous).abs() >= BATTERY_REPORT_STEP,
+            (level, previous) => level.is_some() != previous.is_some(),
+        };
+        level_changed || self.charging != previous.charging
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum DeviceChangeKind {
+    Connected,
+    Disconnected,
+    RoleChanged,
+    BatteryChanged,
+}
+
+impl DeviceChangeKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            DeviceChangeKind::Connected => "connected",
+            DeviceChangeKind::Disconnected => "disconnected",
+            DeviceChangeKind::RoleChanged => "roleChanged",
+            DeviceChangeKind::BatteryChanged => "batteryChanged",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct DeviceChange {
+    pub kind: DeviceChangeKind,
+    pub index: u32,
+    /// Index the same device (by serial) had before it reconnected
+    /// 同じデバイス (シリアル一致) が再接続前に使っていた番号
+    pub previous_index: Option<u32>,
+    pub device: TrackedDevice,
+    pub timestamp: f64,
+}
+
+/// Connected devices by index, kept current from SteamVR device events.
+/// SteamVR のデバイスイベントで更新する、番号ごとの接続中デバイス一覧
+#[derive(Default)]
+pub(super) struct DeviceTable {
+    seeded: bool,
+    devices: BTreeMap<u32, TrackedDevice>,
+    /// Last index of each disconnected serial / 切断されたシリアルごとの最後の番号
+    departed: HashMap<String, u32>,
+    changes: VecDeque<DeviceChange>,
+}
+
+impl DeviceTable {
+    pub(super) fn is_seeded(&self) -> bool {
+        self.seeded
+    }
+
+    /// Initial scan; devices already present are not reported as changes.
+    /// 初回スキャン (既に接続済みのデバイスは変更として通知しない)
+    pub(super) fn seed(&mut self, devices: Vec<(u32, TrackedDevice)>) {
+        self.devices = devices.into_iter().collect();
+        self.seeded = true;
+    }
+
+    fn push(
+        &mut self,
+        kind: DeviceChangeKind,
+        index: u32,
+        previous_index: Option<u32>,
+        device: TrackedDevice,
+        timestamp: f64,
+    ) {
+        if self.changes.len() >= MAX_PENDING_CHANGES {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(DeviceChange {
+            kind,
+            index,
+            previous_index,
+            device,
+            timestamp,
+        });
+    }
+
+    /// Record an activated device. Returns the index it had before if the same
+    /// serial was seen elsewhere, so per-controller state can be moved over.
+    /// 接続されたデバイスを記録する。同じシリアルが別の番号にあった場合はその番号を返し、
+    /// コントローラーごとの状態を引き継げるようにする。
+    pub(super) fn connect(
+        &mut self,
+        index: u32,
+        device: TrackedDevice,
+        timestamp: f64,
+    ) -> Option<u32> {
+        if self
+            .devices
+            .get(&index)
+            .is_some_and(|existing| existing.serial == device.serial)
+        {
+            self.update(index, device, timestamp);
+            return None;
+        }
+        if let Some(replaced) = self.devices.remove(&index) {
+            // Slot reused without a deactivation event / 切断イベント無しで番号が再利用された
+            self.disconnected(index, replaced, timestamp);
+        }
+
+        let mut previous_index = None;
+        if !device.serial.is_empty() {
+            previous_index = self.departed.remove(&device.serial);
+            // A missed deactivation leaves the device listed at its old index
+            // 切断イベントを取りこぼすと古い番号に残ったままになる
+            let stale = self
+                .devices
+                .iter()
+                .find(|(_, existing)| existing.serial == device.serial)
+                .map(|(stale_index, _)| *stale_index);
+            if let Some(stale_index) = stale {
+                if let Some(stale_device) = self.devices.remove(&stale_index) {
+                    self.push(
+                        DeviceChangeKind::Disconnected,
+                        stale_index,
+                        None,
+                        stale_device,
+                        timestamp,
+                    );
+                }
+                previous_index = Some(stale_index);
+            }
+        }
+        let previous_index = previous_index.filter(|previous| *previous != index);
+
+        self.devices.insert(index, device.clone());
+        self.push(
+            DeviceChangeKind::Connected,
+            index,
+            previous_index,
+            device,
+            timestamp,
+        );
+        previous_index
+    }
+
+    fn disconnected(&mut self, index: u32, device: TrackedDevice, timestamp: f64) {
+        if !device.serial.is_empty() {
+            self.departed.insert(device.serial.clone(), index);
+        }
+        self.push(
+            DeviceChangeKind::Disconnected,
+            index,
+            None,
+            device,
+            timestamp,
+        );
+    }
+
+    pub(super) fn disconnect(&mut self, index: u32, timestamp: f64) {
+        if let Some(device) = self.devices.remove(&index) {
+            self.disconnected(index, device, timestamp);
+        }
+    }
+
+    /// Refresh role and battery of a connected device / 接続中デバイスのロールとバッテリーを更新
+    pub(super) fn update(&mut self, index: u32, device: TrackedDevice, timestamp: f64) {
+        let Some(existing) = self.devices.get(&index) else {
+            return;
+        };
+        let role_changed = existing.role != device.role;
+        let battery_changed = device.battery_changed(existing);
+        if !role_changed && !battery_changed && existing.class == device.class {
+            return;
+        }
+        // Keep the last reported level so small drifts add up to a report
+        // 小さな変化が積み重なって通知されるよう、通知済みの値を保持する
+        let stored = if battery_changed {
+            device.clone()
+        } else {
+            TrackedDevice {
+                battery: existing.battery,
+                charging: existing.charging,
+                ..device.clone()
+            }
+        };
+        self.devices.insert(index, stored.clone());
+        if role_changed {
+            self.push(
+                DeviceChangeKind::RoleChanged,
+                index,
+                None,
+                stored.clone(),
+                timestamp,
+            );
+        }
+        if battery_changed {
+            self.push(
+                DeviceChangeKind::BatteryChanged,
+                index,
+                None,
+                stored,
+                timestamp,
+            );
+        }
+    }
+
+    pub(super) fn indices(&self) -> Vec<u32> {
+        self.devices.keys().copied().collect()
+    }
+
+    pub(super) fn devices(&self) -> impl Iterator<Item = (u32, &TrackedDevice)> {
+        self.devices.iter().map(|(index, device)| (*index, device))
+    }
+
+    pub(super) fn controller_ids(&self) -> Vec<u32> {
+        self.devices()
+            .filter(|(_, device)| device.class == DeviceClass::Controller)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub(super) fn drain_changes(&mut self) -> Vec<DeviceChange> {
+        self.changes.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(serial: &str, role: Option<Hand>) -> TrackedDevice {
+        TrackedDevice {
+            class: DeviceClass::Controller,
+            role,
+            serial: serial.to_string(),
+            battery: Some(0.8),
+            charging: Some(false),
+        }
+    }
+
+    fn kinds(changes: &[DeviceChange]) -> Vec<(DeviceChangeKind, u32, Option<u32>)> {
+        changes
+            .iter()
+            .map(|change| (change.kind, change.index, change.previous_index))
+            .collect()
+    }
+
+    fn seeded_pair() -> DeviceTable {
+        let mut table = DeviceTable::default();
+        table.seed(vec![
+            (1, controller("LEFT", Some(Hand::Left))),
+            (2, controller("RIGHT", Some(Hand::Right))),
+        ]);
+        table
+    }
+
+    #[test]
+    fn seeded_devices_are_not_reported() {
+        let mut table = seeded_pair();
+        assert!(table.is_seeded());
+        assert_eq!(table.controller_ids(), vec![1, 2]);
+        assert!(table.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn reconnect_under_new_index_returns_previous_index() {
+        let mut table = seeded_pair();
+        table.disconnect(1, 1.0);
+        assert_eq!(
+            table.connect(3, controller("LEFT", Some(Hand::Left)), 2.0),
+            Some(1)
+        );
+        assert_eq!(table.indices(), vec![2, 3]);
+        assert_eq!(
+            kinds(&table.drain_changes()),
+            vec![
+                (DeviceChangeKind::Disconnected, 1, None),
+                (DeviceChangeKind::Connected, 3, Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reconnect_without_deactivation_drops_stale_index() {
+        let mut table = seeded_pair();
+        assert_eq!(
+            table.connect(4, controller("RIGHT", Some(Hand::Right)), 1.0),
+            Some(2)
+        );
+        assert_eq!(table.indices(), vec![1, 4]);
+        assert_eq!(
+            kinds(&table.drain_changes()),
+            vec![
+                (DeviceChangeKind::Disconnected, 2, None),
+                (DeviceChangeKind::Connected, 4, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reconnect_under_same_index_is_not_a_move() {
+        let mut table = seeded_pair();
+        table.disconnect(1, 1.0);
+        assert_eq!(
+            table.connect(1, controller("LEFT", Some(Hand::Left)), 2.0),
+            None
+        );
+        assert_eq!(
+            kinds(&table.drain_changes()),
+            vec![
+                (DeviceChangeKind::Disconnected, 1, None),
+                (DeviceChangeKind::Connected, 1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn hand_role_swap_reports_both_controllers() {
+        let mut table = seeded_pair();
+        table.update(1, controller("LEFT", Some(Hand::Right)), 1.0);
+        table.update(2, controller("RIGHT", Some(Hand::Left)), 1.0);
+
+        let changes = table.drain_changes();
+        assert_eq!(
+            kinds(&changes),
+            vec![
+                (DeviceChangeKind::RoleChanged, 1, None),
+                (DeviceChangeKind::RoleChanged, 2, None),
+            ]
+        );
+        assert_eq!(changes[0].device.role, Some(Hand::Right));
+        assert_eq!(changes[1].device.role, Some(Hand::Left));
+        let roles: Vec<_> = table.devices().map(|(_, device)| device.role).collect();
+        assert_eq!(roles, vec![Some(Hand::Right), Some(Hand::Left)]);
+    }
+
+    #[test]
+    fn small_battery_drift_adds_up_to_one_report() {
+        let mut table = seeded_pair();
+        let drifted = |battery| TrackedDevice {
+            battery: Some(battery),
+            ..controller("LEFT", Some(Hand::Left))
+        };
+        table.update(1, drifted(0.795), 1.0);
+        assert!(table.drain_changes().is_empty());
+        table.update(1, drifted(0.789), 2.0);
+        assert_eq!(
+            kinds(&table.drain_changes()),
+            vec![(DeviceChangeKind::BatteryChanged, 1, None)]
+        );
+    }
+
+    #[test]
+    fn undrained_changes_keep_only_the_latest() {
+        let mut table = seeded_pair();
+        let drifted = |battery| TrackedDevice {
+            battery: Some(battery),
+            ..controller("LEFT", Some(Hand::Left))
+        };
+        let updates = MAX_PENDING_CHANGES + 10;
+        for step in 0..updates {
+            let battery = if step % 2 == 0 { 0.5 } else { 0.8 };
+            table.update(1, drifted(battery), step as f64);
+        }
+
+        let changes = table.drain_changes();
+        assert_eq!(changes.len(), MAX_PENDING_CHANGES);
+        assert_eq!(changes[0].timestamp, 10.0);
+        assert_eq!(changes.last().unwrap().timestamp, (updates - 1) as f64);
+        assert!(table.drain_changes().is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+use super::pixels::{AlphaConversion, PixelFormat};
+
+/// Frames are compared in square tiles of this many pixels / フレームはこのピクセル数の正方形タイル単位で比較する
+pub(super) const TILE_SIZE: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct DirtyRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl DirtyRect {
+    fn area(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct FrameDiff {
+    pub rects: Vec<DirtyRect>,
+    pub total_pixels: usize,
+}
+
+impl FrameDiff {
+    pub(super) fn full(width: usize, height: usize) -> Self {
+        Self {
+            rects: vec![DirtyRect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }],
+            total_pixels: width * height,
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Share of the frame covered by dirty rects (0..1) / 変更矩形が占めるフレームの割合 (0..1)
+    pub(super) fn dirty_ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        let dirty: usize = self.rects.iter().map(DirtyRect::area).sum();
+        dirty as f64 / self.total_pixels as f64
+    }
+}
+
+/// Previous frame of an overlay, used to find the tiles that changed.
+/// 変更されたタイルを求めるための、オーバーレイの直前のフレーム
+#[derive(Default)]
+pub(super) struct FrameHistory {
+    width: usize,
+    height: usize,
+    /// Packed copy of the last source frame / 直前のソースフレームの詰めたコピー
+    pixels: Vec<u8>,
+    /// Frame converted for upload, updated only where dirty / アップロード用に変換したフレーム (変更箇所のみ更新)
+    pub converted: Vec<u8>,
+    /// Source format and alpha conversion of the remembered frame / 記録したフレームのソース形式とアルファ変換
+    pub conversion: Option<(PixelFormat, AlphaConversion)>,
+}
+
+impl FrameHistory {
+    /// Forget the previous frame so the next diff covers everything / 直前のフレームを破棄し、次の差分を全体にする
+    pub(super) fn invalidate(&mut self) {
+        self.width = 0;
+        self.height = 0;
+        self.pixels.clear();
+        self.converted.clear();
+    }
+
+    /// Compare `src` (32-bit pixels, `row_pitch` bytes per row) with the previous frame,
+    /// remember it, and return the changed area as merged tile rectangles.
+    /// `src` (32 ビットピクセル、1 行 `row_pitch` バイト) を直前のフレームと比較して記録し、
+    /// 変更された領域をタイルをまとめた矩形で返す。
+    pub(super) fn diff(
+        &mut self,
+        src: &[u8],
+        width: usize,
+        height: usize,
+        row_pitch: usize,
+    ) -> FrameDiff {
+        let row_bytes = width * 4;
+        if width != self.width || height != self.height || self.pixels.is_empty() {
+            self.invalidate();
+            self.width = width;
+            self.height = height;
+            self.pixels.reserve(row_bytes * height);
+            for row in src.chunks(row_pitch).take(height) {
+                self.pixels.extend_from_slice(&row[..row_bytes]);
+            }
+            return FrameDiff::full(width, height);
+        }
+
+        let columns = width.div_ceil(TILE_SIZE);
+        let mut rects: Vec<DirtyRect> = Vec::new();
+        // Rects still growing downward, by column range / 下方向に伸ばしている矩形 (列範囲ごと)
+        let mut open: Vec<usize> = Vec::new();
+        for tile_y in 0..height.div_ceil(TILE_SIZE) {
+            let y0 = tile_y * TILE_SIZE;
+            let y1 = (y0 + TILE_SIZE).min(height);
+            let mut dirty = vec![false; columns];
+            for (tile_x, dirty) in dirty.iter_mut().enumerate() {
+                let x0 = tile_x * TILE_SIZE * 4;
+                let x1 = ((tile_x + 1) * TILE_SIZE).min(width) * 4;
+                for y in y0..y1 {
+                    let current = &src[y * row_pitch + x0..y * row_pitch + x1];
+                    let previous = &mut self.pixels[y * row_bytes + x0..y * row_bytes + x1];
+                    if current != previous {
+                        previous.copy_from_slice(current);
+                        *dirty = true;
+                    }
+                }
+            }
+
+            // Runs of dirty tiles in this row / この行で連続する変更タイル
+            let mut runs = Vec::new();
+            let mut tile_x = 0;
+            while tile_x < columns {
+                if !dirty[tile_x] {
+                    tile_x += 1;
+                    continue;
+                }
+                let start = tile_x;
+                while tile_x < columns && dirty[tile_x] {
+                    tile_x += 1;
+                }
+                let x = start * TILE_SIZE;
+                runs.push((x, (tile_x * TILE_SIZE).min(width) - x));
+            }
+
+            // Extend a rect from the row above when the run spans the same columns
+            // 上の行の矩形と同じ列範囲なら、その矩形を下に伸ばす
+            let mut still_open = Vec::with_capacity(runs.len());
+            for (x, run_width) in runs {
+                let above = open
+                    .iter()
+                    .copied()
+                    .find(|&index| rects[index].x == x && rects[index].width == run_width);
+                match above {
+                    Some(index) => {
+                        rects[index].height = y1 - rects[index].y;
+                        still_open.push(index);
+                    }
+                    None => {
+                        still_open.push(rects.len());
+                        rects.push(DirtyRect {
+                            x,
+                            y: y0,
+                            width: run_width,
+                            height: y1 - y0,
+                        });
+                    }
+                }
+            }
+            open = still_open;
+        }
+
+        FrameDiff {
+            rects,
+            total_pixels: width * height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 70x40 leaves a 6-pixel column and an 8-pixel row of partial tiles,
+    // and each row carries 24 bytes of padding
+    // 70x40 は幅 6 ピクセルの列と高さ 8 ピクセルの行が端数タイルになり、各行に 24 バイトの余白がある
+    const WIDTH: usize = 70;
+    const HEIGHT: usize = 40;
+    const PITCH: usize = WIDTH * 4 + 24;
+
+    fn frame() -> Vec<u8> {
+        let mut src = vec![0u8; PITCH * HEIGHT];
+        for row in src.chunks_mut(PITCH) {
+            row[WIDTH * 4..].fill(0xAA);
+        }
+        src
+    }
+
+    fn paint(src: &mut [u8], x: usize, y: usize) {
+        src[y * PITCH + x * 4..][..4].copy_from_slice(&[1, 2, 3, 4]);
+    }
+
+    fn rect(x: usize, y: usize, width: usize, height: usize) -> DirtyRect {
+        DirtyRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn seeded() -> FrameHistory {
+        let mut history = FrameHistory::default();
+        history.diff(&frame(), WIDTH, HEIGHT, PITCH);
+        history
+    }
+
+    #[test]
+    fn first_frame_and_size_changes_are_full() {
+        let mut history = FrameHistory::default();
+        let first = history.diff(&frame(), WIDTH, HEIGHT, PITCH);
+        assert_eq!(first.rects, vec![rect(0, 0, WIDTH, HEIGHT)]);
+        assert_eq!(first.dirty_ratio(), 1.0);
+        assert!(history.diff(&frame(), WIDTH, HEIGHT, PITCH).is_empty());
+
+        // Same bytes read as a narrower frame / 同じバイト列を幅の狭いフレームとして読む
+        let narrower = history.diff(&frame(), WIDTH - 6, HEIGHT, PITCH);
+        assert_eq!(narrower.rects, vec![rect(0, 0, WIDTH - 6, HEIGHT)]);
+
+        history.invalidate();
+        let mut src = frame();
+        paint(&mut src, 0, 0);
+        assert_eq!(
+            history.diff(&src, WIDTH, HEIGHT, PITCH).rects,
+            vec![rect(0, 0, WIDTH, HEIGHT)]
+        );
+    }
+
+    #[test]
+    fn edge_tiles_are_clipped_to_the_frame() {
+        let mut history = seeded();
+        let mut src = frame();
+        paint(&mut src, WIDTH - 1, HEIGHT - 1);
+        let diff = history.diff(&src, WIDTH, HEIGHT, PITCH);
+        assert_eq!(diff.rects, vec![rect(64, 32, 6, 8)]);
+        assert_eq!(diff.total_pixels, WIDTH * HEIGHT);
+        assert_eq!(diff.dirty_ratio(), 48.0 / (WIDTH * HEIGHT) as f64);
+    }
+
+    #[test]
+    fn row_padding_is_ignored() {
+        let mut history = seeded();
+        let mut src = frame();
+        for row in src.chunks_mut(PITCH) {
+            row[WIDTH * 4..].fill(0x55);
+        }
+        assert!(history.diff(&src, WIDTH, HEIGHT, PITCH).is_empty());
+
+        // The first pixel of the next row sits right after the padding
+        // 次の行の先頭ピクセルは余白の直後にある
+        paint(&mut src, 0, 33);
+        assert_eq!(
+            history.diff(&src, WIDTH, HEIGHT, PITCH).rects,
+            vec![rect(0, 32, 32, 8)]
+        );
+    }
+
+    #[test]
+    fn matching_runs_merge_down_across_rows() {
+        let mut history = seeded();
+        let mut src = frame();
+        for (x, y) in [(0, 0), (33, 0), (0, 33), (63, 39)] {
+            paint(&mut src, x, y);
+        }
+        assert_eq!(
+            history.diff(&src, WIDTH, HEIGHT, PITCH).rects,
+            vec![rect(0, 0, 64, HEIGHT)]
+        );
+        // Unchanged bytes produce nothing the next time / 変化の無いバイト列では何も返さない
+        assert!(history.diff(&src, WIDTH, HEIGHT, PITCH).is_empty());
+    }
+
+    #[test]
+    fn differing_runs_start_new_rects() {
+        let mut history = seeded();
+        let mut src = frame();
+        for (x, y) in [(0, 0), (65, 0), (0, 33), (40, 33)] {
+            paint(&mut src, x, y);
+        }
+        assert_eq!(
+            history.diff(&src, WIDTH, HEIGHT, PITCH).rects,
+            vec![rect(0, 0, 32, 32), rect(64, 0, 6, 32), rect(0, 32, 64, 8)]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct GazeConfig {
+    pub enabled: bool,
+    pub dwell_seconds: f64,
+    /// Looking away for less than this keeps the dwell progress (head tremor)
+    /// これ未満の視線の外れは進捗を維持する (頭の揺れ対策)
+    pub exit_grace_seconds: f64,
+}
+
+impl Default for GazeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dwell_seconds: 0.8,
+            exit_grace_seconds: 0.15,
+        }
+    }
+}
+
+/// Key area in overlay UV space / オーバーレイ UV 空間でのキー領域
+#[derive(Clone, Debug)]
+pub(super) struct DwellRegion {
+    pub id: String,
+    pub u_min: f64,
+    pub v_min: f64,
+    pub u_max: f64,
+    pub v_max: f64,
+    /// Overrides the configured dwell time / 設定の注視時間を上書き
+    pub dwell_seconds: Option<f64>,
+}
+
+impl DwellRegion {
+    fn contains(&self, [u, v]: [f64; 2]) -> bool {
+        u >= self.u_min && u <= self.u_max && v >= self.v_min && v <= self.v_max
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct DwellStatus {
+    pub region: Option<String>,
+    /// 0..1 toward a click on `region` / `region` のクリックまでの進捗 (0..1)
+    pub progress: f64,
+    pub clicked_at: Option<f64>,
+}
+
+struct DwellTarget {
+    overlay: u64,
+    region: String,
+    dwell_seconds: f64,
+    entered_at: f64,
+    left_at: Option<f64>,
+    /// Already clicked; the gaze must leave the region to re-arm / クリック済み (領域を出るまで再発火しない)
+    fired: bool,
+}
+
+#[derive(Default)]
+pub(super) struct DwellTimer {
+    target: Option<DwellTarget>,
+}
+
+impl DwellTimer {
+    /// Feed the gazed region (None when the gaze is off every region) at `now`.
+    /// `now` 時点で注視している領域を入力 (どの領域にも無い場合は None)
+    pub(super) fn update(
+        &mut self,
+        overlay: u64,
+        region: Option<&DwellRegion>,
+        now: f64,
+        config: &GazeConfig,
+    ) -> DwellStatus {
+        match (region, self.target.as_mut()) {
+            (Some(region), Some(target))
+                if target.overlay == overlay && target.region == region.id =>
+            {
+                // Time spent looking away does not count / 視線が外れていた時間は数えない
+                if let Some(left_at) = target.left_at.take() {
+                    target.entered_at += now - left_at;
+                }
+            }
+            (Some(region), _) => {
+                self.target = Some(DwellTarget {
+                    overlay,
+                    region: region.id.clone(),
+                    dwell_seconds: region.dwell_seconds.unwrap_or(config.dwell_seconds),
+                    entered_at: now,
+                    left_at: None,
+                    fired: false,
+                });
+            }
+            (None, Some(target)) => {
+                let left_at = *target.left_at.get_or_insert(now);
+                if now - left_at > config.exit_grace_seconds {
+                    self.target = None;
+                }
+            }
+            (None, None) => {}
+        }
+
+        let Some(target) = self.target.as_mut() else {
+            return DwellStatus::default();
+        };
+        let mut status = DwellStatus {
+            region: Some(target.region.clone()),
+            ..DwellStatus::default()
+        };
+        if target.fired {
+            return status;
+        }
+
+        // Progress pauses during the grace period / 猶予期間中は進捗を止める
+        let dwelled = target.left_at.unwrap_or(now) - target.entered_at;
+        status.progress = if target.dwell_seconds > 0.0 {
+            (dwelled / target.dwell_seconds).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        if status.progress >= 1.0 && target.left_at.is_none() {
+            target.fired = true;
+            status.progress = 0.0;
+            status.clicked_at = Some(target.entered_at + target.dwell_seconds);
+        }
+        status
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.target = None;
+    }
+}
+
+#[derive(Default)]
+pub(super) struct GazeState {
+    pub config: GazeConfig,
+    /// Key regions per overlay handle / オーバーレイハンドルごとのキー領域
+    pub regions: HashMap<u64, Vec<DwellRegion>>,
+    pub dwell: DwellTimer,
+}
+
+impl GazeState {
+    /// Run the dwell timer for a gaze hit at `uv` on `overlay` (None = no hit).
+    /// `overlay` 上の `uv` への注視で注視タイマーを進める (None はヒット無し)
+    pub(super) fn update(&mut self, overlay: u64, uv: Option<[f64; 2]>, now: f64) -> DwellStatus {
+        let config = self.config;
+        let region = uv.and_then(|uv| {
+            self.regions
+                .get(&overlay)
+                .and_then(|regions| regions.iter().find(|region| region.contains(uv)))
+        });
+        self.dwell.update(overlay, region, now, &config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(id: &str) -> DwellRegion {
+        DwellRegion {
+            id: id.to_string(),
+            u_min: 0.0,
+            v_min: 0.0,
+            u_max: 0.5,
+            v_max: 0.5,
+            dwell_seconds: None,
+        }
+    }
+
+    fn config() -> GazeConfig {
+        GazeConfig {
+            enabled: true,
+            dwell_seconds: 1.0,
+            exit_grace_seconds: 0.2,
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn progress_grows_over_time_until_click() {
+        let key = region("a");
+        let mut timer = DwellTimer::default();
+        let config = config();
+        assert_eq!(timer.update(1, Some(&key), 0.0, &config).progress, 0.0);
+        assert!(close(
+            timer.update(1, Some(&key), 0.25, &config).progress,
+            0.25
+        ));
+        let status = timer.update(1, Some(&key), 0.75, &config);
+        assert!(close(status.progress, 0.75));
+        assert_eq!(status.region.as_deref(), Some("a"));
+        assert_eq!(status.clicked_at, None);
+
+        let status = timer.update(1, Some(&key), 1.1, &config);
+        assert_eq!(status.clicked_at, Some(1.0));
+        assert_eq!(status.progress, 0.0);
+    }
+
+    #[test]
+    fn region_dwell_time_overrides_config() {
+        let key = DwellRegion {
+            dwell_seconds: Some(0.5),
+            ..region("a")
+        };
+        let mut timer = DwellTimer::default();
+        timer.update(1, Some(&key), 0.0, &config());
+        assert_eq!(
+            timer.update(1, Some(&key), 0.6, &config()).clicked_at,
+            Some(0.5)
+        );
+    }
+
+    #[test]
+    fn reentering_within_grace_keeps_progress() {
+        let key = region("a");
+        let mut timer = DwellTimer::default();
+        let config = config();
+        timer.update(1, Some(&key), 0.0, &config);
+        timer.update(1, Some(&key), 0.5, &config);
+
+        // Paused while away / 外れている間は止まる
+        let status = timer.update(1, None, 0.6, &config);
+        assert_eq!(status.region.as_deref(), Some("a"));
+        assert!(close(status.progress, 0.6));
+        assert!(close(timer.update(1, None, 0.7, &config).progress, 0.6));
+
+        let status = timer.update(1, Some(&key), 0.75, &config);
+        assert!(close(status.progress, 0.6));
+        assert!(close(
+            timer.update(1, Some(&key), 0.9, &config).progress,
+            0.75
+        ));
+        assert_eq!(
+            timer.update(1, Some(&key), 1.2, &config).clicked_at,
+            Some(1.15)
+        );
+    }
+
+    #[test]
+    fn leaving_past_grace_restarts_dwell() {
+        let key = region("a");
+        let mut timer = DwellTimer::default();
+        let config = config();
+        timer.update(1, Some(&key), 0.0, &config);
+        timer.update(1, Some(&key), 0.5, &config);
+        timer.update(1, None, 0.6, &config);
+        assert_eq!(timer.update(1, None, 0.9, &config), DwellStatus::default());
+
+        assert_eq!(timer.update(1, Some(&key), 1.0, &config).progress, 0.0);
+        assert!(close(
+            timer.update(1, Some(&key), 1.5, &config).progress,
+            0.5
+        ));
+    }
+
+    #[test]
+    fn moving_to_another_region_restarts_dwell() {
+        let mut timer = DwellTimer::default();
+        let config = config();
+        timer.update(1, Some(&region("a")), 0.0, &config);
+        timer.update(1, Some(&region("a")), 0.5, &config);
+        let status = timer.update(1, Some(&region("b")), 0.6, &config);
+        assert_eq!(status.region.as_deref(), Some("b"));
+        assert_eq!(status.progress, 0.0);
+    }
+
+    #[test]
+    fn one_click_per_dwell() {
+        let key = region("a");
+        let mut timer = DwellTimer::default();
+        let config = config();
+        let clicks = (0..=40)
+            .filter_map(|step| {
+                timer
+                    .update(1, Some(&key), step as f64 * 0.1, &config)
+                    .clicked_at
+            })
+            .count();
+        assert_eq!(clicks, 1);
+
+        // Re-armed only after leaving for longer than the grace period
+        // 猶予期間より長く外れてから再び有効になる
+        timer.update(1, None, 4.1, &config);
+        timer.update(1, None, 4.4, &config);
+        timer.update(1, Some(&key), 4.5, &config);
+        assert_eq!(
+            timer.update(1, Some(&key), 5.6, &config).clicked_at,
+            Some(5.5)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::constants::HMD_DEVICE_INDEX;
+use super::controller_ops::DevicePose;
+use super::gaze::{DwellRegion, DwellStatus};
+use super::handles::{overlay_handle, OverlayHandle};
+use super::manager::OverlayManager;
+use super::math::ray_from_matrix34;
+use super::overlay_ops::intersect_overlay;
+use super::smoothing::SmoothingState;
+use super::smoothing_ops::smooth_hit;
+use super::trigger::TriggerEventKind;
+use super::types::{GazeRegion, GazeSettings, GazeUpdate, IntersectionResult, TriggerEvent};
+
+/// Smoothed hit of the head ray on `handle`; the filter restarts after a miss.
+/// 頭部のレイと `handle` の交点 (平滑化済み)。外れた後はフィルタを初期化する
+pub(super) fn gaze_hit(
+    overlay: &vr::VR_IVROverlay_FnTable,
+    handle: OverlayHandle,
+    hmd_pose: Option<DevicePose>,
+    smoothing: &mut SmoothingState,
+) -> napi::Result<Option<IntersectionResult>> {
+    let key = (HMD_DEVICE_INDEX, handle.as_u64());
+    let raw = match &hmd_pose {
+        Some(pose) => {
+            let (origin, direction) = ray_from_matrix34(&pose.matrix);
+            intersect_overlay(
+                overlay,
+                handle,
+                origin.map(|v| v as f32),
+                direction.map(|v| v as f32),
+            )?
+        }
+        None => None,
+    };
+    match (raw, hmd_pose) {
+        (Some(raw), Some(pose)) => Ok(Some(smooth_hit(smoothing, key, &raw, pose.timestamp))),
+        _ => {
+            smoothing.stabilizers.remove(&key);
+            Ok(None)
+        }
+    }
+}
+
+/// A dwell click is reported like a controller trigger click on the HMD index.
+/// 注視クリックは HMD の番号でのトリガークリックとして通知する
+pub(super) fn gaze_click_events(clicked_at: f64) -> Vec<TriggerEvent> {
+    [
+        (TriggerEventKind::Pressed, 1.0),
+        (TriggerEventKind::Released, 0.0),
+    ]
+    .into_iter()
+    .map(|(kind, value)| TriggerEvent {
+        kind: kind.as_str().to_string(),
+        controllerIndex: HMD_DEVICE_INDEX,
+        timestamp: clicked_at,
+        value,
+    })
+    .collect()
+}
+
+pub(super) fn gaze_update(
+    status: DwellStatus,
+    hit: Option<IntersectionResult>,
+    timestamp: f64,
+) -> GazeUpdate {
+    GazeUpdate {
+        timestamp,
+        hit,
+        regionId: status.region,
+        progress: status.progress,
+        clicks: status.clicked_at.map(gaze_click_events).unwrap_or_default(),
+    }
+}
+
+fn seconds(name: &str, value: Option<f64>, current: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(napi::Error::from_reason(format!(
+            "{name} must be a non-negative number"
+        ))),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+fn dwell_region(region: GazeRegion) -> napi::Result<DwellRegion> {
+    let bounds = [region.u, region.v, region.width, region.height];
+    if bounds.iter().any(|value| !value.is_finite()) || region.width < 0.0 || region.height < 0.0 {
+        return Err(napi::Error::from_reason(format!(
+            "Gaze region {} must have finite bounds and a non-negative size",
+            region.id
+        )));
+    }
+    Ok(DwellRegion {
+        u_min: region.u,
+        v_min: region.v,
+        u_max: region.u + region.width,
+        v_max: region.v + region.height,
+        dwell_seconds: region
+            .dwellSeconds
+            .map(|value| seconds("dwellSeconds", Some(value), 0.0))
+            .transpose()?,
+        id: region.id,
+    })
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update gaze pointer settings; omitted fields keep their value.
+    /// 視線ポインターの設定を更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_gaze(&self, settings: GazeSettings) -> napi::Result<()> {
+        let mut gaze = self.lock_gaze()?;
+        let mut config = gaze.config;
+        if let Some(enabled) = settings.enabled {
+            config.enabled = enabled;
+        }
+        config.dwell_seconds =
+            seconds("dwellSeconds", settings.dwellSeconds, config.dwell_seconds)?;
+        config.exit_grace_seconds = seconds(
+            "exitGraceSeconds",
+            settings.exitGraceSeconds,
+            config.exit_grace_seconds,
+        )?;
+        gaze.config = config;
+        gaze.dwell.reset();
+        Ok(())
+    }
+
+    /// Replace the dwell regions (keys) of an overlay, in UV space; the first match wins.
+    /// オーバーレイの注視領域 (キー) を UV 空間で置き換える (先に一致したものを優先)
+    #[napi]
+    pub fn set_gaze_regions(&self, handle: i64, regions: Vec<GazeRegion>) -> napi::Result<()> {
+        let handle = overlay_handle(handle)?.as_u64();
+        let regions = regions
+            .into_iter()
+            .map(dwell_region)
+            .collect::<napi::Result<Vec<_>>>()?;
+        let mut gaze = self.lock_gaze()?;
+        if regions.is_empty() {
+            gaze.regions.remove(&handle);
+        } else {
+            gaze.regions.insert(handle, regions);
+        }
+        gaze.dwell.reset();
+        Ok(())
+    }
+
+    /// Cast the head ray at the overlay and advance the dwell timer.
+    /// Completed dwells are returned as trigger clicks on the HMD index (0).
+    /// 頭部のレイをオーバーレイに当てて注視タイマーを進める。
+    /// 注視が完了すると HMD の番号 (0) のトリガークリックとして返す。
+    /// While the input thread runs it reports "gaze" events and this returns an empty update.
+    /// 入力スレッドの実行中は "gaze" イベントとして届き、この関数は空の更新を返す。
+    #[napi]
+    pub fn poll_gaze(&self, handle: i64) -> napi::Result<GazeUpdate> {
+        let handle = overlay_handle(handle)?;
+        let now = self.now_seconds();
+        // The input thread reports gaze itself / 入力スレッドが視線を通知する
+        if self.input_thread_running()? || !self.lock_gaze()?.config.enabled {
+            return Ok(gaze_update(DwellStatus::default(), None, now));
+        }
+
+        let pose = self.device_pose(HMD_DEVICE_INDEX)?;
+        let timestamp = pose.as_ref().map_or(now, |pose| pose.timestamp);
+        let hit = gaze_hit(self.overlay()?, handle, pose, &mut self.lock_smoothing()?)?;
+        let status = self.lock_gaze()?.update(
+            handle.as_u64(),
+            hit.as_ref().map(|hit| [hit.u, hit.v]),
+            timestamp,
+        );
+        Ok(gaze_update(status, hit, timestamp))
+    }
+}
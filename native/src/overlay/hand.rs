@@ -0,0 +1,33 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    pub(super) const ALL: [Hand; 2] = [Hand::Left, Hand::Right];
+
+    pub(super) fn from_name(name: &str) -> napi::Result<Self> {
+        match name {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            _ => Err(napi::Error::from_reason(format!(
+                "Unknown hand: {name} (expected left or right)"
+            ))),
+        }
+    }
+
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    pub(super) fn index(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+        }
+    }
+}
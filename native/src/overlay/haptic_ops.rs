@@ -0,0 +1,170 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::controller_ops::action_sources;
+use super::errors::require_fn;
+use super::haptics::{HapticPattern, HapticPulse};
+use super::manager::{InputActionCache, OverlayManager};
+use super::types::HapticOptions;
+
+/// Play one pulse through the vibration action; false if the action is unavailable or fails.
+/// 振動アクションでパルスを再生する (アクションが使えないか失敗した場合は false)
+pub(super) fn play_action_pulse(
+    system: &vr::VR_IVRSystem_FnTable,
+    input: Option<&vr::VR_IVRInput_FnTable>,
+    cache: &InputActionCache,
+    controller_index: u32,
+    pulse: HapticPulse,
+) -> bool {
+    let Some(trigger_vibration_fn) = input.and_then(|input| input.TriggerHapticVibrationAction)
+    else {
+        return false;
+    };
+    if !cache.initialized || cache.haptic_action_handle == vr::k_ulInvalidActionHandle {
+        return false;
+    }
+    // Restrict to the controller's hand so only that side vibrates
+    // 対象コントローラーの手に限定し、片側のみ振動させる
+    let source = action_sources(system, controller_index, cache)
+        .first()
+        .copied()
+        .unwrap_or(vr::k_ulInvalidInputValueHandle);
+    if source == vr::k_ulInvalidInputValueHandle {
+        return false;
+    }
+    let err = unsafe {
+        trigger_vibration_fn(
+            cache.haptic_action_handle,
+            pulse.start_seconds,
+            pulse.duration_seconds,
+            pulse.frequency,
+            pulse.amplitude,
+            source,
+        )
+    };
+    err == vr::EVRInputError_VRInputError_None
+}
+
+/// Play one pulse immediately with the legacy device pulse / レガシーのデバイスパルスで即座に再生
+pub(super) fn play_legacy_pulse(
+    system: &vr::VR_IVRSystem_FnTable,
+    controller_index: u32,
+    pulse: HapticPulse,
+) -> napi::Result<()> {
+    let trigger_pulse_fn = require_fn(system.TriggerHapticPulse, "TriggerHapticPulse")?;
+    unsafe {
+        trigger_pulse_fn(controller_index, 0, pulse.legacy_duration_micros());
+    }
+    Ok(())
+}
+
+impl OverlayManager {
+    /// Play pulses through the vibration action, falling back to the legacy device pulse for
+    /// any the action does not take. Delayed legacy pulses are queued for play_due_haptics.
+    /// While the input thread runs, all pulses are queued for it instead.
+    /// 振動アクションでパルスを再生し、アクションで再生できないものはレガシーのデバイスパルスに
+    /// フォールバックする。遅延付きのレガシーパルスは play_due_haptics 用に登録する。
+    /// 入力スレッドの実行中は、すべてのパルスを代わりにスレッドへ登録する。
+    fn play_haptic_pulses(
+        &self,
+        controller_index: u32,
+        pulses: impl IntoIterator<Item = HapticPulse>,
+    ) -> napi::Result<()> {
+        let now = self.now_seconds();
+        if self.input_thread_running()? {
+            let mut queue = self.lock_haptics()?;
+            for pulse in pulses {
+                queue.schedule(now, controller_index, pulse);
+            }
+            return Ok(());
+        }
+
+        self.play_due_haptics()?;
+        let system = self.system()?;
+        let input = self.input().ok();
+        let cache = self.borrow_input_cache()?;
+        for pulse in pulses {
+            if play_action_pulse(system, input, &cache, controller_index, pulse) {
+                continue;
+            }
+            if pulse.start_seconds > 0.0 {
+                self.lock_haptics()?.schedule(now, controller_index, pulse);
+            } else {
+                play_legacy_pulse(system, controller_index, pulse)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Play queued pulses that are due. Polls call this on the JS thread; while the input
+    /// thread runs it plays them itself.
+    /// 期限の来たパルスを再生する。JS スレッドのポーリングから呼ばれ、入力スレッドの実行中は
+    /// スレッド自身が再生する。
+    pub(super) fn play_due_haptics(&self) -> napi::Result<()> {
+        let due = self.lock_haptics()?.take_due(self.now_seconds());
+        if due.is_empty() {
+            return Ok(());
+        }
+        let system = self.system()?;
+        let input = self.input().ok();
+        let cache = self.borrow_input_cache()?;
+        for scheduled in due {
+            let (index, pulse) = (scheduled.controller_index, scheduled.pulse);
+            if !play_action_pulse(system, input, &cache, index, pulse) {
+                play_legacy_pulse(system, index, pulse)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    #[napi]
+    pub fn trigger_haptic_vibration(
+        &self,
+        controller_index: u32,
+        duration_seconds: f64,
+        frequency: f64,
+        amplitude: f64,
+    ) -> napi::Result<()> {
+        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+            return Err(napi::Error::from_reason("Invalid device index"));
+        }
+        self.play_haptic_pulses(
+            controller_index,
+            [HapticPulse::new(duration_seconds, frequency, amplitude)],
+        )
+    }
+
+    /// Play a named pattern: click, hoverEnter, errorBuzz or longPressConfirm.
+    /// Without the vibration action, later pulses of a pattern play from the next
+    /// pollToggleClicked or pollTriggerEvents, or from the input thread while it runs.
+    /// 名前付きパターンを再生: click, hoverEnter, errorBuzz, longPressConfirm
+    /// 振動アクションが無い場合、パターンの後続のパルスは次の pollToggleClicked か
+    /// pollTriggerEvents で (入力スレッドの実行中はスレッドで) 再生する。
+    #[napi]
+    pub fn play_haptic_pattern(
+        &self,
+        controller_index: u32,
+        pattern: String,
+        options: Option<HapticOptions>,
+    ) -> napi::Result<()> {
+        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+            return Err(napi::Error::from_reason("Invalid device index"));
+        }
+        let pattern = HapticPattern::from_name(&pattern)?;
+        let (amplitude, frequency) = match options {
+            Some(options) => (options.amplitude, options.frequency),
+            None => (None, None),
+        };
+
+        self.play_haptic_pulses(
+            controller_index,
+            pattern
+                .pulses()
+                .iter()
+                .map(|pulse| pulse.with_overrides(amplitude, frequency)),
+        )
+    }
+}
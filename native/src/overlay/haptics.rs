@@ -0,0 +1,210 @@
+// Legacy TriggerHapticPulse accepts at most 3999µs per call
+// レガシー TriggerHapticPulse は 1 回あたり最大 3999µs
+pub(super) const LEGACY_PULSE_MAX_MICROS: f32 = 3999.0;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct HapticPulse {
+    pub start_seconds: f32,
+    pub duration_seconds: f32,
+    pub frequency: f32,
+    pub amplitude: f32,
+}
+
+impl HapticPulse {
+    pub(super) fn new(duration_seconds: f64, frequency: f64, amplitude: f64) -> Self {
+        Self {
+            start_seconds: 0.0,
+            duration_seconds: duration_seconds.max(0.0) as f32,
+            frequency: frequency.max(0.0) as f32,
+            amplitude: amplitude.clamp(0.0, 1.0) as f32,
+        }
+    }
+
+    /// Apply caller overrides: amplitude is a 0..1 scale, frequency replaces the pattern's.
+    /// 呼び出し側の指定を適用: amplitude は 0..1 の倍率、frequency はパターンの値を置換
+    pub(super) fn with_overrides(self, amplitude: Option<f64>, frequency: Option<f64>) -> Self {
+        Self {
+            amplitude: match amplitude {
+                Some(scale) => (self.amplitude * scale.clamp(0.0, 1.0) as f32).clamp(0.0, 1.0),
+                None => self.amplitude,
+            },
+            frequency: match frequency {
+                Some(frequency) => frequency.max(0.0) as f32,
+                None => self.frequency,
+            },
+            ..self
+        }
+    }
+
+    /// Legacy pulse length: the duration capped to what one call accepts, then scaled by
+    /// amplitude, since the legacy API has no strength of its own.
+    /// レガシーパルスの長さ: 1 回で受け付ける長さに制限した後、amplitude で縮める
+    /// (レガシー API には強さの指定が無いため)
+    pub(super) fn legacy_duration_micros(&self) -> u16 {
+        let micros = (self.duration_seconds * 1_000_000.0).min(LEGACY_PULSE_MAX_MICROS);
+        (micros * self.amplitude).clamp(0.0, LEGACY_PULSE_MAX_MICROS) as u16
+    }
+}
+
+const fn pulse(
+    start_seconds: f32,
+    duration_seconds: f32,
+    frequency: f32,
+    amplitude: f32,
+) -> HapticPulse {
+    HapticPulse {
+        start_seconds,
+        duration_seconds,
+        frequency,
+        amplitude,
+    }
+}
+
+const CLICK_PULSES: &[HapticPulse] = &[pulse(0.0, 0.012, 160.0, 0.6)];
+const HOVER_ENTER_PULSES: &[HapticPulse] = &[pulse(0.0, 0.006, 220.0, 0.25)];
+const ERROR_BUZZ_PULSES: &[HapticPulse] = &[
+    pulse(0.0, 0.04, 60.0, 0.8),
+    pulse(0.07, 0.04, 60.0, 0.8),
+    pulse(0.14, 0.04, 60.0, 0.8),
+];
+const LONG_PRESS_CONFIRM_PULSES: &[HapticPulse] =
+    &[pulse(0.0, 0.02, 120.0, 0.5), pulse(0.06, 0.05, 180.0, 0.9)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum HapticPattern {
+    Click,
+    HoverEnter,
+    ErrorBuzz,
+    LongPressConfirm,
+}
+
+impl HapticPattern {
+    pub(super) fn from_name(name: &str) -> napi::Result<Self> {
+        match name {
+            "click" => Ok(Self::Click),
+            "hoverEnter" => Ok(Self::HoverEnter),
+            "errorBuzz" => Ok(Self::ErrorBuzz),
+            "longPressConfirm" => Ok(Self::LongPressConfirm),
+            _ => Err(napi::Error::from_reason(format!(
+                "Unknown haptic pattern: {name} (expected click, hoverEnter, errorBuzz or longPressConfirm)"
+            ))),
+        }
+    }
+
+    pub(super) fn pulses(self) -> &'static [HapticPulse] {
+        match self {
+            Self::Click => CLICK_PULSES,
+            Self::HoverEnter => HOVER_ENTER_PULSES,
+            Self::ErrorBuzz => ERROR_BUZZ_PULSES,
+            Self::LongPressConfirm => LONG_PRESS_CONFIRM_PULSES,
+        }
+    }
+}
+
+/// Pulse waiting for its start time on the manager clock / マネージャー時計で開始時刻を待つパルス
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ScheduledPulse {
+    pub due: f64,
+    pub controller_index: u32,
+    pub pulse: HapticPulse,
+}
+
+/// Delayed pulses, played once due by the input thread or a JS-thread poll
+/// 遅延付きのパルス (期限が来たら入力スレッドか JS スレッドのポーリングで再生する)
+#[derive(Debug, Default)]
+pub(super) struct HapticQueue {
+    pending: Vec<ScheduledPulse>,
+}
+
+impl HapticQueue {
+    /// Queue `pulse` to start `start_seconds` after `now`; it then plays without further delay.
+    /// `pulse` を `now` の `start_seconds` 後に開始するよう登録する (その時点で遅延なしで再生)
+    pub(super) fn schedule(&mut self, now: f64, controller_index: u32, pulse: HapticPulse) {
+        self.pending.push(ScheduledPulse {
+            due: now + pulse.start_seconds as f64,
+            controller_index,
+            pulse: HapticPulse {
+                start_seconds: 0.0,
+                ..pulse
+            },
+        });
+    }
+
+    /// Remove and return the pulses due at `now`, earliest first / `now` までに期限の来たパルスを早い順に取り出す
+    pub(super) fn take_due(&mut self, now: f64) -> Vec<ScheduledPulse> {
+        let (mut due, pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|scheduled| scheduled.due <= now);
+        self.pending = pending;
+        due.sort_by(|a, b| a.due.total_cmp(&b.due));
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_scale_amplitude_and_replace_frequency() {
+        let pulse = CLICK_PULSES[0].with_overrides(Some(0.5), Some(90.0));
+        assert!((pulse.amplitude - 0.3).abs() < 1e-6);
+        assert_eq!(pulse.frequency, 90.0);
+        assert_eq!(pulse.duration_seconds, CLICK_PULSES[0].duration_seconds);
+    }
+
+    #[test]
+    fn overrides_are_clamped_and_optional() {
+        let pulse = ERROR_BUZZ_PULSES[1].with_overrides(Some(3.0), Some(-10.0));
+        assert_eq!(pulse.amplitude, 0.8);
+        assert_eq!(pulse.frequency, 0.0);
+        assert_eq!(pulse.start_seconds, 0.07);
+
+        let pulse = CLICK_PULSES[0].with_overrides(None, None);
+        assert_eq!(pulse.amplitude, 0.6);
+        assert_eq!(pulse.frequency, 160.0);
+    }
+
+    #[test]
+    fn legacy_duration_caps_before_scaling_by_amplitude() {
+        // 12 ms is capped to 3999 µs, so amplitude still changes the result
+        assert_eq!(CLICK_PULSES[0].legacy_duration_micros(), 2399);
+        let softer = CLICK_PULSES[0].with_overrides(Some(0.5), None);
+        assert_eq!(softer.legacy_duration_micros(), 1199);
+    }
+
+    #[test]
+    fn legacy_duration_of_short_and_silent_pulses() {
+        assert_eq!(
+            HapticPulse::new(0.002, 0.0, 0.5).legacy_duration_micros(),
+            1000
+        );
+        assert_eq!(HapticPulse::new(0.05, 0.0, 0.0).legacy_duration_micros(), 0);
+        assert_eq!(HapticPulse::new(-1.0, 0.0, 1.0).legacy_duration_micros(), 0);
+        assert_eq!(
+            HapticPulse::new(1.0, 0.0, 1.0).legacy_duration_micros(),
+            3999
+        );
+    }
+
+    #[test]
+    fn queue_plays_pattern_pulses_when_due() {
+        let mut queue = HapticQueue::default();
+        for pulse in ERROR_BUZZ_PULSES {
+            queue.schedule(10.0, 3, *pulse);
+        }
+        let first = queue.take_due(10.0);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].controller_index, 3);
+        assert!(queue.take_due(10.05).is_empty());
+
+        let rest = queue.take_due(10.2);
+        assert_eq!(rest.len(), 2);
+        assert!(rest[0].due < rest[1].due);
+        assert!(rest
+            .iter()
+            .all(|scheduled| scheduled.pulse.start_seconds == 0.0));
+        assert!(queue.take_due(11.0).is_empty());
+    }
+}
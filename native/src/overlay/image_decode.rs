@@ -0,0 +1,298 @@
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use resvg::{tiny_skia, usvg};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use super::constants::MAX_IMAGE_SIZE;
+use super::pixels::{convert_to_rgba, AlphaMode, PixelFormat, SourceLayout};
+
+/// Image formats decoded in Rust / Rust でデコードする画像形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Svg,
+}
+
+impl ImageFormat {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Svg => "svg",
+        }
+    }
+
+    /// Detect the format from the file signature; SVG has none, so it also goes by extension.
+    /// ファイルのシグネチャから形式を判定する (SVG には無いため拡張子でも判定する)
+    pub(super) fn detect(path: &Path, data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return Some(ImageFormat::Png);
+        }
+        if data.starts_with(&[0xff, 0xd8, 0xff]) {
+            return Some(ImageFormat::Jpeg);
+        }
+        if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            return Some(ImageFormat::WebP);
+        }
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let text = String::from_utf8_lossy(&data[..data.len().min(256)]);
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        if matches!(extension.as_deref(), Some("svg" | "svgz"))
+            || text.starts_with("<?xml")
+            || text.starts_with("<svg")
+        {
+            return Some(ImageFormat::Svg);
+        }
+        None
+    }
+
+    fn image_format(self) -> Option<image::ImageFormat> {
+        match self {
+            ImageFormat::Png => Some(image::ImageFormat::Png),
+            ImageFormat::Jpeg => Some(image::ImageFormat::Jpeg),
+            ImageFormat::WebP => Some(image::ImageFormat::WebP),
+            ImageFormat::Svg => None,
+        }
+    }
+}
+
+/// Decoded image resized to the texture size, as packed RGBA8
+/// テクスチャサイズにリサイズしたデコード済みの画像 (詰めた RGBA8)
+#[derive(Debug)]
+pub(super) struct DecodedImage {
+    pub format: ImageFormat,
+    /// Size of the file's image before resizing / リサイズ前のファイルの画像サイズ
+    pub source_width: u32,
+    pub source_height: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    /// Color is multiplied by alpha (resized or rendered images) / 色がアルファで乗算済み (リサイズ・描画した画像)
+    pub premultiplied: bool,
+}
+
+/// Texture size for a `source` image: the requested `width` and `height`, with a missing one
+/// following the aspect ratio, or the source size when neither is given.
+/// `source` の画像のテクスチャサイズ: 指定された `width` と `height` (片方のみなら縦横比を保つ)、
+/// どちらも無ければソースのサイズ。
+pub(super) fn target_size(
+    (source_width, source_height): (u32, u32),
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<(u32, u32), String> {
+    let scaled = |size: u32, from: u32, to: u32| {
+        ((size as f64 * to as f64 / from as f64).round() as u32).max(1)
+    };
+    let (width, height) = match (width, height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (width, scaled(source_height, source_width, width)),
+        (None, Some(height)) => (scaled(source_width, source_height, height), height),
+        (None, None) => (source_width, source_height),
+    };
+    if width == 0 || height == 0 {
+        return Err("width and height must be positive".to_string());
+    }
+    if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return Err(format!(
+            "Texture size {}x{} exceeds the {} pixel limit; pass width or height to scale it down",
+            width, height, MAX_IMAGE_SIZE
+        ));
+    }
+    Ok((width, height))
+}
+
+/// Read and decode the image at `path`, resized to the texture size chosen by `target_size`.
+/// `path` の画像を読み込んでデコードし、`target_size` で決まるテクスチャサイズにリサイズする
+pub(super) fn decode_image(
+    path: &Path,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<DecodedImage, String> {
+    let data =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let format = ImageFormat::detect(path, &data).ok_or_else(|| {
+        format!(
+            "Unsupported image format: {} (expected PNG, JPEG, WebP or SVG)",
+            path.display()
+        )
+    })?;
+    let decode_error = |e: String| {
+        format!(
+            "Failed to decode {} image {}: {}",
+            format.name().to_uppercase(),
+            path.display(),
+            e
+        )
+    };
+    let (source, image, premultiplied) = match format.image_format() {
+        Some(image_format) => {
+            let image = image::load_from_memory_with_format(&data, image_format)
+                .map_err(|e| decode_error(e.to_string()))?
+                .into_rgba8();
+            let source = image.dimensions();
+            let target = target_size(source, width, height)?;
+            let (image, premultiplied) = resize_rgba(image, target)?;
+            (source, image, premultiplied)
+        }
+        None => {
+            let tree = parse_svg(&data, path).map_err(decode_error)?;
+            let size = tree.size();
+            let source = (
+                (size.width().ceil() as u32).max(1),
+                (size.height().ceil() as u32).max(1),
+            );
+            let target = target_size(source, width, height)?;
+            // tiny-skia renders premultiplied RGBA / tiny-skia は乗算済みの RGBA で描画する
+            (source, render_svg(&tree, target)?, true)
+        }
+    };
+    Ok(DecodedImage {
+        format,
+        source_width: source.0,
+        source_height: source.1,
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+        premultiplied,
+    })
+}
+
+/// Resize to `target` with a Lanczos filter; returns whether the result is premultiplied.
+/// Lanczos フィルタで `target` にリサイズする (結果が乗算済みかを返す)
+fn resize_rgba(image: RgbaImage, target: (u32, u32)) -> Result<(RgbaImage, bool), String> {
+    let (width, height) = image.dimensions();
+    if (width, height) == target {
+        return Ok((image, false));
+    }
+    // Filter premultiplied color, so transparent pixels don't bleed their color into edges
+    // 透明なピクセルの色が縁に滲まないよう、乗算済みの色でフィルタする
+    let layout = SourceLayout {
+        width: width as usize,
+        height: height as usize,
+        row_pitch: width as usize * 4,
+        format: PixelFormat::Rgba8,
+    };
+    let mut premultiplied = Vec::new();
+    convert_to_rgba(
+        &image,
+        &layout,
+        AlphaMode::Premultiply.into(),
+        &mut premultiplied,
+    )?;
+    let image = RgbaImage::from_raw(width, height, premultiplied)
+        .ok_or("Premultiplied image has the wrong size")?;
+    let resized = imageops::resize(&image, target.0, target.1, FilterType::Lanczos3);
+    Ok((resized, true))
+}
+
+/// Fonts for SVG text, loaded from the system once / SVG のテキスト用のフォント (システムから一度だけ読み込む)
+fn svg_fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+fn parse_svg(data: &[u8], path: &Path) -> Result<usvg::Tree, String> {
+    let options = usvg::Options {
+        // Relative <image> references resolve next to the file / 相対パスの <image> はファイルの隣から解決
+        resources_dir: path.parent().map(Path::to_path_buf),
+        fontdb: svg_fonts(),
+        ..usvg::Options::default()
+    };
+    usvg::Tree::from_data(data, &options).map_err(|e| e.to_string())
+}
+
+/// Render an SVG straight at the texture size, so it stays sharp at any scale
+/// SVG をテクスチャサイズで直接描画する (どの倍率でも鮮明になる)
+fn render_svg(tree: &usvg::Tree, (width, height): (u32, u32)) -> Result<RgbaImage, String> {
+    let mut pixmap =
+        tiny_skia::Pixmap::new(width, height).ok_or("Failed to allocate the SVG render target")?;
+    let size = tree.size();
+    let transform = tiny_skia::Transform::from_scale(
+        width as f32 / size.width(),
+        height as f32 / size.height(),
+    );
+    resvg::render(tree, transform, &mut pixmap.as_mut());
+    RgbaImage::from_raw(width, height, pixmap.take())
+        .ok_or_else(|| "Rendered SVG has the wrong size".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(name: &str, data: &[u8]) -> Option<ImageFormat> {
+        ImageFormat::detect(Path::new(name), data)
+    }
+
+    #[test]
+    fn detects_raster_formats_by_signature() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        // The signature wins over a misleading extension / 誤った拡張子よりシグネチャを優先
+        assert_eq!(detect("image.jpg", png), Some(ImageFormat::Png));
+        assert_eq!(
+            detect("photo", &[0xff, 0xd8, 0xff, 0xe0, 0, 0x10]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            detect("image.png", b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some(ImageFormat::WebP)
+        );
+        // RIFF containers other than WebP are not images / WebP 以外の RIFF は画像ではない
+        assert_eq!(detect("sound.webp", b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(detect("short.webp", b"RIFF"), None);
+    }
+
+    #[test]
+    fn detects_svg_by_markup_or_extension() {
+        assert_eq!(
+            detect("icon", b"\xef\xbb\xbf  <?xml version=\"1.0\"?><svg/>"),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(
+            detect("icon", b"\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(detect("ICON.SVGZ", &[0x1f, 0x8b]), Some(ImageFormat::Svg));
+        assert_eq!(detect("notes.txt", b"hello"), None);
+        assert_eq!(detect("empty", b""), None);
+    }
+
+    #[test]
+    fn target_size_keeps_the_aspect_ratio_of_a_missing_side() {
+        assert_eq!(target_size((400, 300), None, None), Ok((400, 300)));
+        assert_eq!(target_size((400, 300), Some(200), None), Ok((200, 150)));
+        assert_eq!(target_size((400, 300), None, Some(100)), Ok((133, 100)));
+        assert_eq!(target_size((400, 300), Some(50), Some(60)), Ok((50, 60)));
+        // A thin image never scales to zero / 細い画像でも 0 にはならない
+        assert_eq!(target_size((1000, 1), Some(10), None), Ok((10, 1)));
+    }
+
+    #[test]
+    fn target_size_rejects_empty_and_oversized_textures() {
+        assert!(target_size((400, 300), Some(0), None).is_err());
+        assert!(target_size((400, 300), None, Some(0)).is_err());
+        let limit = MAX_IMAGE_SIZE;
+        assert_eq!(target_size((limit, limit), None, None), Ok((limit, limit)));
+        let error = target_size((limit + 1, 10), None, None).unwrap_err();
+        assert!(error.contains("pass width or height"), "{}", error);
+        // Scaling a large source down brings it under the limit / 大きなソースも縮小すれば制限内
+        assert_eq!(
+            target_size((limit * 2, limit), Some(limit), None),
+            Ok((limit, limit / 2))
+        );
+        assert!(target_size((100, limit), Some(200), None).is_err());
+    }
+}
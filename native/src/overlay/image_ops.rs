@@ -0,0 +1,118 @@
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::handles::{overlay_handle, OverlayHandle};
+use super::image_decode::decode_image;
+use super::manager::OverlayManager;
+use super::pixels::{convert_to_rgba, AlphaMode, PixelFormat};
+use super::texture_ops::texture_source;
+use super::texture_pipeline::{lock_pipeline, TexturePipeline, UploadQueue};
+use super::types::{ImageInfo, ImageOptions, TextureDescriptor};
+
+/// Image file handed over by set_overlay_from_image, decoded and set on a libuv worker.
+/// set_overlay_from_image から渡され、libuv のワーカーでデコード・設定される画像ファイル
+pub struct ImageLoad {
+    pipeline: Arc<Mutex<TexturePipeline>>,
+    queue: Arc<UploadQueue>,
+    handle: OverlayHandle,
+    sequence: u64,
+    path: PathBuf,
+    options: ImageOptions,
+}
+
+impl Task for ImageLoad {
+    type Output = ImageInfo;
+    type JsValue = ImageInfo;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let image = decode_image(&self.path, self.options.width, self.options.height)
+            .map_err(napi::Error::from_reason)?;
+        let descriptor = TextureDescriptor {
+            format: Some("rgba8".to_string()),
+            premultiplied: Some(image.premultiplied),
+            alpha: self.options.alpha.take(),
+            ..Default::default()
+        };
+        let output = lock_pipeline(&self.pipeline)?.output;
+        let source = texture_source(
+            image.rgba.len(),
+            image.width,
+            image.height,
+            Some(descriptor),
+            PixelFormat::Rgba8,
+            output,
+        )?;
+        let mut converted = Vec::new();
+        let rgba = if source.alpha.mode == AlphaMode::Straight {
+            &image.rgba
+        } else {
+            convert_to_rgba(
+                source.pixels(&image.rgba),
+                &source.layout,
+                source.alpha,
+                &mut converted,
+            )
+            .map_err(napi::Error::from_reason)?;
+            &converted
+        };
+
+        let front = self.handle.as_u64();
+        let mut pipeline = lock_pipeline(&self.pipeline)?;
+        // A frame set after this call replaces the image / この呼び出し以降に設定されたフレームが画像より優先
+        if self.queue.is_latest(front, self.sequence) {
+            // The next setOverlayTexturesD3D11 frame must not be skipped as unchanged
+            // 次の setOverlayTexturesD3D11 のフレームを変化無しとして省略させない
+            pipeline.forget_overlay(front);
+            pipeline.upload_raw(&[self.handle], rgba, image.width, image.height)?;
+        }
+
+        Ok(ImageInfo {
+            format: image.format.name().to_string(),
+            sourceWidth: image.source_width,
+            sourceHeight: image.source_height,
+            width: image.width,
+            height: image.height,
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Decode a PNG, JPEG, WebP or SVG file in Rust and set it on `handle` with SetOverlayRaw.
+    /// The image is resized to `options.width` x `options.height` with a Lanczos filter (SVGs
+    /// are rendered at that size), keeping the aspect ratio when only one is given. Reading,
+    /// decoding and resizing run on a worker thread; a frame set on `handle` before the
+    /// promise settles wins over the image. Unlike setOverlayFromFile, failures name the file
+    /// and what went wrong.
+    /// PNG・JPEG・WebP・SVG ファイルを Rust でデコードし、SetOverlayRaw で `handle` に設定する。
+    /// 画像は Lanczos フィルタで `options.width` x `options.height` にリサイズし (SVG はそのサイズで描画)、
+    /// 片方のみの指定なら縦横比を保つ。読み込み・デコード・リサイズはワーカースレッドで行い、
+    /// Promise の確定前に `handle` に設定されたフレームが画像より優先される。
+    /// setOverlayFromFile と異なり、失敗時はファイルと原因を示す。
+    #[napi]
+    pub fn set_overlay_from_image(
+        &self,
+        handle: i64,
+        file_path: String,
+        options: Option<ImageOptions>,
+    ) -> napi::Result<AsyncTask<ImageLoad>> {
+        let handle = overlay_handle(handle)?;
+        // Queued frames are older than this image / 待機中のフレームはこの画像より古い
+        let sequence = self.upload_queue().enqueue(handle.as_u64());
+        Ok(AsyncTask::new(ImageLoad {
+            pipeline: self.texture_pipeline(),
+            queue: self.upload_queue().clone(),
+            handle,
+            sequence,
+            path: PathBuf::from(file_path),
+            options: options.unwrap_or_default(),
+        }))
+    }
+}
@@ -1,19 +1,21 @@
 use napi_derive::napi;
 use openvr_sys as vr;
-use std::collections::BTreeSet;
 use std::ffi::{c_char, CString};
+use std::path::Path;
 
+use super::action_manifest::{
+    ACTION_SET_PATH, GRIP_ACTION_PATH, HAPTIC_ACTION_PATH, LEFT_HAND_PATH, POINTER_ACTION_PATH,
+    RIGHT_HAND_PATH, SCROLL_ACTION_PATH, SKELETON_LEFT_ACTION_PATH, SKELETON_RIGHT_ACTION_PATH,
+    TOGGLE_ACTION_PATH, TRIGGER_ACTION_PATH, TRIGGER_VALUE_ACTION_PATH,
+};
+use super::action_manifest_ops::warn_manifest_issues;
 use super::errors::{input_error, require_fn};
-use super::manager::OverlayManager;
-use super::types::CurrentBindings;
+use super::manager::{InputActionCache, OverlayManager};
+use super::types::{BindingInfo, CurrentBindings};
 
-const ACTION_SET_PATH: &str = "/actions/vrkb2";
-const TOGGLE_ACTION_PATH: &str = "/actions/vrkb2/in/toggle_overlay";
-const TRIGGER_ACTION_PATH: &str = "/actions/vrkb2/in/trigger_click";
-const GRIP_ACTION_PATH: &str = "/actions/vrkb2/in/grip_click";
-const LEFT_HAND_PATH: &str = "/user/hand/left";
-const RIGHT_HAND_PATH: &str = "/user/hand/right";
-const TOGGLE_RELEASE_STREAK_TO_UNLOCK: u8 = 3;
+const INITIAL_BINDING_CAPACITY: usize = 16;
+const BINDING_INFO_ATTEMPTS: u32 = 4;
+const ORIGIN_NAME_BUFFER_SIZE: usize = 256;
 
 fn to_cstring(input: &str, label: &str) -> napi::Result<CString> {
     CString::new(input)
@@ -40,23 +42,130 @@ fn read_c_buffer(buf: &[c_char]) -> String {
     String::from_utf8_lossy(&bytes[..len]).trim().to_string()
 }
 
-fn get_binding_labels(
+/// Resolve the tracked device index behind an action origin.
+/// アクションの発生元から対応するトラッキングデバイスのインデックスを取得
+pub(super) fn origin_device_index(
+    input: &vr::VR_IVRInput_FnTable,
+    origin: vr::VRInputValueHandle_t,
+) -> Option<u32> {
+    if origin == vr::k_ulInvalidInputValueHandle {
+        return None;
+    }
+    let get_origin_info_fn = input.GetOriginTrackedDeviceInfo?;
+    unsafe {
+        let mut info: vr::InputOriginInfo_t = std::mem::zeroed();
+        let err = get_origin_info_fn(
+            origin,
+            &mut info,
+            std::mem::size_of::<vr::InputOriginInfo_t>() as u32,
+        );
+        if err != vr::EVRInputError_VRInputError_None
+            || info.trackedDeviceIndex == vr::k_unTrackedDeviceIndexInvalid
+        {
+            return None;
+        }
+        Some(info.trackedDeviceIndex)
+    }
+}
+
+/// Read a digital action from the first source that reports it active.
+/// 最初にアクティブなソースからデジタルアクションを読み取る
+pub(super) fn read_digital_action(
     input: &vr::VR_IVRInput_FnTable,
     action_handle: vr::VRActionHandle_t,
-) -> napi::Result<Vec<String>> {
+    sources: &[vr::VRInputValueHandle_t],
+) -> Option<vr::InputDigitalActionData_t> {
+    if action_handle == vr::k_ulInvalidActionHandle {
+        return None;
+    }
+    let get_digital_action_data_fn = input.GetDigitalActionData?;
+
+    for source in sources.iter().copied() {
+        let mut data: vr::InputDigitalActionData_t = unsafe { std::mem::zeroed() };
+        let err = unsafe {
+            get_digital_action_data_fn(
+                action_handle,
+                &mut data,
+                std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
+                source,
+            )
+        };
+        if err == vr::EVRInputError_VRInputError_None && data.bActive {
+            return Some(data);
+        }
+    }
+    None
+}
+
+/// Activate the keyboard action set for this frame / このフレームのキーボード用アクションセットを有効化
+pub(super) fn update_action_state(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+) -> napi::Result<()> {
+    let update_action_state_fn = require_fn(input.UpdateActionState, "UpdateActionState")?;
+    let mut active_set = vr::VRActiveActionSet_t {
+        ulActionSet: cache.action_set_handle,
+        ulRestrictedToDevice: vr::k_ulInvalidInputValueHandle,
+        ulSecondaryActionSet: vr::k_ulInvalidActionSetHandle,
+        unPadding: 0,
+        nPriority: 0,
+    };
+    input_error_if_needed("UpdateActionState", unsafe {
+        update_action_state_fn(
+            &mut active_set,
+            std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
+            1,
+        )
+    })
+}
+
+pub(super) fn read_toggle_state(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+) -> napi::Result<bool> {
+    let get_digital_action_data_fn =
+        require_fn(input.GetDigitalActionData, "GetDigitalActionData")?;
+    let mut digital: vr::InputDigitalActionData_t = unsafe { std::mem::zeroed() };
+    input_error_if_needed("GetDigitalActionData", unsafe {
+        get_digital_action_data_fn(
+            cache.toggle_action_handle,
+            &mut digital,
+            std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
+            vr::k_ulInvalidInputValueHandle,
+        )
+    })?;
+    Ok(digital.bActive && digital.bState)
+}
+
+fn get_action_bindings(
+    input: &vr::VR_IVRInput_FnTable,
+    action_handle: vr::VRActionHandle_t,
+) -> napi::Result<Vec<BindingInfo>> {
     let get_action_binding_info_fn =
         require_fn(input.GetActionBindingInfo, "GetActionBindingInfo")?;
-    let mut bindings: [vr::InputBindingInfo_t; 16] = unsafe { std::mem::zeroed() };
-    let mut returned_count: u32 = 0;
-
-    unsafe {
-        let err = get_action_binding_info_fn(
-            action_handle,
-            bindings.as_mut_ptr(),
-            std::mem::size_of::<vr::InputBindingInfo_t>() as u32,
-            bindings.len() as u32,
-            &mut returned_count,
-        );
+    let mut bindings: Vec<vr::InputBindingInfo_t> = Vec::new();
+    let mut capacity = INITIAL_BINDING_CAPACITY;
+    let mut attempts = 0;
+    let returned_count = loop {
+        attempts += 1;
+        bindings.resize_with(capacity, || unsafe { std::mem::zeroed() });
+        let mut returned_count: u32 = 0;
+        let err = unsafe {
+            get_action_binding_info_fn(
+                action_handle,
+                bindings.as_mut_ptr(),
+                std::mem::size_of::<vr::InputBindingInfo_t>() as u32,
+                bindings.len() as u32,
+                &mut returned_count,
+            )
+        };
+        if err == vr::EVRInputError_VRInputError_BufferTooSmall && attempts < BINDING_INFO_ATTEMPTS
+        {
+            // Grow to the reported count (or double if none was reported) and retry
+            // 報告された件数 (無ければ 2 倍) に拡張して再取得
+            capacity = (returned_count as usize).max(capacity * 2);
+            continue;
+        }
         if err != vr::EVRInputError_VRInputError_None
             && err != vr::EVRInputError_VRInputError_BufferTooSmall
         {
@@ -65,235 +174,365 @@ fn get_binding_labels(
             }
             return Err(input_error("GetActionBindingInfo", err));
         }
+        break (returned_count as usize).min(bindings.len());
+    };
+
+    let mut result: Vec<BindingInfo> = Vec::new();
+    for info in bindings.iter().take(returned_count) {
+        let binding = BindingInfo {
+            devicePath: read_c_buffer(&info.rchDevicePathName),
+            inputPath: read_c_buffer(&info.rchInputPathName),
+            mode: read_c_buffer(&info.rchModeName),
+            slot: read_c_buffer(&info.rchSlotName),
+            sourceType: read_c_buffer(&info.rchInputSourceType),
+        };
+        let empty = binding.devicePath.is_empty() && binding.inputPath.is_empty();
+        let duplicate = result.iter().any(|existing| {
+            existing.devicePath == binding.devicePath
+                && existing.inputPath == binding.inputPath
+                && existing.mode == binding.mode
+                && existing.slot == binding.slot
+                && existing.sourceType == binding.sourceType
+        });
+        if !empty && !duplicate {
+            result.push(binding);
+        }
+    }
+    Ok(result)
+}
+
+/// Localized names of the devices/inputs currently bound to an action,
+/// e.g. "Right Index Controller B Button".
+/// アクションに現在割り当てられている入力の表示名 (例: "Right Index Controller B Button")
+fn get_origin_names(
+    input: &vr::VR_IVRInput_FnTable,
+    action_set_handle: vr::VRActionSetHandle_t,
+    action_handle: vr::VRActionHandle_t,
+) -> napi::Result<Vec<String>> {
+    let get_action_origins_fn = require_fn(input.GetActionOrigins, "GetActionOrigins")?;
+    let get_origin_name_fn = require_fn(input.GetOriginLocalizedName, "GetOriginLocalizedName")?;
+
+    let mut origins = [vr::k_ulInvalidInputValueHandle; vr::k_unMaxActionOriginCount as usize];
+    let err = unsafe {
+        get_action_origins_fn(
+            action_set_handle,
+            action_handle,
+            origins.as_mut_ptr(),
+            origins.len() as u32,
+        )
+    };
+    if err != vr::EVRInputError_VRInputError_None {
+        if is_non_fatal_binding_info_error(err) {
+            return Ok(vec![]);
+        }
+        return Err(input_error("GetActionOrigins", err));
     }
 
-    let mut names = BTreeSet::new();
-    for info in bindings.iter().take(returned_count as usize) {
-        let device = read_c_buffer(&info.rchDevicePathName);
-        let input_path = read_c_buffer(&info.rchInputPathName);
-        let mode = read_c_buffer(&info.rchModeName);
-        let slot = read_c_buffer(&info.rchSlotName);
-        let source = read_c_buffer(&info.rchInputSourceType);
-        let label = format!("{device} {input_path} {mode} {slot} {source}")
-            .trim()
-            .replace("  ", " ");
-        if !label.is_empty() {
-            names.insert(label);
+    let mut names: Vec<String> = Vec::new();
+    for origin in origins
+        .iter()
+        .copied()
+        .take_while(|origin| *origin != vr::k_ulInvalidInputValueHandle)
+    {
+        let mut buf: [c_char; ORIGIN_NAME_BUFFER_SIZE] = [0; ORIGIN_NAME_BUFFER_SIZE];
+        let err = unsafe {
+            get_origin_name_fn(
+                origin,
+                buf.as_mut_ptr(),
+                buf.len() as u32,
+                vr::EVRInputStringBits_VRInputString_All,
+            )
+        };
+        if err != vr::EVRInputError_VRInputError_None {
+            continue;
+        }
+        let name = read_c_buffer(&buf);
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
         }
     }
+    Ok(names)
+}
+
+/// Load the action manifest and look up the handles this addon uses.
+/// アクションマニフェストを読み込み、このアドオンが使うハンドルを取得する
+fn load_action_manifest(
+    input: &vr::VR_IVRInput_FnTable,
+    manifest_abs_path: &str,
+) -> napi::Result<InputActionCache> {
+    let manifest = to_cstring(manifest_abs_path, "manifest path")?;
+
+    let set_manifest_fn = require_fn(input.SetActionManifestPath, "SetActionManifestPath")?;
+    let get_action_set_fn = require_fn(input.GetActionSetHandle, "GetActionSetHandle")?;
+    let get_action_fn = require_fn(input.GetActionHandle, "GetActionHandle")?;
+    let get_input_source_fn = require_fn(input.GetInputSourceHandle, "GetInputSourceHandle")?;
+
+    let action_set_path = to_cstring(ACTION_SET_PATH, "action set path")?;
+    let toggle_action_path = to_cstring(TOGGLE_ACTION_PATH, "toggle action path")?;
+    let trigger_action_path = to_cstring(TRIGGER_ACTION_PATH, "trigger action path")?;
+    let grip_action_path = to_cstring(GRIP_ACTION_PATH, "grip action path")?;
+    let trigger_value_action_path =
+        to_cstring(TRIGGER_VALUE_ACTION_PATH, "trigger value action path")?;
+    let scroll_action_path = to_cstring(SCROLL_ACTION_PATH, "scroll action path")?;
+    let pointer_action_path = to_cstring(POINTER_ACTION_PATH, "pointer action path")?;
+    let haptic_action_path = to_cstring(HAPTIC_ACTION_PATH, "haptic action path")?;
+    let skeleton_left_action_path =
+        to_cstring(SKELETON_LEFT_ACTION_PATH, "left skeleton action path")?;
+    let skeleton_right_action_path =
+        to_cstring(SKELETON_RIGHT_ACTION_PATH, "right skeleton action path")?;
+    let left_hand_path = to_cstring(LEFT_HAND_PATH, "left hand path")?;
+    let right_hand_path = to_cstring(RIGHT_HAND_PATH, "right hand path")?;
+
+    let mut action_set_handle: vr::VRActionSetHandle_t = vr::k_ulInvalidActionSetHandle;
+    let mut toggle_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut trigger_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut grip_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut trigger_value_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut scroll_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut pointer_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut haptic_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut skeleton_left_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut skeleton_right_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
+    let mut left_hand_source: vr::VRInputValueHandle_t = vr::k_ulInvalidInputValueHandle;
+    let mut right_hand_source: vr::VRInputValueHandle_t = vr::k_ulInvalidInputValueHandle;
+
+    unsafe {
+        input_error_if_needed(
+            "SetActionManifestPath",
+            set_manifest_fn(manifest.as_ptr() as *mut c_char),
+        )?;
+        input_error_if_needed(
+            "GetActionSetHandle",
+            get_action_set_fn(
+                action_set_path.as_ptr() as *mut c_char,
+                &mut action_set_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                toggle_action_path.as_ptr() as *mut c_char,
+                &mut toggle_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                trigger_action_path.as_ptr() as *mut c_char,
+                &mut trigger_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                grip_action_path.as_ptr() as *mut c_char,
+                &mut grip_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                trigger_value_action_path.as_ptr() as *mut c_char,
+                &mut trigger_value_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                scroll_action_path.as_ptr() as *mut c_char,
+                &mut scroll_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                pointer_action_path.as_ptr() as *mut c_char,
+                &mut pointer_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                haptic_action_path.as_ptr() as *mut c_char,
+                &mut haptic_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                skeleton_left_action_path.as_ptr() as *mut c_char,
+                &mut skeleton_left_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetActionHandle",
+            get_action_fn(
+                skeleton_right_action_path.as_ptr() as *mut c_char,
+                &mut skeleton_right_action_handle,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetInputSourceHandle",
+            get_input_source_fn(
+                left_hand_path.as_ptr() as *mut c_char,
+                &mut left_hand_source,
+            ),
+        )?;
+        input_error_if_needed(
+            "GetInputSourceHandle",
+            get_input_source_fn(
+                right_hand_path.as_ptr() as *mut c_char,
+                &mut right_hand_source,
+            ),
+        )?;
+    }
 
-    Ok(names.into_iter().collect())
+    Ok(InputActionCache {
+        initialized: true,
+        action_set_handle,
+        toggle_action_handle,
+        trigger_action_handle,
+        grip_action_handle,
+        trigger_value_action_handle,
+        scroll_action_handle,
+        pointer_action_handle,
+        haptic_action_handle,
+        skeleton_left_action_handle,
+        skeleton_right_action_handle,
+        left_hand_source,
+        right_hand_source,
+    })
+}
+
+fn open_binding_ui(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+    app_key: &str,
+    show_on_desktop: bool,
+) -> napi::Result<()> {
+    if !cache.initialized {
+        return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+    }
+
+    let open_binding_ui_fn = require_fn(input.OpenBindingUI, "OpenBindingUI")?;
+    if app_key.trim().is_empty() {
+        return Err(napi::Error::from_reason("app key is required"));
+    }
+    let app_key_cstring = to_cstring(app_key, "app key")?;
+
+    unsafe {
+        input_error_if_needed(
+            "OpenBindingUI",
+            open_binding_ui_fn(
+                app_key_cstring.as_ptr() as *mut c_char,
+                cache.action_set_handle,
+                vr::k_ulInvalidInputValueHandle,
+                show_on_desktop,
+            ),
+        )
+    }
+}
+
+fn current_bindings(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+) -> napi::Result<CurrentBindings> {
+    if !cache.initialized {
+        return Ok(CurrentBindings {
+            initialized: false,
+            toggleOverlay: vec![],
+            triggerBindings: vec![],
+            gripBindings: vec![],
+            toggleOverlayOrigins: vec![],
+            triggerOrigins: vec![],
+            gripOrigins: vec![],
+            triggerBound: false,
+            gripBound: false,
+        });
+    }
+
+    let toggle_bindings = get_action_bindings(input, cache.toggle_action_handle)?;
+    let trigger_bindings = get_action_bindings(input, cache.trigger_action_handle)?;
+    let grip_bindings = get_action_bindings(input, cache.grip_action_handle)?;
+
+    let trigger_bound = !trigger_bindings.is_empty();
+    let grip_bound = !grip_bindings.is_empty();
+
+    Ok(CurrentBindings {
+        initialized: true,
+        toggleOverlay: toggle_bindings,
+        triggerBindings: trigger_bindings,
+        gripBindings: grip_bindings,
+        toggleOverlayOrigins: get_origin_names(
+            input,
+            cache.action_set_handle,
+            cache.toggle_action_handle,
+        )?,
+        triggerOrigins: get_origin_names(
+            input,
+            cache.action_set_handle,
+            cache.trigger_action_handle,
+        )?,
+        gripOrigins: get_origin_names(input, cache.action_set_handle, cache.grip_action_handle)?,
+        triggerBound: trigger_bound,
+        gripBound: grip_bound,
+    })
 }
 
 #[napi]
 impl OverlayManager {
     #[napi]
     pub fn init_input(&self, manifest_abs_path: String) -> napi::Result<()> {
-        let input = self.input()?;
-        let manifest = to_cstring(&manifest_abs_path, "manifest path")?;
-
-        let set_manifest_fn = require_fn(input.SetActionManifestPath, "SetActionManifestPath")?;
-        let get_action_set_fn = require_fn(input.GetActionSetHandle, "GetActionSetHandle")?;
-        let get_action_fn = require_fn(input.GetActionHandle, "GetActionHandle")?;
-        let get_input_source_fn = require_fn(input.GetInputSourceHandle, "GetInputSourceHandle")?;
-
-        let action_set_path = to_cstring(ACTION_SET_PATH, "action set path")?;
-        let toggle_action_path = to_cstring(TOGGLE_ACTION_PATH, "toggle action path")?;
-        let trigger_action_path = to_cstring(TRIGGER_ACTION_PATH, "trigger action path")?;
-        let grip_action_path = to_cstring(GRIP_ACTION_PATH, "grip action path")?;
-        let left_hand_path = to_cstring(LEFT_HAND_PATH, "left hand path")?;
-        let right_hand_path = to_cstring(RIGHT_HAND_PATH, "right hand path")?;
-
-        let mut action_set_handle: vr::VRActionSetHandle_t = vr::k_ulInvalidActionSetHandle;
-        let mut toggle_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
-        let mut trigger_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
-        let mut grip_action_handle: vr::VRActionHandle_t = vr::k_ulInvalidActionHandle;
-        let mut left_hand_source: vr::VRInputValueHandle_t = vr::k_ulInvalidInputValueHandle;
-        let mut right_hand_source: vr::VRInputValueHandle_t = vr::k_ulInvalidInputValueHandle;
-
-        unsafe {
-            input_error_if_needed(
-                "SetActionManifestPath",
-                set_manifest_fn(manifest.as_ptr() as *mut c_char),
-            )?;
-            input_error_if_needed(
-                "GetActionSetHandle",
-                get_action_set_fn(
-                    action_set_path.as_ptr() as *mut c_char,
-                    &mut action_set_handle,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetActionHandle",
-                get_action_fn(
-                    toggle_action_path.as_ptr() as *mut c_char,
-                    &mut toggle_action_handle,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetActionHandle",
-                get_action_fn(
-                    trigger_action_path.as_ptr() as *mut c_char,
-                    &mut trigger_action_handle,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetActionHandle",
-                get_action_fn(
-                    grip_action_path.as_ptr() as *mut c_char,
-                    &mut grip_action_handle,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetInputSourceHandle",
-                get_input_source_fn(
-                    left_hand_path.as_ptr() as *mut c_char,
-                    &mut left_hand_source,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetInputSourceHandle",
-                get_input_source_fn(
-                    right_hand_path.as_ptr() as *mut c_char,
-                    &mut right_hand_source,
-                ),
-            )?;
-        }
+        // Name manifest mistakes in the log instead of leaving only VRInputError_NameNotFound
+        // マニフェストの誤りを VRInputError_NameNotFound だけでなく内容付きでログに出す
+        warn_manifest_issues(Path::new(&manifest_abs_path));
+        let cache = self.with_input(move |input, cache| {
+            *cache = load_action_manifest(input, &manifest_abs_path)?;
+            Ok(cache.clone())
+        })?;
+        *self.borrow_input_cache_mut()? = cache;
 
-        let mut cache = self.borrow_input_cache_mut()?;
-        cache.initialized = true;
-        cache.action_set_handle = action_set_handle;
-        cache.toggle_action_handle = toggle_action_handle;
-        cache.trigger_action_handle = trigger_action_handle;
-        cache.grip_action_handle = grip_action_handle;
-        cache.left_hand_source = left_hand_source;
-        cache.right_hand_source = right_hand_source;
-        cache.last_toggle_state = false;
-        cache.toggle_lock = false;
-        cache.toggle_release_streak = 0;
+        self.lock_toggle()?.recognizer.reset();
         Ok(())
     }
 
     #[napi]
     pub fn poll_toggle_clicked(&self) -> napi::Result<bool> {
+        if self.input_thread_running()? {
+            // The input thread owns UpdateActionState and reports toggles itself
+            // 入力スレッドが UpdateActionState を担当し、トグルも通知する
+            return Ok(false);
+        }
+        self.play_due_haptics()?;
+
         let input = self.input()?;
-        let mut cache = self.borrow_input_cache_mut()?;
+        let cache = self.borrow_input_cache()?;
         if !cache.initialized {
             return Err(napi::Error::from_reason("SteamVR input is not initialized"));
         }
 
-        let update_action_state_fn = require_fn(input.UpdateActionState, "UpdateActionState")?;
-        let get_digital_action_data_fn =
-            require_fn(input.GetDigitalActionData, "GetDigitalActionData")?;
+        update_action_state(input, &cache)?;
+        let held = read_toggle_state(input, &cache)?;
+        drop(cache);
 
-        let mut active_set = vr::VRActiveActionSet_t {
-            ulActionSet: cache.action_set_handle,
-            ulRestrictedToDevice: vr::k_ulInvalidInputValueHandle,
-            ulSecondaryActionSet: vr::k_ulInvalidActionSetHandle,
-            unPadding: 0,
-            nPriority: 0,
-        };
-
-        let mut digital: vr::InputDigitalActionData_t = unsafe { std::mem::zeroed() };
-
-        unsafe {
-            input_error_if_needed(
-                "UpdateActionState",
-                update_action_state_fn(
-                    &mut active_set,
-                    std::mem::size_of::<vr::VRActiveActionSet_t>() as u32,
-                    1,
-                ),
-            )?;
-            input_error_if_needed(
-                "GetDigitalActionData",
-                get_digital_action_data_fn(
-                    cache.toggle_action_handle,
-                    &mut digital,
-                    std::mem::size_of::<vr::InputDigitalActionData_t>() as u32,
-                    vr::k_ulInvalidInputValueHandle,
-                ),
-            )?;
-        }
-
-        let current_state = digital.bActive && digital.bState;
-
-        // Latch toggle while held:
-        // avoid repeated toggles if SteamVR briefly drops action activity/state
-        // during overlay visibility changes.
-        if current_state {
-            cache.toggle_release_streak = 0;
-        } else if cache.toggle_release_streak < u8::MAX {
-            cache.toggle_release_streak = cache.toggle_release_streak.saturating_add(1);
-            if cache.toggle_release_streak >= TOGGLE_RELEASE_STREAK_TO_UNLOCK {
-                cache.toggle_lock = false;
-            }
-        }
-
-        let clicked = current_state && !cache.toggle_lock;
-        if clicked {
-            cache.toggle_lock = true;
-        }
-        cache.last_toggle_state = current_state;
-        Ok(clicked)
+        // Odd number of toggles since the last poll / 前回のポーリング以降のトグルが奇数回
+        let toggles = self
+            .recognize_toggle(held)?
+            .iter()
+            .filter(|gesture| gesture.action.is_some())
+            .count();
+        Ok(toggles % 2 == 1)
     }
 
     #[napi]
     pub fn open_binding_ui(&self, app_key: String, show_on_desktop: bool) -> napi::Result<()> {
-        let input = self.input()?;
-        let cache = self.borrow_input_cache()?;
-        if !cache.initialized {
-            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
-        }
-
-        let open_binding_ui_fn = require_fn(input.OpenBindingUI, "OpenBindingUI")?;
-        if app_key.trim().is_empty() {
-            return Err(napi::Error::from_reason("app key is required"));
-        }
-        let app_key_cstring = to_cstring(&app_key, "app key")?;
-
-        unsafe {
-            input_error_if_needed(
-                "OpenBindingUI",
-                open_binding_ui_fn(
-                    app_key_cstring.as_ptr() as *mut c_char,
-                    cache.action_set_handle,
-                    vr::k_ulInvalidInputValueHandle,
-                    show_on_desktop,
-                ),
-            )
-        }
+        self.with_input(move |input, cache| {
+            open_binding_ui(input, cache, &app_key, show_on_desktop)
+        })
     }
 
     #[napi]
     pub fn get_current_bindings(&self) -> napi::Result<CurrentBindings> {
-        let input = self.input()?;
-        let cache = self.borrow_input_cache()?;
-        if !cache.initialized {
-            return Ok(CurrentBindings {
-                initialized: false,
-                toggleOverlay: vec![],
-                triggerBindings: vec![],
-                gripBindings: vec![],
-                triggerBound: false,
-                gripBound: false,
-            });
-        }
-
-        let toggle_labels = get_binding_labels(input, cache.toggle_action_handle)?;
-        let trigger_labels = get_binding_labels(input, cache.trigger_action_handle)?;
-        let grip_labels = get_binding_labels(input, cache.grip_action_handle)?;
-
-        let trigger_bound = !trigger_labels.is_empty();
-        let grip_bound = !grip_labels.is_empty();
-
-        Ok(CurrentBindings {
-            initialized: true,
-            toggleOverlay: toggle_labels,
-            triggerBindings: trigger_labels,
-            gripBindings: grip_labels,
-            triggerBound: trigger_bound,
-            gripBound: grip_bound,
-        })
+        self.with_input(|input, cache| current_bindings(input, cache))
     }
 }
@@ -0,0 +1,706 @@
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::Status;
+use openvr_sys as vr;
+use std::collections::{HashMap, HashSet};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::analog_ops::read_analog_state;
+use super::constants::{HMD_DEVICE_INDEX, INPUT_EVENT_QUEUE_SIZE};
+use super::controller_ops::{
+    action_sources, controller_hand_for, read_controller_state, refresh_pose_snapshot,
+};
+use super::device_ops::{device_event, lock_device_table, pump_device_events};
+use super::devices::DeviceTable;
+use super::gaze::{DwellStatus, GazeState};
+use super::gaze_ops::{gaze_hit, gaze_update};
+use super::hand::Hand;
+use super::handles::OverlayHandle;
+use super::haptic_ops::{play_action_pulse, play_legacy_pulse};
+use super::haptics::HapticQueue;
+use super::input_ops::{read_toggle_state, update_action_state};
+use super::manager::{lock_input_state, InputActionCache, PoseSnapshot};
+use super::overlay_ops::intersect_overlay;
+use super::poke::PokeState;
+use super::pose_ops::{aim_ray, read_pointer_ray};
+use super::radial::RadialState;
+use super::radial_ops::{radial_event, read_radial_sample};
+use super::skeletal_ops::{read_index_fingertip, Fingertip};
+use super::smoothing::SmoothingState;
+use super::smoothing_ops::smooth_hit;
+use super::swipe::SwipeState;
+use super::swipe_ops::swipe_result;
+use super::toggle::ToggleState;
+use super::toggle_ops::toggle_gesture_event;
+use super::trigger::TriggerState;
+use super::trigger_ops::{read_trigger_sample, trigger_event};
+use super::types::{AnalogActionState, ControllerState, InputThreadEvent, PointerRay};
+
+/// Events wait in a bounded queue; when JS falls behind, hits are merged and other events dropped.
+/// イベントは上限付きのキューで待つ。JS が遅れるとヒットはまとめられ、他のイベントは破棄される
+pub(super) type InputEventCallback = ThreadsafeFunction<
+    InputThreadEvent,
+    (),
+    InputThreadEvent,
+    Status,
+    false,
+    false,
+    INPUT_EVENT_QUEUE_SIZE,
+>;
+
+/// OpenVR function tables used from the input thread / 入力スレッドで使う OpenVR 関数テーブル
+pub(super) struct VrTables {
+    pub overlay: NonNull<vr::VR_IVROverlay_FnTable>,
+    pub system: NonNull<vr::VR_IVRSystem_FnTable>,
+    pub input: Option<NonNull<vr::VR_IVRInput_FnTable>>,
+}
+
+// SAFETY: the function tables are process-wide and stay valid until VR shutdown.
+// OverlayManager joins the input thread in Drop before shutting VR down.
+// While the thread runs it is the only caller of IVRInput and of IVRSystem's controller,
+// pose, haptic and event functions: the manager's methods that need them return nothing,
+// answer from the thread's InputSnapshot or run on the thread (see InputThread::run),
+// and haptic pulses are queued to the thread.
+// The JS thread keeps calling IVRSystem device property getters and IVROverlay, which the
+// texture pipeline's upload workers already use from their own threads.
+// 関数テーブルはプロセス全体で共有され VR シャットダウンまで有効。
+// OverlayManager は Drop で VR をシャットダウンする前に入力スレッドを join する。
+// 実行中、IVRInput と IVRSystem のコントローラー・姿勢・振動・イベント関連を呼ぶのはこのスレッドのみ:
+// それらを使うマネージャーのメソッドは何も返さないか、スレッドの InputSnapshot から答えるか、
+// スレッド上で実行され (InputThread::run を参照)、振動パルスはスレッドに登録される。JS スレッドは引き続き IVRSystem のデバイスプロパティ取得と
+// IVROverlay (テクスチャパイプラインのアップロードワーカーも別スレッドから使っている) を呼ぶ。
+unsafe impl Send for VrTables {}
+
+/// Input states shared by the manager and the input thread. Settings changed from JS
+/// apply to the running thread on its next tick.
+/// マネージャーと入力スレッドが共有する入力状態。JS から変更した設定は、実行中のスレッドの
+/// 次の周期から反映される。
+#[derive(Clone)]
+pub(super) struct SharedInputState {
+    pub poke: Arc<Mutex<PokeState>>,
+    pub smoothing: Arc<Mutex<SmoothingState>>,
+    pub trigger: Arc<Mutex<TriggerState>>,
+    pub toggle: Arc<Mutex<ToggleState>>,
+    pub gaze: Arc<Mutex<GazeState>>,
+    pub radial: Arc<Mutex<RadialState>>,
+    pub swipe: Arc<Mutex<SwipeState>>,
+}
+
+impl SharedInputState {
+    fn smoothing(&self) -> napi::Result<MutexGuard<'_, SmoothingState>> {
+        lock_input_state(&self.smoothing, "smoothing state")
+    }
+
+    fn trigger(&self) -> napi::Result<MutexGuard<'_, TriggerState>> {
+        lock_input_state(&self.trigger, "trigger state")
+    }
+
+    fn toggle(&self) -> napi::Result<MutexGuard<'_, ToggleState>> {
+        lock_input_state(&self.toggle, "toggle state")
+    }
+
+    fn gaze(&self) -> napi::Result<MutexGuard<'_, GazeState>> {
+        lock_input_state(&self.gaze, "gaze state")
+    }
+
+    fn radial(&self) -> napi::Result<MutexGuard<'_, RadialState>> {
+        lock_input_state(&self.radial, "radial state")
+    }
+
+    fn swipe(&self) -> napi::Result<MutexGuard<'_, SwipeState>> {
+        lock_input_state(&self.swipe, "swipe state")
+    }
+
+    /// Move per-controller state to a reconnected controller's new index.
+    /// 再接続したコントローラーの状態を新しい番号に移す
+    pub(super) fn remap_controller(&self, previous: u32, index: u32) -> napi::Result<()> {
+        self.smoothing()?.remap_controller(previous, index);
+        self.trigger()?.remap_controller(previous, index);
+        lock_input_state(&self.poke, "poke state")?.remap_controller(previous, index);
+        self.radial()?.remap_controller(previous, index);
+        Ok(())
+    }
+}
+
+/// A controller as read on the input thread's latest tick / 入力スレッドの直近の周期で読み取ったコントローラー
+#[derive(Clone)]
+pub(super) struct ControllerSnapshot {
+    pub hand: Option<Hand>,
+    pub state: ControllerState,
+    /// None before init_input / init_input 前は None
+    pub analog: Option<AnalogActionState>,
+    pub pointer: Option<PointerRay>,
+}
+
+/// What the input thread read on its latest tick. While it runs the manager answers
+/// controller and pose queries from this instead of calling OpenVR itself.
+/// 入力スレッドが直近の周期で読み取ったもの。実行中のマネージャーは OpenVR を自ら呼ばず、
+/// コントローラーや姿勢の問い合わせにこれで答える。
+pub(super) struct InputSnapshot {
+    pub poses: PoseSnapshot,
+    /// Connected controllers by device index / 接続中のコントローラー (デバイス番号毎)
+    pub controllers: HashMap<u32, ControllerSnapshot>,
+    /// Indexed by Hand::index / Hand::index で引く
+    pub fingertips: [Option<Fingertip>; 2],
+}
+
+/// Work run on the input thread between ticks / 入力スレッドで周期の合間に実行する処理
+type InputJob = Box<dyn FnOnce(&vr::VR_IVRInput_FnTable, &mut InputActionCache) + Send>;
+
+/// What the thread needs from the manager when it starts / スレッド開始時にマネージャーから受け取るもの
+pub(super) struct InputThreadSetup {
+    pub tables: VrTables,
+    /// Action handles as of start; init_input while running replaces them on the thread
+    /// 開始時点のアクションハンドル (実行中の init_input はスレッド側で置き換える)
+    pub cache: InputActionCache,
+    pub state: SharedInputState,
+    /// The manager queues pulses here while the thread runs / 実行中はマネージャーがここにパルスを登録する
+    pub haptics: Arc<Mutex<HapticQueue>>,
+    /// Pumped by the thread while it runs / 実行中はスレッド側がイベントを処理する
+    pub devices: Arc<Mutex<DeviceTable>>,
+    pub epoch: Instant,
+    pub interval: Duration,
+}
+
+pub(super) struct InputThread {
+    stop: Arc<AtomicBool>,
+    overlay_handle: Arc<AtomicU64>,
+    snapshot: Arc<Mutex<InputSnapshot>>,
+    jobs: mpsc::Sender<InputJob>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputThread {
+    pub(super) fn spawn(
+        setup: InputThreadSetup,
+        overlay_handle: Option<OverlayHandle>,
+        callback: InputEventCallback,
+    ) -> napi::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let overlay_handle = Arc::new(AtomicU64::new(
+            overlay_handle.map_or(0, |handle| handle.as_u64()),
+        ));
+
+        let snapshot = Arc::new(Mutex::new(InputSnapshot {
+            poses: PoseSnapshot::new(),
+            controllers: HashMap::new(),
+            fingertips: [None; 2],
+        }));
+        let (jobs, job_receiver) = mpsc::channel();
+
+        let worker = Worker {
+            tables: setup.tables,
+            cache: setup.cache,
+            state: setup.state,
+            haptics: setup.haptics,
+            snapshot: snapshot.clone(),
+            jobs: job_receiver,
+            devices: setup.devices,
+            epoch: setup.epoch,
+            poses: PoseSnapshot::new(),
+            controllers: Vec::new(),
+            hitting: HashSet::new(),
+            hits_in_flight: HashMap::new(),
+            gazing: false,
+            target: 0,
+            overlay_handle: overlay_handle.clone(),
+            stop: stop.clone(),
+            callback,
+        };
+        let interval = setup.interval;
+        let handle = std::thread::Builder::new()
+            .name("vr-overlay-input".to_string())
+            .spawn(move || worker.run(interval))
+            .map_err(|e| {
+                napi::Error::from_reason(format!("Failed to spawn input thread: {}", e))
+            })?;
+
+        Ok(Self {
+            stop,
+            overlay_handle,
+            snapshot,
+            jobs,
+            handle: Some(handle),
+        })
+    }
+
+    /// What the thread read on its latest tick / スレッドが直近の周期で読み取ったもの
+    pub(super) fn snapshot(&self) -> napi::Result<MutexGuard<'_, InputSnapshot>> {
+        lock_input_state(&self.snapshot, "input snapshot")
+    }
+
+    /// Run `job` on the thread between ticks with its IVRInput table and action cache,
+    /// and wait for the result.
+    /// スレッドの周期の合間に、スレッドの IVRInput テーブルとアクションキャッシュで `job` を実行し、
+    /// 結果を待つ
+    pub(super) fn run<T: Send + 'static>(
+        &self,
+        job: impl FnOnce(&vr::VR_IVRInput_FnTable, &mut InputActionCache) -> napi::Result<T>
+            + Send
+            + 'static,
+    ) -> napi::Result<T> {
+        let stopped = || napi::Error::from_reason("Input thread stopped before running the call");
+        let (reply, result) = mpsc::sync_channel(1);
+        let job: InputJob = Box::new(
+            move |input: &vr::VR_IVRInput_FnTable, cache: &mut InputActionCache| {
+                let _ = reply.send(job(input, cache));
+            },
+        );
+        self.jobs.send(job).map_err(|_| stopped())?;
+        if let Some(thread) = &self.handle {
+            thread.thread().unpark();
+        }
+        // A job dropped unrun (the thread exited) drops its reply sender too
+        // 実行されずに破棄されたジョブ (スレッド終了時) は返信側も破棄する
+        result.recv().map_err(|_| stopped())?
+    }
+
+    /// Overlay to hit-test against; None disables hit events / 交差判定するオーバーレイ (None でヒット通知を停止)
+    pub(super) fn set_overlay(&self, handle: Option<OverlayHandle>) {
+        self.overlay_handle
+            .store(handle.map_or(0, |handle| handle.as_u64()), Ordering::SeqCst);
+        if let Some(thread) = &self.handle {
+            thread.thread().unpark();
+        }
+    }
+
+    /// False once the thread exited on its own (e.g. the callback was released)
+    /// スレッドが自ら終了した場合 (コールバック解放時など) は false
+    pub(super) fn is_running(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.handle.take() {
+            thread.thread().unpark();
+            if thread.join().is_err() {
+                eprintln!("[vr-overlay-native] input thread panicked");
+            }
+        }
+    }
+}
+
+struct Worker {
+    tables: VrTables,
+    cache: InputActionCache,
+    state: SharedInputState,
+    haptics: Arc<Mutex<HapticQueue>>,
+    snapshot: Arc<Mutex<InputSnapshot>>,
+    jobs: mpsc::Receiver<InputJob>,
+    /// A gaze hit or dwell progress was last reported / 直前に視線のヒットか進捗を通知した
+    gazing: bool,
+    devices: Arc<Mutex<DeviceTable>>,
+    epoch: Instant,
+    poses: PoseSnapshot,
+    controllers: Vec<u32>,
+    hitting: HashSet<u32>,
+    /// Set while a controller's hit waits in the queue; newer hits are skipped until JS takes it
+    /// コントローラーのヒットがキューで待っている間は立つ (JS が受け取るまで新しいヒットは送らない)
+    hits_in_flight: HashMap<u32, Arc<AtomicBool>>,
+    target: u64,
+    overlay_handle: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    callback: InputEventCallback,
+}
+
+fn event(kind: &str, timestamp: f64) -> InputThreadEvent {
+    InputThreadEvent {
+        kind: kind.to_string(),
+        timestamp,
+        controllerIndex: None,
+        trigger: None,
+        gesture: None,
+        hit: None,
+        controllerIds: None,
+        device: None,
+        gaze: None,
+        radial: None,
+        swipe: None,
+    }
+}
+
+impl Worker {
+    fn run(mut self, interval: Duration) {
+        let mut next_tick = Instant::now();
+        while !self.stop.load(Ordering::SeqCst) {
+            self.run_jobs();
+            match self.tick() {
+                Ok(true) => {}
+                // JS side released the callback / JS 側がコールバックを解放した
+                Ok(false) => break,
+                Err(e) => eprintln!("[vr-overlay-native] input thread: {}", e.reason),
+            }
+
+            next_tick += interval;
+            let now = Instant::now();
+            if next_tick <= now {
+                // Fell behind: do not try to catch up / 遅延時は追いつこうとしない
+                next_tick = now;
+                continue;
+            }
+            while !self.stop.load(Ordering::SeqCst) {
+                let now = Instant::now();
+                if now >= next_tick {
+                    break;
+                }
+                std::thread::park_timeout(next_tick - now);
+                self.run_jobs();
+            }
+        }
+    }
+
+    /// Run calls the manager routed to this thread / マネージャーがこのスレッドに回した呼び出しを実行
+    fn run_jobs(&mut self) {
+        // SAFETY: see VrTables / VrTables を参照
+        let input = self.tables.input.map(|input| unsafe { input.as_ref() });
+        while let Ok(job) = self.jobs.try_recv() {
+            // The manager checks for IVRInput before sending; without it the caller gets an error
+            // マネージャーは送信前に IVRInput を確認済み (無ければ呼び出し側はエラーになる)
+            if let Some(input) = input {
+                job(input, &mut self.cache);
+            }
+        }
+    }
+
+    fn now_seconds(&self) -> f64 {
+        self.epoch.elapsed().as_secs_f64()
+    }
+
+    /// Returns false when JS can no longer receive events; a full queue drops the event.
+    /// JS がイベントを受け取れない場合は false (キューが満杯ならイベントを破棄する)
+    fn emit(&self, event: InputThreadEvent) -> bool {
+        let kind = event.kind.clone();
+        match self
+            .callback
+            .call(event, ThreadsafeFunctionCallMode::NonBlocking)
+        {
+            Status::Closing => false,
+            Status::QueueFull => {
+                eprintln!("[vr-overlay-native] input event queue is full, dropped a {kind} event");
+                true
+            }
+            _ => true,
+        }
+    }
+
+    /// Send a controller's hit unless its previous one is still queued; the next tick sends
+    /// a newer hit anyway, so only the latest position reaches a busy JS thread.
+    /// 前回のヒットがキューに残っていなければ送る。次の周期で新しいヒットを送るため、
+    /// 忙しい JS スレッドには最新の位置だけが届く
+    fn emit_hit(&mut self, index: u32, event: InputThreadEvent) -> bool {
+        let in_flight = self.hits_in_flight.entry(index).or_default().clone();
+        if in_flight.swap(true, Ordering::AcqRel) {
+            return true;
+        }
+        let delivered = in_flight.clone();
+        let status = self.callback.call_with_return_value(
+            event,
+            ThreadsafeFunctionCallMode::NonBlocking,
+            move |_, _| {
+                delivered.store(false, Ordering::Release);
+                Ok(())
+            },
+        );
+        match status {
+            Status::Ok => true,
+            Status::Closing => false,
+            // Queue full: this hit is dropped / キューが満杯: このヒットは破棄
+            _ => {
+                in_flight.store(false, Ordering::Release);
+                true
+            }
+        }
+    }
+
+    fn tick(&mut self) -> napi::Result<bool> {
+        let now = self.now_seconds();
+        // SAFETY: see VrTables / VrTables を参照
+        let system = unsafe { self.tables.system.as_ref() };
+        let overlay = unsafe { self.tables.overlay.as_ref() };
+        let input = match self.tables.input {
+            Some(input) if self.cache.initialized => Some(unsafe { input.as_ref() }),
+            _ => None,
+        };
+        let mut open = true;
+
+        if let Some(input) = input {
+            update_action_state(input, &self.cache)?;
+            let held = read_toggle_state(input, &self.cache)?;
+            let gestures = {
+                let mut toggle = self.state.toggle()?;
+                let config = toggle.config;
+                toggle.recognizer.update(held, now, &config)
+            };
+            for gesture in gestures {
+                open &= self.emit(InputThreadEvent {
+                    gesture: Some(toggle_gesture_event(gesture)),
+                    ..event("toggle", gesture.timestamp)
+                });
+            }
+        }
+        self.play_due_haptics(system, input, now)?;
+
+        let (ids, changes) = {
+            let mut devices = lock_device_table(&self.devices)?;
+            for (previous, index) in pump_device_events(system, &mut devices, now)? {
+                self.state.remap_controller(previous, index)?;
+            }
+            (devices.controller_ids(), devices.drain_changes())
+        };
+        for change in changes {
+            let timestamp = change.timestamp;
+            open &= self.emit(InputThreadEvent {
+                device: Some(device_event(change)),
+                ..event("device", timestamp)
+            });
+        }
+        refresh_pose_snapshot(system, &mut self.poses, now)?;
+        self.publish_snapshot(system, input, &ids)?;
+        if ids != self.controllers {
+            self.controllers = ids.clone();
+            open &= self.emit(InputThreadEvent {
+                controllerIds: Some(ids.clone()),
+                ..event("devices", now)
+            });
+        }
+
+        let mut samples = Vec::with_capacity(ids.len());
+        for index in ids.iter().copied() {
+            samples.push((
+                index,
+                read_trigger_sample(system, input, &self.cache, index, now)?,
+            ));
+        }
+        let edges = self.state.trigger()?.update_all(samples, now);
+        for (index, edge) in edges {
+            open &= self.emit(InputThreadEvent {
+                controllerIndex: Some(index),
+                trigger: Some(trigger_event(index, edge)),
+                ..event("trigger", edge.timestamp)
+            });
+            let finished = self.state.swipe()?.trigger_edge(index, &edge);
+            if let Some((trail, candidates)) = finished {
+                open &= self.emit(InputThreadEvent {
+                    controllerIndex: Some(index),
+                    swipe: Some(swipe_result(index, trail, candidates)),
+                    ..event("swipe", edge.timestamp)
+                });
+            }
+        }
+
+        let radial_enabled = self.state.radial()?.config.enabled;
+        if radial_enabled {
+            let mut samples = Vec::with_capacity(ids.len());
+            for index in ids.iter().copied() {
+                samples.push((
+                    index,
+                    read_radial_sample(system, input, &self.cache, index, now)?,
+                ));
+            }
+            let events = self.state.radial()?.update_all(samples, now);
+            for (index, radial) in events {
+                let timestamp = radial.timestamp;
+                open &= self.emit(InputThreadEvent {
+                    controllerIndex: Some(index),
+                    radial: Some(radial_event(index, radial)),
+                    ..event("radial", timestamp)
+                });
+            }
+        }
+
+        let target = self.overlay_handle.load(Ordering::SeqCst);
+        if target != self.target {
+            // Hits on the previous overlay end here / 以前のオーバーレイへのヒットはここで終了
+            open &= self.end_hits(|_| true, now)?;
+            self.state.smoothing()?.stabilizers.clear();
+            self.state.gaze()?.dwell.reset();
+            self.target = target;
+        }
+        open &= self.end_hits(|index| !ids.contains(&index), now)?;
+        if target == 0 {
+            open &= self.end_gaze(now);
+            return Ok(open);
+        }
+
+        let gaze_enabled = self.state.gaze()?.config.enabled;
+        if gaze_enabled {
+            let pose = self.poses.device_pose(HMD_DEVICE_INDEX);
+            let timestamp = pose.as_ref().map_or(now, |pose| pose.timestamp);
+            let overlay_handle = OverlayHandle::from_u64(target);
+            let hit = gaze_hit(overlay, overlay_handle, pose, &mut self.state.smoothing()?)?;
+            let status = self.state.gaze()?.update(
+                target,
+                hit.as_ref().map(|hit| [hit.u, hit.v]),
+                timestamp,
+            );
+            let active = hit.is_some() || status.region.is_some();
+            if active || self.gazing {
+                self.gazing = active;
+                let mut update = gaze_update(status, hit, timestamp);
+                // Dwell clicks go out as trigger events, like controller clicks
+                // 注視クリックはコントローラーのクリックと同じくトリガーイベントで送る
+                for click in std::mem::take(&mut update.clicks) {
+                    let timestamp = click.timestamp;
+                    open &= self.emit(InputThreadEvent {
+                        controllerIndex: Some(HMD_DEVICE_INDEX),
+                        trigger: Some(click),
+                        ..event("trigger", timestamp)
+                    });
+                }
+                open &= self.emit(InputThreadEvent {
+                    gaze: Some(update),
+                    ..event("gaze", timestamp)
+                });
+            }
+        } else {
+            // Disabled while running / 実行中に無効化された
+            open &= self.end_gaze(now);
+        }
+        // Aim with the pointer rays this tick published / この周期で公開したポインターレイで照準する
+        let pointers: HashMap<u32, PointerRay> = {
+            let snapshot = lock_input_state(&self.snapshot, "input snapshot")?;
+            snapshot
+                .controllers
+                .iter()
+                .filter_map(|(index, controller)| Some((*index, controller.pointer.clone()?)))
+                .collect()
+        };
+        for index in ids {
+            let key = (index, target);
+            let raw = match aim_ray(pointers.get(&index), self.poses.device_pose(index).as_ref()) {
+                Some((origin, direction)) => intersect_overlay(
+                    overlay,
+                    OverlayHandle::from_u64(target),
+                    origin.map(|v| v as f32),
+                    direction.map(|v| v as f32),
+                )?
+                .map(|raw| (raw, self.poses.timestamp)),
+                None => None,
+            };
+
+            match raw {
+                Some((raw, timestamp)) => {
+                    let hit = smooth_hit(&mut self.state.smoothing()?, key, &raw, timestamp);
+                    self.hitting.insert(index);
+                    self.state
+                        .swipe()?
+                        .hover(index, target, Some([hit.u, hit.v]));
+                    let timestamp = self.poses.timestamp;
+                    open &= self.emit_hit(
+                        index,
+                        InputThreadEvent {
+                            controllerIndex: Some(index),
+                            hit: Some(hit),
+                            ..event("hit", timestamp)
+                        },
+                    );
+                }
+                None => {
+                    self.state.smoothing()?.stabilizers.remove(&key);
+                    open &= self.end_hits(|hitting| hitting == index, now)?;
+                }
+            }
+        }
+        Ok(open)
+    }
+
+    /// Share what this tick read with the manager's controller and pose queries.
+    /// この周期で読み取ったものを、マネージャーのコントローラー・姿勢の問い合わせと共有する
+    fn publish_snapshot(
+        &self,
+        system: &vr::VR_IVRSystem_FnTable,
+        input: Option<&vr::VR_IVRInput_FnTable>,
+        ids: &[u32],
+    ) -> napi::Result<()> {
+        let mut controllers = HashMap::with_capacity(ids.len());
+        for index in ids.iter().copied() {
+            let sources = action_sources(system, index, &self.cache);
+            let (analog, pointer) = match input {
+                Some(input) => (
+                    Some(read_analog_state(input, &self.cache, &sources)),
+                    // A failed read is reported like an untracked pose / 読み取り失敗は未トラッキングと同じ扱い
+                    read_pointer_ray(input, &self.cache, &sources, None)
+                        .ok()
+                        .flatten(),
+                ),
+                None => (None, None),
+            };
+            controllers.insert(
+                index,
+                ControllerSnapshot {
+                    hand: controller_hand_for(system, index),
+                    state: read_controller_state(system, input, &self.cache, index)?,
+                    analog,
+                    pointer,
+                },
+            );
+        }
+        let fingertips = Hand::ALL
+            .map(|hand| input.and_then(|input| read_index_fingertip(input, &self.cache, hand)));
+
+        let mut snapshot = lock_input_state(&self.snapshot, "input snapshot")?;
+        snapshot.poses.clone_from(&self.poses);
+        snapshot.controllers = controllers;
+        snapshot.fingertips = fingertips;
+        Ok(())
+    }
+
+    /// Play queued haptic pulses that are due, like OverlayManager does on the JS thread.
+    /// 期限の来た振動パルスを、JS スレッドでの OverlayManager と同じ方法で再生する
+    fn play_due_haptics(
+        &self,
+        system: &vr::VR_IVRSystem_FnTable,
+        input: Option<&vr::VR_IVRInput_FnTable>,
+        now: f64,
+    ) -> napi::Result<()> {
+        let due = lock_input_state(&self.haptics, "haptic queue")?.take_due(now);
+        for scheduled in due {
+            let (index, pulse) = (scheduled.controller_index, scheduled.pulse);
+            if !play_action_pulse(system, input, &self.cache, index, pulse) {
+                play_legacy_pulse(system, index, pulse)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Report the gaze pointer as gone once / 視線ポインターが消えたことを一度だけ通知
+    fn end_gaze(&mut self, now: f64) -> bool {
+        if !self.gazing {
+            return true;
+        }
+        self.gazing = false;
+        self.emit(InputThreadEvent {
+            gaze: Some(gaze_update(DwellStatus::default(), None, now)),
+            ..event("gaze", now)
+        })
+    }
+
+    /// Emit a hit event without a hit for controllers that left the overlay.
+    /// オーバーレイから外れたコントローラーに、ヒット無しのイベントを送る
+    fn end_hits(&mut self, ended: impl Fn(u32) -> bool, now: f64) -> napi::Result<bool> {
+        let mut open = true;
+        let finished: Vec<u32> = self
+            .hitting
+            .iter()
+            .copied()
+            .filter(|index| ended(*index))
+            .collect();
+        for index in finished {
+            self.hitting.remove(&index);
+            self.state.swipe()?.hover(index, self.target, None);
+            open &= self.emit(InputThreadEvent {
+                controllerIndex: Some(index),
+                ..event("hit", now)
+            });
+        }
+        Ok(open)
+    }
+}
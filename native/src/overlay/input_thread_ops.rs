@@ -0,0 +1,167 @@
+use napi::threadsafe_function::ThreadsafeFunction;
+use napi::Status;
+use napi_derive::napi;
+use openvr_sys as vr;
+use std::ptr::NonNull;
+use std::time::Duration;
+
+use super::constants::{FALLBACK_DISPLAY_FREQUENCY_HZ, HMD_DEVICE_INDEX, INPUT_EVENT_QUEUE_SIZE};
+use super::device_ops::float_property;
+use super::handles::overlay_handle;
+use super::input_thread::{InputSnapshot, InputThread, InputThreadSetup, VrTables};
+use super::manager::{InputActionCache, OverlayManager};
+use super::types::{InputThreadEvent, InputThreadOptions};
+
+fn display_frequency(system: &vr::VR_IVRSystem_FnTable) -> f64 {
+    float_property(
+        system,
+        HMD_DEVICE_INDEX,
+        vr::ETrackedDeviceProperty_Prop_DisplayFrequency_Float,
+    )
+    .filter(|hz| *hz > 0.0)
+    .map_or(FALLBACK_DISPLAY_FREQUENCY_HZ, f64::from)
+}
+
+impl OverlayManager {
+    pub(super) fn input_thread_running(&self) -> napi::Result<bool> {
+        Ok(self
+            .borrow_input_thread_mut()?
+            .as_ref()
+            .is_some_and(|thread| thread.is_running()))
+    }
+
+    /// Read the running input thread's latest tick; None when the thread is not running.
+    /// While it runs it is the only caller of IVRInput and of the controller and pose parts
+    /// of IVRSystem, so main-thread queries that need them answer from this instead.
+    /// 実行中の入力スレッドの直近の周期を読む (スレッドが実行中でなければ None)。
+    /// 実行中は IVRInput と IVRSystem のコントローラー・姿勢関連を呼ぶのはスレッドのみのため、
+    /// それらを使うメインスレッドの問い合わせはこれで答える。
+    pub(super) fn read_input_snapshot<R>(
+        &self,
+        read: impl FnOnce(&InputSnapshot) -> R,
+    ) -> napi::Result<Option<R>> {
+        let slot = self.borrow_input_thread_mut()?;
+        match slot.as_ref().filter(|thread| thread.is_running()) {
+            Some(thread) => Ok(Some(read(&*thread.snapshot()?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Call IVRInput with the action cache: on the input thread while it runs, here otherwise.
+    /// アクションキャッシュと共に IVRInput を呼ぶ (入力スレッドの実行中はスレッド上、それ以外はここで)
+    pub(super) fn with_input<T: Send + 'static>(
+        &self,
+        job: impl FnOnce(&vr::VR_IVRInput_FnTable, &mut InputActionCache) -> napi::Result<T>
+            + Send
+            + 'static,
+    ) -> napi::Result<T> {
+        let input = self.input()?;
+        if let Some(thread) = self
+            .borrow_input_thread_mut()?
+            .as_ref()
+            .filter(|thread| thread.is_running())
+        {
+            return thread.run(job);
+        }
+        job(input, &mut self.borrow_input_cache_mut()?)
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Poll input on a native thread and deliver toggle, trigger, hit, device, gaze, radial and swipe events to `callback`.
+    /// Call after init_input. Settings configured while it runs apply from its next tick.
+    /// While running, the poll methods it replaces return nothing, queries that read controllers
+    /// answer from the thread's latest tick, SteamVR Input calls run on the thread, and haptics
+    /// are played by the thread.
+    /// Events wait in a bounded queue: while JS is busy each controller has at most one hit
+    /// queued, and other events are dropped once the queue is full.
+    /// ネイティブスレッドで入力をポーリングし、トグル・トリガー・ヒット・デバイス・視線・放射状入力・スワイプのイベントを `callback` に渡す。
+    /// init_input の後に呼ぶこと。実行中に変更した設定は次の周期から反映される。
+    /// 実行中は、置き換えられる poll 系メソッドは何も返さず、コントローラーを読む問い合わせは
+    /// スレッドの直近の周期の値で答え、SteamVR Input の呼び出しはスレッド上で実行し、振動はスレッドが再生する。
+    /// イベントは上限付きのキューで待つ。JS が忙しい間はコントローラーごとに最大 1 件のヒットだけが
+    /// キューに入り、キューが満杯になると他のイベントは破棄される。
+    #[napi]
+    pub fn start_input_thread(
+        &self,
+        callback: ThreadsafeFunction<
+            InputThreadEvent,
+            (),
+            InputThreadEvent,
+            Status,
+            false,
+            false,
+            INPUT_EVENT_QUEUE_SIZE,
+        >,
+        options: Option<InputThreadOptions>,
+    ) -> napi::Result<()> {
+        let mut slot = self.borrow_input_thread_mut()?;
+        if slot.as_ref().is_some_and(|thread| thread.is_running()) {
+            return Err(napi::Error::from_reason("Input thread is already running"));
+        }
+        // Join a thread that exited on its own before starting a new one
+        // 自ら終了したスレッドを join してから新しく開始する
+        slot.take();
+
+        let system = self.system()?;
+        let options = options.unwrap_or(InputThreadOptions {
+            rateHz: None,
+            overlayHandle: None,
+        });
+
+        let max_rate = display_frequency(system);
+        let rate = match options.rateHz {
+            Some(rate) if !rate.is_finite() || rate <= 0.0 => {
+                return Err(napi::Error::from_reason("rateHz must be a positive number"));
+            }
+            Some(rate) => rate.min(max_rate),
+            None => max_rate,
+        };
+        let overlay = options.overlayHandle.map(overlay_handle).transpose()?;
+
+        let setup = InputThreadSetup {
+            tables: VrTables {
+                overlay: self.overlay_ptr()?,
+                system: NonNull::from(system),
+                input: self.input().ok().map(NonNull::from),
+            },
+            cache: self.borrow_input_cache()?.clone(),
+            state: self.shared_input_state(),
+            haptics: self.haptic_queue(),
+            devices: self.device_table(),
+            epoch: self.epoch(),
+            interval: Duration::from_secs_f64(1.0 / rate),
+        };
+
+        *slot = Some(InputThread::spawn(setup, overlay, callback)?);
+        Ok(())
+    }
+
+    /// Stop and join the input thread; no-op if it is not running.
+    /// 入力スレッドを停止して join する (未実行なら何もしない)
+    #[napi]
+    pub fn stop_input_thread(&self) -> napi::Result<()> {
+        self.borrow_input_thread_mut()?.take();
+        Ok(())
+    }
+
+    /// Change the overlay the input thread hit-tests; null stops hit events.
+    /// 入力スレッドが交差判定するオーバーレイを変更 (null でヒット通知を停止)
+    #[napi]
+    pub fn set_input_thread_overlay(&self, handle: Option<i64>) -> napi::Result<()> {
+        let handle = handle.map(overlay_handle).transpose()?;
+        match self.borrow_input_thread_mut()?.as_ref() {
+            Some(thread) => {
+                thread.set_overlay(handle);
+                Ok(())
+            }
+            None => Err(napi::Error::from_reason("Input thread is not running")),
+        }
+    }
+
+    #[napi]
+    pub fn is_input_thread_running(&self) -> napi::Result<bool> {
+        self.input_thread_running()
+    }
+}
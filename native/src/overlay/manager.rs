@@ -6,26 +6,64 @@ use std::ptr::NonNull;
 use std::rc::Rc;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
-    Mutex, OnceLock,
+    Arc, Mutex, MutexGuard, OnceLock,
 };
+use std::time::Instant;
 
+use super::atlas::AtlasTable;
 use super::constants::{
     DEFAULT_INPUT_INTERFACE, DEFAULT_OVERLAY_INTERFACE, DEFAULT_SYSTEM_INTERFACE,
     INPUT_INTERFACE_ENV, OVERLAY_INTERFACE_ENV, SYSTEM_INTERFACE_ENV,
 };
 use super::d3d11;
-use super::d3d11::D3D11Context;
+use super::device_ops::lock_device_table;
+use super::devices::DeviceTable;
 use super::errors::{cstring_from_env, init_error_message};
+use super::gaze::GazeState;
+use super::haptics::HapticQueue;
+use super::input_thread::{InputThread, SharedInputState};
+use super::poke::PokeState;
+use super::radial::RadialState;
+use super::smoothing::SmoothingState;
+use super::swipe::SwipeState;
+use super::texture_pipeline::{lock_pipeline, TexturePipeline, UploadQueue};
+use super::toggle::ToggleState;
+use super::trigger::TriggerState;
+
+/// Lock an input state shared with the input thread / 入力スレッドと共有する入力状態をロック
+pub(super) fn lock_input_state<'a, T>(
+    state: &'a Mutex<T>,
+    name: &str,
+) -> napi::Result<MutexGuard<'a, T>> {
+    state
+        .lock()
+        .map_err(|_| napi::Error::from_reason(format!("{} lock poisoned", name)))
+}
 
 static VR_INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
 static VR_INIT_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
-fn create_poses_cache() -> Vec<vr::TrackedDevicePose_t> {
-    let mut poses = Vec::with_capacity(vr::k_unMaxTrackedDeviceCount as usize);
-    poses.resize_with(vr::k_unMaxTrackedDeviceCount as usize, || unsafe {
-        std::mem::zeroed()
-    });
-    poses
+/// Device poses fetched once per compositor frame, with the time they were sampled.
+/// コンポジターのフレーム毎に一度取得するデバイス姿勢と、そのサンプリング時刻
+#[derive(Clone)]
+pub(super) struct PoseSnapshot {
+    pub poses: Vec<vr::TrackedDevicePose_t>,
+    pub frame_index: Option<u64>,
+    pub timestamp: f64,
+}
+
+impl PoseSnapshot {
+    pub(super) fn new() -> Self {
+        let mut poses = Vec::with_capacity(vr::k_unMaxTrackedDeviceCount as usize);
+        poses.resize_with(vr::k_unMaxTrackedDeviceCount as usize, || unsafe {
+            std::mem::zeroed()
+        });
+        Self {
+            poses,
+            frame_index: None,
+            timestamp: 0.0,
+        }
+    }
 }
 
 struct VrContext {
@@ -34,17 +72,21 @@ struct VrContext {
     input: Option<NonNull<vr::VR_IVRInput_FnTable>>,
 }
 
+#[derive(Clone)]
 pub(super) struct InputActionCache {
     pub initialized: bool,
     pub action_set_handle: vr::VRActionSetHandle_t,
     pub toggle_action_handle: vr::VRActionHandle_t,
     pub trigger_action_handle: vr::VRActionHandle_t,
     pub grip_action_handle: vr::VRActionHandle_t,
+    pub trigger_value_action_handle: vr::VRActionHandle_t,
+    pub scroll_action_handle: vr::VRActionHandle_t,
+    pub pointer_action_handle: vr::VRActionHandle_t,
+    pub haptic_action_handle: vr::VRActionHandle_t,
+    pub skeleton_left_action_handle: vr::VRActionHandle_t,
+    pub skeleton_right_action_handle: vr::VRActionHandle_t,
     pub left_hand_source: vr::VRInputValueHandle_t,
     pub right_hand_source: vr::VRInputValueHandle_t,
-    pub last_toggle_state: bool,
-    pub toggle_lock: bool,
-    pub toggle_release_streak: u8,
 }
 
 impl InputActionCache {
@@ -55,11 +97,14 @@ impl InputActionCache {
             toggle_action_handle: 0,
             trigger_action_handle: 0,
             grip_action_handle: 0,
+            trigger_value_action_handle: 0,
+            scroll_action_handle: 0,
+            pointer_action_handle: 0,
+            haptic_action_handle: 0,
+            skeleton_left_action_handle: 0,
+            skeleton_right_action_handle: 0,
             left_hand_source: 0,
             right_hand_source: 0,
-            last_toggle_state: false,
-            toggle_lock: false,
-            toggle_release_streak: 0,
         }
     }
 }
@@ -70,9 +115,32 @@ impl InputActionCache {
 #[napi]
 pub struct OverlayManager {
     context: VrContext,
-    d3d11: Option<D3D11Context>,
-    poses_cache: RefCell<Vec<vr::TrackedDevicePose_t>>,
+    poses_cache: RefCell<PoseSnapshot>,
     input_cache: RefCell<InputActionCache>,
+    /// Input states are shared with the input thread, which updates them while it runs
+    /// 入力の状態は入力スレッドと共有する (実行中はスレッド側が更新する)
+    poke: Arc<Mutex<PokeState>>,
+    smoothing: Arc<Mutex<SmoothingState>>,
+    trigger: Arc<Mutex<TriggerState>>,
+    toggle: Arc<Mutex<ToggleState>>,
+    gaze: Arc<Mutex<GazeState>>,
+    radial: Arc<Mutex<RadialState>>,
+    swipe: Arc<Mutex<SwipeState>>,
+    atlas: RefCell<AtlasTable>,
+    /// Reused CPU conversion output for SetOverlayRaw / SetOverlayRaw 用の CPU 変換結果 (再利用)
+    pixel_scratch: RefCell<Vec<u8>>,
+    /// Shared with asynchronous upload workers / 非同期アップロードのワーカーと共有
+    pipeline: Arc<Mutex<TexturePipeline>>,
+    upload_queue: Arc<UploadQueue>,
+    input_thread: RefCell<Option<InputThread>>,
+    /// Timer threads playing delayed legacy haptic pulses / 遅延付きのレガシー振動パルスを再生するタイマースレッド
+    /// Delayed pulses, played once due by the input thread or by JS-thread polls
+    /// 遅延付きのパルス (期限が来たら入力スレッドか JS スレッドのポーリングで再生する)
+    haptics: Arc<Mutex<HapticQueue>>,
+    /// Shared with the input thread, which pumps device events while it runs
+    /// 入力スレッドと共有 (実行中はスレッド側がデバイスイベントを処理する)
+    devices: Arc<Mutex<DeviceTable>>,
+    epoch: Instant,
     _vr_token: Option<isize>,
     // Make the manager !Send/!Sync unless we can prove thread safety.
     _not_send: PhantomData<Rc<()>>,
@@ -101,13 +169,7 @@ impl OverlayManager {
         Ok(unsafe { ptr.as_ref() })
     }
 
-    pub(super) fn d3d11_mut(&mut self) -> Option<&mut D3D11Context> {
-        self.d3d11.as_mut()
-    }
-
-    pub(super) fn borrow_poses_cache(
-        &self,
-    ) -> napi::Result<std::cell::RefMut<'_, Vec<vr::TrackedDevicePose_t>>> {
+    pub(super) fn borrow_poses_cache(&self) -> napi::Result<std::cell::RefMut<'_, PoseSnapshot>> {
         self.poses_cache
             .try_borrow_mut()
             .map_err(|_| napi::Error::from_reason("poses_cache is already borrowed"))
@@ -134,6 +196,103 @@ impl OverlayManager {
             .try_borrow_mut()
             .map_err(|_| napi::Error::from_reason("input_cache is already mutably borrowed"))
     }
+
+    pub(super) fn lock_poke(&self) -> napi::Result<MutexGuard<'_, PokeState>> {
+        lock_input_state(&self.poke, "poke state")
+    }
+
+    pub(super) fn lock_smoothing(&self) -> napi::Result<MutexGuard<'_, SmoothingState>> {
+        lock_input_state(&self.smoothing, "smoothing state")
+    }
+
+    pub(super) fn lock_trigger(&self) -> napi::Result<MutexGuard<'_, TriggerState>> {
+        lock_input_state(&self.trigger, "trigger state")
+    }
+
+    pub(super) fn lock_toggle(&self) -> napi::Result<MutexGuard<'_, ToggleState>> {
+        lock_input_state(&self.toggle, "toggle state")
+    }
+
+    pub(super) fn lock_gaze(&self) -> napi::Result<MutexGuard<'_, GazeState>> {
+        lock_input_state(&self.gaze, "gaze state")
+    }
+
+    pub(super) fn lock_radial(&self) -> napi::Result<MutexGuard<'_, RadialState>> {
+        lock_input_state(&self.radial, "radial state")
+    }
+
+    pub(super) fn lock_swipe(&self) -> napi::Result<MutexGuard<'_, SwipeState>> {
+        lock_input_state(&self.swipe, "swipe state")
+    }
+
+    pub(super) fn borrow_atlas_mut(&self) -> napi::Result<std::cell::RefMut<'_, AtlasTable>> {
+        self.atlas
+            .try_borrow_mut()
+            .map_err(|_| napi::Error::from_reason("atlas table is already borrowed"))
+    }
+
+    pub(super) fn borrow_pixel_scratch_mut(&self) -> napi::Result<std::cell::RefMut<'_, Vec<u8>>> {
+        self.pixel_scratch
+            .try_borrow_mut()
+            .map_err(|_| napi::Error::from_reason("pixel buffer is already borrowed"))
+    }
+
+    pub(super) fn borrow_input_thread_mut(
+        &self,
+    ) -> napi::Result<std::cell::RefMut<'_, Option<InputThread>>> {
+        self.input_thread
+            .try_borrow_mut()
+            .map_err(|_| napi::Error::from_reason("input thread is already borrowed"))
+    }
+
+    pub(super) fn lock_haptics(&self) -> napi::Result<MutexGuard<'_, HapticQueue>> {
+        lock_input_state(&self.haptics, "haptic queue")
+    }
+
+    pub(super) fn haptic_queue(&self) -> Arc<Mutex<HapticQueue>> {
+        self.haptics.clone()
+    }
+
+    pub(super) fn lock_devices(&self) -> napi::Result<MutexGuard<'_, DeviceTable>> {
+        lock_device_table(&self.devices)
+    }
+
+    pub(super) fn lock_pipeline(&self) -> napi::Result<MutexGuard<'_, TexturePipeline>> {
+        lock_pipeline(&self.pipeline)
+    }
+
+    pub(super) fn texture_pipeline(&self) -> Arc<Mutex<TexturePipeline>> {
+        self.pipeline.clone()
+    }
+
+    pub(super) fn upload_queue(&self) -> &Arc<UploadQueue> {
+        &self.upload_queue
+    }
+
+    pub(super) fn device_table(&self) -> Arc<Mutex<DeviceTable>> {
+        self.devices.clone()
+    }
+
+    pub(super) fn shared_input_state(&self) -> SharedInputState {
+        SharedInputState {
+            poke: self.poke.clone(),
+            smoothing: self.smoothing.clone(),
+            trigger: self.trigger.clone(),
+            toggle: self.toggle.clone(),
+            gaze: self.gaze.clone(),
+            radial: self.radial.clone(),
+            swipe: self.swipe.clone(),
+        }
+    }
+
+    pub(super) fn epoch(&self) -> Instant {
+        self.epoch
+    }
+
+    /// Monotonic seconds since the manager was created / マネージャー生成からの単調増加秒
+    pub(super) fn now_seconds(&self) -> f64 {
+        self.epoch.elapsed().as_secs_f64()
+    }
 }
 
 #[napi]
@@ -243,9 +402,23 @@ impl OverlayManager {
                     system: system_ptr,
                     input: input_ptr,
                 },
-                d3d11: d3d11_ctx,
-                poses_cache: RefCell::new(create_poses_cache()),
+                poses_cache: RefCell::new(PoseSnapshot::new()),
                 input_cache: RefCell::new(InputActionCache::new()),
+                poke: Arc::new(Mutex::new(PokeState::default())),
+                smoothing: Arc::new(Mutex::new(SmoothingState::default())),
+                trigger: Arc::new(Mutex::new(TriggerState::default())),
+                toggle: Arc::new(Mutex::new(ToggleState::default())),
+                gaze: Arc::new(Mutex::new(GazeState::default())),
+                radial: Arc::new(Mutex::new(RadialState::default())),
+                swipe: Arc::new(Mutex::new(SwipeState::default())),
+                atlas: RefCell::new(AtlasTable::default()),
+                pixel_scratch: RefCell::new(Vec::new()),
+                pipeline: Arc::new(Mutex::new(TexturePipeline::new(overlay_ptr, d3d11_ctx))),
+                upload_queue: Arc::new(UploadQueue::default()),
+                input_thread: RefCell::new(None),
+                haptics: Arc::new(Mutex::new(HapticQueue::default())),
+                devices: Arc::new(Mutex::new(DeviceTable::default())),
+                epoch: Instant::now(),
                 _vr_token: init_token,
                 _not_send: PhantomData,
             })
@@ -255,15 +428,22 @@ impl OverlayManager {
 
 impl Drop for OverlayManager {
     fn drop(&mut self) {
+        // Join the input thread while the interfaces it uses are still valid
+        // 入力スレッドが使うインターフェースが有効なうちに join する
+        self.input_thread.get_mut().take();
+
         // Clear pointers before VR shutdown to prevent dangling access
         // VR シャットダウン前にポインタをクリアしダングリングアクセスを防止
         self.context.overlay = None;
         self.context.system = None;
         self.context.input = None;
 
-        // Drop D3D11 resources before VR shutdown
-        // VR シャットダウン前に D3D11 リソースを解放
-        self.d3d11 = None;
+        // Drop D3D11 resources before VR shutdown; uploads still queued fail from now on
+        // VR シャットダウン前に D3D11 リソースを解放 (待機中のアップロードは以降失敗する)
+        match self.pipeline.lock() {
+            Ok(mut pipeline) => pipeline.close(),
+            Err(poisoned) => poisoned.into_inner().close(),
+        }
 
         let init_lock = VR_INIT_LOCK.get_or_init(|| Mutex::new(()));
         let _guard = match init_lock.lock() {
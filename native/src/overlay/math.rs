@@ -39,3 +39,32 @@ pub(super) fn hmd_matrix34_to_vec(m: &[[f32; 4]; 3]) -> Vec<f64> {
         1.0,
     ]
 }
+
+/// Extract a pointing ray from an OpenVR 3x4 pose (origin, normalized -Z axis)
+/// OpenVR の 3x4 姿勢からレイを取得 (原点, 正規化した -Z 軸)
+pub(super) fn ray_from_matrix34(m: &[[f32; 4]; 3]) -> ([f64; 3], [f64; 3]) {
+    let origin = [m[0][3] as f64, m[1][3] as f64, m[2][3] as f64];
+    let forward = [-m[0][2] as f64, -m[1][2] as f64, -m[2][2] as f64];
+    let length =
+        (forward[0] * forward[0] + forward[1] * forward[1] + forward[2] * forward[2]).sqrt();
+    if length <= f64::EPSILON {
+        return (origin, [0.0, 0.0, -1.0]);
+    }
+    (
+        origin,
+        [
+            forward[0] / length,
+            forward[1] / length,
+            forward[2] / length,
+        ],
+    )
+}
+
+/// Transform a point by an OpenVR 3x4 matrix / OpenVR の 3x4 行列で点を変換
+pub(super) fn transform_point34(m: &[[f32; 4]; 3], p: [f64; 3]) -> [f64; 3] {
+    let mut out = [0.0; 3];
+    for (i, row) in m.iter().enumerate() {
+        out[i] = row[0] as f64 * p[0] + row[1] as f64 * p[1] + row[2] as f64 * p[2] + row[3] as f64;
+    }
+    out
+}
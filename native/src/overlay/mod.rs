@@ -1,16 +1,61 @@
+mod action_manifest;
+mod action_manifest_ops;
+mod analog_ops;
+mod atlas;
+mod atlas_ops;
 mod buffers;
 mod constants;
 mod manager;
 mod controller_ops;
 mod d3d11;
+mod device_ops;
+mod devices;
+mod dirty;
 mod errors;
+mod gaze;
+mod gaze_ops;
+mod hand;
 mod handles;
+mod haptic_ops;
+mod haptics;
+mod image_decode;
+mod image_ops;
 mod input_ops;
+mod input_thread;
+mod input_thread_ops;
 mod math;
 mod overlay_ops;
+mod pixels;
+mod poke;
+mod poke_ops;
+mod pose_ops;
+mod radial;
+mod radial_ops;
+mod skeletal_ops;
+mod smoothing;
+mod smoothing_ops;
+mod snapshot;
+mod snapshot_ops;
+mod swipe;
+mod swipe_ops;
 mod texture_ops;
+mod texture_pipeline;
+mod texture_stats;
+mod toggle;
+mod toggle_ops;
 mod transform_ops;
+mod trigger;
+mod trigger_ops;
 mod types;
 
 pub use manager::OverlayManager;
-pub use types::{ControllerState, CurrentBindings, IntersectionResult, OverlayRelativeTransform};
+pub use types::{
+    ActionManifestFiles, ActionManifestIssue, AnalogActionData, AnalogActionState, BindingInfo,
+    ControllerIntersection, ControllerState, CurrentBindings, DeviceEvent, DurationStats,
+    FrameSubmission, GazeRegion, GazeSettings, GazeUpdate, HapticOptions, ImageInfo, ImageOptions,
+    InputThreadEvent, InputThreadOptions, IntersectionResult, OverlayRelativeTransform, PixelRect,
+    PointerRay, PokeEvent, PokeSettings, RadialTypingEvent, RadialTypingLayout,
+    RadialTypingSettings, SmoothingSettings, SwipeKey, SwipeResult, SwipeSettings, SwipeWord,
+    TextureDescriptor, TextureOutputSettings, TextureStats, TextureUpdate, ToggleGestureEvent,
+    ToggleGestureSettings, TrackedDeviceInfo, TriggerEvent, TriggerSettings,
+};
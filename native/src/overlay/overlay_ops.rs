@@ -8,6 +8,48 @@ use super::manager::OverlayManager;
 use super::math::vec3_f32;
 use super::types::IntersectionResult;
 
+/// Intersect a standing-space ray with an overlay / スタンディング空間のレイとオーバーレイの交差判定
+pub(super) fn intersect_overlay(
+    overlay: &vr::VR_IVROverlay_FnTable,
+    handle: OverlayHandle,
+    source: [f32; 3],
+    direction: [f32; 3],
+) -> napi::Result<Option<IntersectionResult>> {
+    let compute_intersection_fn = require_fn(
+        overlay.ComputeOverlayIntersection,
+        "ComputeOverlayIntersection",
+    )?;
+    unsafe {
+        let mut params = vr::VROverlayIntersectionParams_t {
+            vSource: vr::HmdVector3_t { v: source },
+            vDirection: vr::HmdVector3_t { v: direction },
+            eOrigin: vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
+        };
+
+        let mut results = vr::VROverlayIntersectionResults_t {
+            vPoint: vr::HmdVector3_t { v: [0.0; 3] },
+            vNormal: vr::HmdVector3_t { v: [0.0; 3] },
+            vUVs: vr::HmdVector2_t { v: [0.0; 2] },
+            fDistance: 0.0,
+        };
+
+        let success = compute_intersection_fn(handle.as_u64(), &mut params, &mut results);
+
+        if success {
+            Ok(Some(IntersectionResult {
+                x: results.vPoint.v[0] as f64,
+                y: results.vPoint.v[1] as f64,
+                z: results.vPoint.v[2] as f64,
+                u: results.vUVs.v[0] as f64,
+                v: results.vUVs.v[1] as f64,
+                distance: results.fDistance as f64,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 #[napi]
 impl OverlayManager {
     #[napi]
@@ -47,6 +89,8 @@ impl OverlayManager {
                 return Err(overlay_error("DestroyOverlay", overlay, err));
             }
         }
+        self.borrow_atlas_mut()?.forget(handle.as_u64());
+        self.lock_pipeline()?.forget_overlay(handle.as_u64());
         Ok(())
     }
 
@@ -152,41 +196,9 @@ impl OverlayManager {
         direction: Vec<f64>,
     ) -> napi::Result<Option<IntersectionResult>> {
         let overlay = self.overlay()?;
-        let compute_intersection_fn = require_fn(
-            overlay.ComputeOverlayIntersection,
-            "ComputeOverlayIntersection",
-        )?;
         let handle = overlay_handle(handle)?;
         let source = vec3_f32("source", &source)?;
         let direction = vec3_f32("direction", &direction)?;
-        unsafe {
-            let mut params = vr::VROverlayIntersectionParams_t {
-                vSource: vr::HmdVector3_t { v: source },
-                vDirection: vr::HmdVector3_t { v: direction },
-                eOrigin: vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
-            };
-
-            let mut results = vr::VROverlayIntersectionResults_t {
-                vPoint: vr::HmdVector3_t { v: [0.0; 3] },
-                vNormal: vr::HmdVector3_t { v: [0.0; 3] },
-                vUVs: vr::HmdVector2_t { v: [0.0; 2] },
-                fDistance: 0.0,
-            };
-
-            let success = compute_intersection_fn(handle.as_u64(), &mut params, &mut results);
-
-            if success {
-                Ok(Some(IntersectionResult {
-                    x: results.vPoint.v[0] as f64,
-                    y: results.vPoint.v[1] as f64,
-                    z: results.vPoint.v[2] as f64,
-                    u: results.vUVs.v[0] as f64,
-                    v: results.vUVs.v[1] as f64,
-                    distance: results.fDistance as f64,
-                }))
-            } else {
-                Ok(None)
-            }
-        }
+        intersect_overlay(overlay, handle, source, direction)
     }
 }
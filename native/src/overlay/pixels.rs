@@ -0,0 +1,666 @@
+use std::sync::OnceLock;
+
+/// Layout of 32-bit source pixels; the X formats carry no alpha
+/// 32 ビットのソースピクセルの形式 (X の形式はアルファを持たない)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PixelFormat {
+    Rgba8,
+    Bgra8,
+    Rgbx8,
+    Bgrx8,
+}
+
+impl PixelFormat {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rgba8" | "rgba" => Some(PixelFormat::Rgba8),
+            "bgra8" | "bgra" => Some(PixelFormat::Bgra8),
+            "rgbx8" => Some(PixelFormat::Rgbx8),
+            "bgrx8" => Some(PixelFormat::Bgrx8),
+            _ => None,
+        }
+    }
+
+    fn is_bgr(self) -> bool {
+        matches!(self, PixelFormat::Bgra8 | PixelFormat::Bgrx8)
+    }
+
+    fn has_alpha(self) -> bool {
+        matches!(self, PixelFormat::Rgba8 | PixelFormat::Bgra8)
+    }
+}
+
+/// What to do with the alpha channel / アルファチャンネルの扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum AlphaMode {
+    /// Keep alpha and color as they are / アルファと色をそのまま保持
+    Straight,
+    /// Multiply color by alpha / 色にアルファを乗算
+    Premultiply,
+    /// Divide color by alpha (premultiplied sources) / 色をアルファで除算 (乗算済みのソース向け)
+    Unpremultiply,
+    /// Force alpha to 255 (sources with garbage alpha) / アルファを 255 に固定 (アルファが不定のソース向け)
+    Opaque,
+}
+
+impl AlphaMode {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "straight" => Some(AlphaMode::Straight),
+            "premultiply" => Some(AlphaMode::Premultiply),
+            "opaque" => Some(AlphaMode::Opaque),
+            _ => None,
+        }
+    }
+
+    /// The conversion that produces this output from a `format` source whose color is
+    /// `premultiplied` or straight.
+    /// 色が乗算済み (`premultiplied`) またはストレートの `format` のソースからこの出力を得る変換
+    pub(super) fn for_source(self, format: PixelFormat, premultiplied: bool) -> Self {
+        if !format.has_alpha() {
+            return AlphaMode::Opaque;
+        }
+        match (self, premultiplied) {
+            (AlphaMode::Straight, true) => AlphaMode::Unpremultiply,
+            (AlphaMode::Premultiply, true) => AlphaMode::Straight,
+            (mode, _) => mode,
+        }
+    }
+}
+
+/// Alpha handling of a conversion / 変換でのアルファの扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct AlphaConversion {
+    pub mode: AlphaMode,
+    /// Color is sRGB-encoded and alpha is applied in linear light, as the GPU does through
+    /// sRGB views / 色は sRGB エンコードで、アルファは線形光で適用する (GPU の sRGB ビューと同じ)
+    pub srgb: bool,
+}
+
+impl From<AlphaMode> for AlphaConversion {
+    fn from(mode: AlphaMode) -> Self {
+        Self { mode, srgb: false }
+    }
+}
+
+/// Submission settings shared by the GPU and CPU paths / GPU と CPU のパスで共通の送信設定
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct TextureOutput {
+    pub color_space: ColorSpace,
+    /// sRGB texture formats, with alpha applied in linear light / sRGB のテクスチャ形式 (アルファは線形光で適用)
+    pub srgb: bool,
+}
+
+/// Color space reported to SteamVR with the texture / テクスチャと共に SteamVR に伝える色空間
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum ColorSpace {
+    #[default]
+    Auto,
+    Gamma,
+    Linear,
+}
+
+impl ColorSpace {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(ColorSpace::Auto),
+            "gamma" => Some(ColorSpace::Gamma),
+            "linear" => Some(ColorSpace::Linear),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SourceLayout {
+    pub width: usize,
+    pub height: usize,
+    /// Bytes from one row to the next; at least width * 4 / 行から次の行までのバイト数 (width * 4 以上)
+    pub row_pitch: usize,
+    pub format: PixelFormat,
+}
+
+impl SourceLayout {
+    pub(super) fn row_bytes(&self) -> usize {
+        self.width * 4
+    }
+
+    /// Already in the layout SetOverlayRaw takes / 既に SetOverlayRaw が受け取る形式か
+    pub(super) fn is_packed_rgba(&self) -> bool {
+        self.format == PixelFormat::Rgba8 && self.row_pitch == self.row_bytes()
+    }
+
+    /// Smallest source buffer holding every row (the last row needs no padding)
+    /// 全行を収めるのに必要な最小バッファサイズ (最終行にパディングは不要)
+    pub(super) fn required_len(&self) -> Option<usize> {
+        match self.height {
+            0 => Some(0),
+            height => (height - 1)
+                .checked_mul(self.row_pitch)?
+                .checked_add(self.row_bytes()),
+        }
+    }
+
+    /// Check that a source buffer of `len` bytes holds every row / `len` バイトのソースバッファが全行を収めるか検証
+    pub(super) fn validate(&self, len: usize) -> Result<(), String> {
+        let row_bytes = self.row_bytes();
+        if self.row_pitch < row_bytes {
+            return Err(format!(
+                "Row pitch {} is smaller than a row of {} pixels ({} bytes)",
+                self.row_pitch, self.width, row_bytes
+            ));
+        }
+        let required = self
+            .required_len()
+            .ok_or_else(|| "width/height too large".to_string())?;
+        if len < required {
+            return Err(format!(
+                "Buffer too small: need {} bytes for {}x{} with row pitch {}, got {}",
+                required, self.width, self.height, self.row_pitch, len
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// round(value * alpha / 255) without a division; exact for all u8 inputs
+/// 除算を使わない round(value * alpha / 255) (全ての u8 入力で正確)
+fn premultiply_channel(value: u8, alpha: u8) -> u8 {
+    let x = value as u32 * alpha as u32 + 128;
+    ((x + (x >> 8)) >> 8) as u8
+}
+
+/// sRGB transfer functions as specified for DXGI `_SRGB` formats / DXGI の `_SRGB` 形式と同じ sRGB 変換
+fn srgb_to_linear(value: u8) -> f64 {
+    let c = value as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(linear: f64) -> u8 {
+    let l = linear.clamp(0.0, 1.0);
+    let c = if l <= 0.003_130_8 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0 + 0.5) as u8
+}
+
+/// `table[alpha * 256 + value]`: `apply(linear, alpha)` on sRGB-encoded values
+/// `table[alpha * 256 + value]`: sRGB エンコードされた値への `apply(linear, alpha)`
+fn srgb_alpha_table(apply: fn(f64, f64) -> f64) -> Vec<u8> {
+    (0..256 * 256)
+        .map(|i| {
+            let (alpha, value) = (i / 256, i % 256);
+            linear_to_srgb(apply(srgb_to_linear(value as u8), alpha as f64 / 255.0))
+        })
+        .collect()
+}
+
+fn srgb_premultiply_table() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(|| srgb_alpha_table(|linear, alpha| linear * alpha))
+}
+
+fn srgb_unpremultiply_table() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        srgb_alpha_table(|linear, alpha| {
+            if alpha > 0.0 {
+                (linear / alpha).min(1.0)
+            } else {
+                0.0
+            }
+        })
+    })
+}
+
+fn premultiply_row(row: &mut [u8], srgb: bool) {
+    let table = srgb.then(srgb_premultiply_table);
+    for pixel in row.chunks_exact_mut(4) {
+        let alpha = pixel[3];
+        for channel in &mut pixel[..3] {
+            *channel = match table {
+                Some(table) => table[alpha as usize * 256 + *channel as usize],
+                None => premultiply_channel(*channel, alpha),
+            };
+        }
+    }
+}
+
+fn unpremultiply_row(row: &mut [u8], srgb: bool) {
+    let table = srgb.then(srgb_unpremultiply_table);
+    for pixel in row.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha == 255 {
+            continue;
+        }
+        for channel in &mut pixel[..3] {
+            *channel = match (table, alpha) {
+                (_, 0) => 0,
+                (Some(table), _) => table[alpha as usize * 256 + *channel as usize],
+                (None, _) => ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+            };
+        }
+    }
+}
+
+/// Reorder/mask one row with plain u32 operations / u32 演算だけで 1 行を並べ替え・マスクする
+fn shuffle_row_scalar(src: &[u8], dst: &mut [u8], swap: bool, alpha_or: u32) {
+    for (src, dst) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+        let mut pixel = u32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+        if swap {
+            pixel = (pixel & 0xFF00_FF00) | ((pixel & 0x00FF_0000) >> 16) | ((pixel & 0xFF) << 16);
+        }
+        dst.copy_from_slice(&(pixel | alpha_or).to_le_bytes());
+    }
+}
+
+/// SSE2 (always present on x86_64) version, four pixels per step.
+/// SSE2 版 (x86_64 では常に利用可能)。1 ステップで 4 ピクセルを処理する
+#[cfg(target_arch = "x86_64")]
+fn shuffle_row(src: &[u8], dst: &mut [u8], swap: bool, alpha_or: u32) {
+    use std::arch::x86_64::*;
+
+    let blocks = src.len() / 16;
+    // SAFETY: SSE2 is part of the x86_64 baseline; loads/stores are unaligned and
+    // stay within the first blocks * 16 bytes of both slices.
+    // SSE2 は x86_64 の基本命令セット。読み書きは非整列で、両スライスの先頭 blocks * 16 バイト内に収まる
+    unsafe {
+        let green_alpha = _mm_set1_epi32(0xFF00_FF00u32 as i32);
+        let red_blue = _mm_set1_epi32(0x00FF_00FF);
+        let alpha = _mm_set1_epi32(alpha_or as i32);
+        for block in 0..blocks {
+            let offset = block * 16;
+            let mut pixels = _mm_loadu_si128(src.as_ptr().add(offset) as *const __m128i);
+            if swap {
+                let rb = _mm_and_si128(pixels, red_blue);
+                pixels = _mm_or_si128(
+                    _mm_and_si128(pixels, green_alpha),
+                    _mm_or_si128(_mm_slli_epi32(rb, 16), _mm_srli_epi32(rb, 16)),
+                );
+            }
+            pixels = _mm_or_si128(pixels, alpha);
+            _mm_storeu_si128(dst.as_mut_ptr().add(offset) as *mut __m128i, pixels);
+        }
+    }
+    let done = blocks * 16;
+    shuffle_row_scalar(&src[done..], &mut dst[done..], swap, alpha_or);
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn shuffle_row(src: &[u8], dst: &mut [u8], swap: bool, alpha_or: u32) {
+    shuffle_row_scalar(src, dst, swap, alpha_or);
+}
+
+/// Convert `src` into the tightly packed RGBA SetOverlayRaw expects, in `dst` (resized to fit).
+/// CPU counterpart of the D3D11 conversion pass, used when that is unavailable.
+/// `src` を SetOverlayRaw が期待する詰めた RGBA に変換して `dst` に書き込む (`dst` はサイズを合わせる)。
+/// D3D11 の変換パスの CPU 版で、それが使えない場合に使う。
+pub(super) fn convert_to_rgba(
+    src: &[u8],
+    layout: &SourceLayout,
+    alpha: AlphaConversion,
+    dst: &mut Vec<u8>,
+) -> Result<(), String> {
+    layout.validate(src.len())?;
+    dst.resize(layout.row_bytes() * layout.height, 0);
+    convert_region(
+        src,
+        layout,
+        alpha,
+        [0, 0, layout.width, layout.height],
+        dst,
+        PixelFormat::Rgba8,
+    );
+    Ok(())
+}
+
+/// Convert the `[x, y, width, height]` region of an already validated `src` into the same
+/// region of `dst`, a packed frame of the full size in `output` (Rgba8 or Bgra8) order.
+/// 検証済みの `src` の `[x, y, width, height]` 領域を、全体サイズの詰めたフレーム `dst`
+/// (`output` の順序、Rgba8 か Bgra8) の同じ領域に変換する
+pub(super) fn convert_region(
+    src: &[u8],
+    layout: &SourceLayout,
+    alpha: AlphaConversion,
+    [x, y, width, height]: [usize; 4],
+    dst: &mut [u8],
+    output: PixelFormat,
+) {
+    debug_assert!(x + width <= layout.width && y + height <= layout.height);
+    let region_bytes = width * 4;
+    if region_bytes == 0 {
+        return;
+    }
+    let swap = layout.format.is_bgr() != output.is_bgr();
+    let alpha_or = if alpha.mode == AlphaMode::Opaque {
+        0xFF00_0000
+    } else {
+        0
+    };
+    for row in y..y + height {
+        let src_start = row * layout.row_pitch + x * 4;
+        let dst_start = (row * layout.width + x) * 4;
+        let src_row = &src[src_start..src_start + region_bytes];
+        let dst_row = &mut dst[dst_start..dst_start + region_bytes];
+        if swap || alpha_or != 0 {
+            shuffle_row(src_row, dst_row, swap, alpha_or);
+        } else {
+            dst_row.copy_from_slice(src_row);
+        }
+        match alpha.mode {
+            AlphaMode::Premultiply => premultiply_row(dst_row, alpha.srgb),
+            AlphaMode::Unpremultiply => unpremultiply_row(dst_row, alpha.srgb),
+            AlphaMode::Straight | AlphaMode::Opaque => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(width: usize, height: usize, row_pitch: usize, format: PixelFormat) -> SourceLayout {
+        SourceLayout {
+            width,
+            height,
+            row_pitch,
+            format,
+        }
+    }
+
+    fn convert(src: &[u8], layout: SourceLayout, alpha: AlphaMode) -> Vec<u8> {
+        let mut dst = Vec::new();
+        convert_to_rgba(src, &layout, alpha.into(), &mut dst).unwrap();
+        dst
+    }
+
+    /// Deterministic pseudo-random bytes / 決定的な疑似乱数のバイト列
+    fn pattern(len: usize) -> Vec<u8> {
+        let mut state = 0x1234_5678u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bgra_is_swizzled_to_rgba() {
+        let src = [10, 20, 30, 40, 1, 2, 3, 4];
+        let dst = convert(
+            &src,
+            layout(2, 1, 8, PixelFormat::Bgra8),
+            AlphaMode::Straight,
+        );
+        assert_eq!(dst, [30, 20, 10, 40, 3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn packed_rgba_is_copied_unchanged() {
+        let src = pattern(7 * 3 * 4);
+        let dst = convert(
+            &src,
+            layout(7, 3, 28, PixelFormat::Rgba8),
+            AlphaMode::Straight,
+        );
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn simd_matches_scalar_for_every_tail_length() {
+        for width in 0..=19 {
+            let src = pattern(width * 4);
+            for (swap, alpha_or) in [(true, 0), (false, 0xFF00_0000), (true, 0xFF00_0000)] {
+                let mut simd = vec![0; src.len()];
+                let mut scalar = vec![0; src.len()];
+                shuffle_row(&src, &mut simd, swap, alpha_or);
+                shuffle_row_scalar(&src, &mut scalar, swap, alpha_or);
+                assert_eq!(
+                    simd, scalar,
+                    "width {width}, swap {swap}, alpha {alpha_or:#x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn swizzle_is_its_own_inverse() {
+        let src = pattern(13 * 5 * 4);
+        let once = convert(
+            &src,
+            layout(13, 5, 52, PixelFormat::Bgra8),
+            AlphaMode::Straight,
+        );
+        let twice = convert(
+            &once,
+            layout(13, 5, 52, PixelFormat::Bgra8),
+            AlphaMode::Straight,
+        );
+        assert_eq!(twice, src);
+    }
+
+    #[test]
+    fn row_padding_is_skipped() {
+        #[rustfmt::skip]
+        let src = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0xEE, 0xEE, 0xEE, 0xEE,
+            9, 10, 11, 12, 13, 14, 15, 16,
+        ];
+        let dst = convert(
+            &src,
+            layout(2, 2, 12, PixelFormat::Bgra8),
+            AlphaMode::Straight,
+        );
+        assert_eq!(dst, [3, 2, 1, 4, 7, 6, 5, 8, 11, 10, 9, 12, 15, 14, 13, 16]);
+    }
+
+    #[test]
+    fn opaque_forces_alpha() {
+        let src = [1, 2, 3, 0, 4, 5, 6, 128];
+        let rgba = convert(&src, layout(2, 1, 8, PixelFormat::Rgba8), AlphaMode::Opaque);
+        assert_eq!(rgba, [1, 2, 3, 255, 4, 5, 6, 255]);
+        let bgra = convert(&src, layout(2, 1, 8, PixelFormat::Bgra8), AlphaMode::Opaque);
+        assert_eq!(bgra, [3, 2, 1, 255, 6, 5, 4, 255]);
+    }
+
+    #[test]
+    fn premultiply_rounds_like_division() {
+        for value in 0..=255u32 {
+            for alpha in 0..=255u32 {
+                let expected = ((value * alpha + 127) / 255) as u8;
+                assert_eq!(premultiply_channel(value as u8, alpha as u8), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn premultiply_applies_after_swizzle() {
+        let src = [200, 100, 50, 128, 255, 255, 255, 0, 9, 8, 7, 255];
+        let dst = convert(
+            &src,
+            layout(3, 1, 12, PixelFormat::Bgra8),
+            AlphaMode::Premultiply,
+        );
+        assert_eq!(dst, [25, 50, 100, 128, 0, 0, 0, 0, 7, 8, 9, 255]);
+    }
+
+    #[test]
+    fn short_buffer_and_narrow_pitch_are_rejected() {
+        let mut dst = Vec::new();
+        let short = convert_to_rgba(
+            &[0; 19],
+            &layout(2, 2, 12, PixelFormat::Bgra8),
+            AlphaMode::Straight.into(),
+            &mut dst,
+        );
+        assert!(short.is_err());
+        let narrow = convert_to_rgba(
+            &[0; 32],
+            &layout(2, 2, 7, PixelFormat::Bgra8),
+            AlphaMode::Straight.into(),
+            &mut dst,
+        );
+        assert!(narrow.is_err());
+    }
+
+    #[test]
+    fn region_matches_full_conversion() {
+        let source = layout(9, 7, 40, PixelFormat::Bgra8);
+        let src = pattern(40 * 7);
+        let full = convert(&src, source, AlphaMode::Premultiply);
+        let mut partial = vec![0; full.len()];
+        convert_region(
+            &src,
+            &source,
+            AlphaMode::Premultiply.into(),
+            [2, 1, 5, 4],
+            &mut partial,
+            PixelFormat::Rgba8,
+        );
+        for y in 0..7 {
+            for x in 0..9 {
+                let at = (y * 9 + x) * 4;
+                let inside = (2..7).contains(&x) && (1..5).contains(&y);
+                let expected: &[u8] = if inside { &full[at..at + 4] } else { &[0; 4] };
+                assert_eq!(&partial[at..at + 4], expected, "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn last_row_needs_no_padding() {
+        let src = pattern(12 + 8);
+        let dst = convert(
+            &src,
+            layout(2, 2, 12, PixelFormat::Rgba8),
+            AlphaMode::Straight,
+        );
+        assert_eq!(&dst[..8], &src[..8]);
+        assert_eq!(&dst[8..], &src[12..]);
+    }
+
+    #[test]
+    fn x_formats_ignore_source_alpha() {
+        let src = [1, 2, 3, 0, 4, 5, 6, 77];
+        for (format, expected) in [
+            (PixelFormat::Rgbx8, [1, 2, 3, 255, 4, 5, 6, 255]),
+            (PixelFormat::Bgrx8, [3, 2, 1, 255, 6, 5, 4, 255]),
+        ] {
+            let alpha = AlphaMode::Premultiply.for_source(format, false);
+            assert_eq!(convert(&src, layout(2, 1, 8, format), alpha), expected);
+        }
+    }
+
+    #[test]
+    fn premultiplied_sources_resolve_to_the_requested_output() {
+        let format = PixelFormat::Bgra8;
+        assert_eq!(
+            AlphaMode::Straight.for_source(format, true),
+            AlphaMode::Unpremultiply
+        );
+        assert_eq!(
+            AlphaMode::Premultiply.for_source(format, true),
+            AlphaMode::Straight
+        );
+        assert_eq!(
+            AlphaMode::Premultiply.for_source(format, false),
+            AlphaMode::Premultiply
+        );
+        assert_eq!(
+            AlphaMode::Opaque.for_source(format, true),
+            AlphaMode::Opaque
+        );
+    }
+
+    #[test]
+    fn unpremultiply_restores_straight_color() {
+        let src = [
+            25, 50, 100, 128, 7, 8, 9, 0, 200, 10, 255, 255, 90, 0, 0, 60,
+        ];
+        let dst = convert(
+            &src,
+            layout(4, 1, 16, PixelFormat::Rgba8),
+            AlphaMode::Unpremultiply,
+        );
+        assert_eq!(
+            dst,
+            [50, 100, 199, 128, 0, 0, 0, 0, 200, 10, 255, 255, 255, 0, 0, 60]
+        );
+    }
+
+    #[test]
+    fn region_can_be_written_in_bgra_order() {
+        let source = layout(3, 2, 12, PixelFormat::Rgbx8);
+        let src = pattern(24);
+        let mut bgra = vec![0; 24];
+        convert_region(
+            &src,
+            &source,
+            AlphaMode::Opaque.into(),
+            [0, 0, 3, 2],
+            &mut bgra,
+            PixelFormat::Bgra8,
+        );
+        for (src, dst) in src.chunks_exact(4).zip(bgra.chunks_exact(4)) {
+            assert_eq!(dst, [src[2], src[1], src[0], 255]);
+        }
+    }
+
+    #[test]
+    fn srgb_transfer_round_trips() {
+        for value in 0..=255u8 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+    }
+
+    #[test]
+    fn srgb_premultiply_happens_in_linear_light() {
+        let linear = AlphaConversion {
+            mode: AlphaMode::Premultiply,
+            srgb: true,
+        };
+        let src = [128, 200, 30, 128, 77, 66, 55, 255, 90, 90, 90, 0];
+        let mut dst = Vec::new();
+        convert_to_rgba(
+            &src,
+            &layout(3, 1, 12, PixelFormat::Rgba8),
+            linear,
+            &mut dst,
+        )
+        .unwrap();
+        let encoded = convert(
+            &src,
+            layout(3, 1, 12, PixelFormat::Rgba8),
+            AlphaMode::Premultiply,
+        );
+        // Scaling linear light keeps more of the encoded value / 線形光でのスケールはエンコード値をより多く残す
+        assert!(dst[..3].iter().zip(&encoded[..3]).all(|(l, e)| l > e));
+        assert_eq!(&dst[3..], [128, 77, 66, 55, 255, 0, 0, 0, 0]);
+
+        let mut back = Vec::new();
+        let unpremultiply = AlphaConversion {
+            mode: AlphaMode::Unpremultiply,
+            srgb: true,
+        };
+        convert_to_rgba(
+            &dst,
+            &layout(3, 1, 12, PixelFormat::Rgba8),
+            unpremultiply,
+            &mut back,
+        )
+        .unwrap();
+        for (restored, original) in back[..3].iter().zip(&src[..3]) {
+            assert!(
+                restored.abs_diff(*original) <= 1,
+                "{restored} vs {original}"
+            );
+        }
+    }
+}
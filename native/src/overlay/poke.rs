@@ -0,0 +1,494 @@
+use std::collections::HashMap;
+
+// Roughly the front of a typical controller, in its local space (-Z is forward)
+// 一般的なコントローラー前端の位置 (ローカル空間、-Z が前方)
+const DEFAULT_TIP_OFFSET: [f64; 3] = [0.0, -0.01, -0.09];
+
+/// Overlay quad in tracking space for native plane-crossing tests.
+/// ネイティブの平面交差判定に使うトラッキング空間上のオーバーレイ矩形
+#[derive(Clone, Copy, Debug)]
+pub(super) struct OverlayPlane {
+    origin: [f64; 3],
+    // Unit X/Y/Z axes of the overlay (Z is the normal toward the viewer)
+    // オーバーレイの単位軸 X/Y/Z (Z は閲覧側を向く法線)
+    axes: [[f64; 3]; 3],
+    width: f64,
+    height: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct PlaneProjection {
+    pub u: f64,
+    pub v: f64,
+    /// Signed distance along the normal; positive is in front of the overlay.
+    /// 法線方向の符号付き距離。正はオーバーレイの手前側
+    pub depth: f64,
+    /// Foot of the perpendicular on the overlay plane / オーバーレイ平面上の垂線の足
+    pub point: [f64; 3],
+    pub inside: bool,
+}
+
+impl OverlayPlane {
+    pub(super) fn new(m: &[[f32; 4]; 3], width: f64, height: f64) -> napi::Result<Self> {
+        if !(width > 0.0 && height > 0.0) {
+            return Err(napi::Error::from_reason(format!(
+                "Overlay size must be positive, got {width}x{height}"
+            )));
+        }
+        let origin = [m[0][3] as f64, m[1][3] as f64, m[2][3] as f64];
+        let mut axes = [[0.0; 3]; 3];
+        for (column, axis) in axes.iter_mut().enumerate() {
+            let raw = [
+                m[0][column] as f64,
+                m[1][column] as f64,
+                m[2][column] as f64,
+            ];
+            let length = (raw[0] * raw[0] + raw[1] * raw[1] + raw[2] * raw[2]).sqrt();
+            if length <= f64::EPSILON {
+                return Err(napi::Error::from_reason("Overlay transform is degenerate"));
+            }
+            *axis = [raw[0] / length, raw[1] / length, raw[2] / length];
+        }
+        Ok(Self {
+            origin,
+            axes,
+            width,
+            height,
+        })
+    }
+
+    pub(super) fn normal(&self) -> [f64; 3] {
+        self.axes[2]
+    }
+
+    pub(super) fn project(&self, point: [f64; 3]) -> PlaneProjection {
+        let offset = [
+            point[0] - self.origin[0],
+            point[1] - self.origin[1],
+            point[2] - self.origin[2],
+        ];
+        let local = self
+            .axes
+            .map(|axis| axis[0] * offset[0] + axis[1] * offset[1] + axis[2] * offset[2]);
+        let normal = self.normal();
+
+        // UVs follow ComputeOverlayIntersection: origin bottom-left, v grows upward
+        // UV は ComputeOverlayIntersection と同じく左下原点、v は上向き
+        let u = local[0] / self.width + 0.5;
+        let v = local[1] / self.height + 0.5;
+        PlaneProjection {
+            u,
+            v,
+            depth: local[2],
+            point: [
+                point[0] - normal[0] * local[2],
+                point[1] - normal[1] * local[2],
+                point[2] - normal[2] * local[2],
+            ],
+            inside: (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PokeEventKind {
+    Hover,
+    Press,
+    Release,
+}
+
+impl PokeEventKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Hover => "hover",
+            Self::Press => "press",
+            Self::Release => "release",
+        }
+    }
+}
+
+/// Thresholds shared by finger and controller-tip poking (meters, m/s).
+/// 指先・コントローラー先端のポークで共通の閾値 (メートル, m/s)
+#[derive(Clone, Copy, Debug)]
+pub(super) struct PokeConfig {
+    /// Distance past the plane that counts as a press / 押下とみなす平面の奥行き
+    pub press_depth: f64,
+    /// Distance in front of the plane required to release / 解放に必要な手前側の距離
+    pub release_depth: f64,
+    /// Hover events are reported within this distance / この距離以内でホバーを通知
+    pub hover_distance: f64,
+    /// Points further behind than this disarm until they return / これより奥に行くと再度手前に戻るまで無効
+    pub max_behind_distance: f64,
+    /// Minimum approach speed along the normal / 法線方向の最小接近速度
+    pub min_press_speed: f64,
+    /// Maximum sideways-to-approach speed ratio / 横方向速度と接近速度の最大比
+    pub max_lateral_ratio: f64,
+}
+
+impl Default for PokeConfig {
+    fn default() -> Self {
+        Self {
+            press_depth: 0.004,
+            release_depth: 0.008,
+            hover_distance: 0.06,
+            max_behind_distance: 0.05,
+            min_press_speed: 0.05,
+            max_lateral_ratio: 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct PokeSample {
+    projection: PlaneProjection,
+    timestamp: f64,
+}
+
+/// Press/hover/release detection for one poking point crossing the overlay plane.
+/// Hysteresis between press_depth and release_depth prevents double presses, and
+/// presses must approach from the front fast enough and mostly head-on.
+/// オーバーレイ平面を横切る 1 点の押下/ホバー/解放を検出する。press_depth と
+/// release_depth のヒステリシスで二重押下を防ぎ、押下は手前から十分な速度で
+/// ほぼ正面から近づいた場合のみ受け付ける。
+#[derive(Default)]
+pub(super) struct PokeTracker {
+    pressed: bool,
+    armed: bool,
+    last_sample: Option<PokeSample>,
+}
+
+impl PokeTracker {
+    /// Feed the latest projection (None when tracking is lost) with its timestamp in seconds.
+    /// 最新の投影結果と秒単位のタイムスタンプを渡す (トラッキング喪失時は None)
+    pub(super) fn update(
+        &mut self,
+        projection: Option<PlaneProjection>,
+        timestamp: f64,
+        config: &PokeConfig,
+    ) -> Option<(PokeEventKind, PlaneProjection)> {
+        let Some(projection) = projection else {
+            return self.reset();
+        };
+        let previous = self.last_sample.replace(PokeSample {
+            projection,
+            timestamp,
+        });
+
+        if self.pressed {
+            if projection.depth >= config.release_depth || !projection.inside {
+                self.pressed = false;
+                self.armed = projection.depth > 0.0;
+                return Some((PokeEventKind::Release, projection));
+            }
+            return None;
+        }
+
+        if projection.depth > 0.0 {
+            self.armed = true;
+            if projection.inside && projection.depth <= config.hover_distance {
+                return Some((PokeEventKind::Hover, projection));
+            }
+            return None;
+        }
+        if projection.depth < -config.max_behind_distance {
+            self.armed = false;
+            return None;
+        }
+
+        // Only a front-to-back crossing counts; appearing behind the plane does not
+        // 手前から奥への通過のみ押下とみなす (平面の奥に現れた場合は無視)
+        if !self.armed || projection.depth > -config.press_depth || !projection.inside {
+            return None;
+        }
+        self.armed = false;
+        let approach_ok = previous
+            .map(|previous| approach_is_valid(&previous, &projection, timestamp, config))
+            .unwrap_or(false);
+        if !approach_ok {
+            return None;
+        }
+        self.pressed = true;
+        Some((PokeEventKind::Press, projection))
+    }
+
+    /// Forget history, releasing a held press at its last known location.
+    /// 履歴を破棄し、押下中なら最後の位置で解放する
+    pub(super) fn reset(&mut self) -> Option<(PokeEventKind, PlaneProjection)> {
+        let was_pressed = std::mem::take(&mut self.pressed);
+        self.armed = false;
+        match self.last_sample.take() {
+            Some(sample) if was_pressed => Some((PokeEventKind::Release, sample.projection)),
+            _ => None,
+        }
+    }
+}
+
+fn approach_is_valid(
+    previous: &PokeSample,
+    current: &PlaneProjection,
+    timestamp: f64,
+    config: &PokeConfig,
+) -> bool {
+    let dt = timestamp - previous.timestamp;
+    if dt <= 0.0 {
+        return false;
+    }
+    let normal_speed = (previous.projection.depth - current.depth) / dt;
+    let lateral = [
+        current.point[0] - previous.projection.point[0],
+        current.point[1] - previous.projection.point[1],
+        current.point[2] - previous.projection.point[2],
+    ];
+    let lateral_speed =
+        (lateral[0] * lateral[0] + lateral[1] * lateral[1] + lateral[2] * lateral[2]).sqrt() / dt;
+    normal_speed >= config.min_press_speed
+        && lateral_speed <= normal_speed * config.max_lateral_ratio
+}
+
+/// Poke tracking state for fingertips and controller tips.
+/// 指先とコントローラー先端のポーク追跡状態
+pub(super) struct PokeState {
+    pub config: PokeConfig,
+    /// Tip position in controller space / コントローラー空間での先端位置
+    pub tip_offset: [f64; 3],
+    pub fingers: [PokeTracker; 2],
+    pub controllers: HashMap<u32, PokeTracker>,
+}
+
+impl Default for PokeState {
+    fn default() -> Self {
+        Self {
+            config: PokeConfig::default(),
+            tip_offset: DEFAULT_TIP_OFFSET,
+            fingers: Default::default(),
+            controllers: HashMap::new(),
+        }
+    }
+}
+
+impl PokeState {
+    /// Carry a controller tip over to its new index after it reconnected.
+    /// 再接続したコントローラーの新しい番号に先端の追跡状態を引き継ぐ
+    pub(super) fn remap_controller(&mut self, previous: u32, index: u32) {
+        if let Some(tracker) = self.controllers.remove(&previous) {
+            self.controllers.insert(index, tracker);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    // 1m x 0.5m overlay facing +Z, centred at (0, 1, -1)
+    // +Z を向く 1m x 0.5m のオーバーレイ、中心は (0, 1, -1)
+    fn facing_plane() -> OverlayPlane {
+        let m = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, -1.0],
+        ];
+        OverlayPlane::new(&m, 1.0, 0.5).unwrap()
+    }
+
+    // Same overlay turned 90 degrees about Y so its normal points along +X
+    // 同じオーバーレイを Y 軸回りに 90 度回転し、法線を +X に向けたもの
+    fn rotated_plane() -> OverlayPlane {
+        let m = [
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [-1.0, 0.0, 0.0, -1.0],
+        ];
+        OverlayPlane::new(&m, 1.0, 0.5).unwrap()
+    }
+
+    #[test]
+    fn projects_in_bounds_points_to_bottom_left_uvs() {
+        let plane = facing_plane();
+        let centre = plane.project([0.0, 1.0, -0.9]);
+        assert!(centre.inside);
+        assert!(close(centre.u, 0.5) && close(centre.v, 0.5));
+        assert!(close(centre.depth, 0.1));
+        assert!(centre
+            .point
+            .iter()
+            .zip([0.0, 1.0, -1.0])
+            .all(|(a, b)| close(*a, b)));
+
+        // Towards the top right raises both u and v
+        // 右上に寄るほど u と v が増える
+        let corner = plane.project([0.4, 1.2, -1.0]);
+        assert!(corner.inside);
+        assert!(close(corner.u, 0.9) && close(corner.v, 0.9));
+        assert!(close(corner.depth, 0.0));
+    }
+
+    #[test]
+    fn flags_points_outside_the_quad() {
+        let plane = facing_plane();
+        let right = plane.project([0.6, 1.0, -1.0]);
+        assert!(!right.inside);
+        assert!(close(right.u, 1.1));
+        let below = plane.project([0.0, 0.7, -1.0]);
+        assert!(!below.inside);
+        assert!(close(below.v, -0.1));
+        // The edge itself still counts as inside / 端そのものは内側扱い
+        assert!(plane.project([0.5, 1.25, -1.0]).inside);
+    }
+
+    #[test]
+    fn depth_follows_the_rotated_normal() {
+        let plane = rotated_plane();
+        assert!(plane
+            .normal()
+            .iter()
+            .zip([1.0, 0.0, 0.0])
+            .all(|(a, b)| close(*a, b)));
+
+        let front = plane.project([0.1, 1.0, -1.0]);
+        assert!(close(front.depth, 0.1));
+        assert!(front
+            .point
+            .iter()
+            .zip([0.0, 1.0, -1.0])
+            .all(|(a, b)| close(*a, b)));
+        let behind = plane.project([-0.1, 1.0, -1.0]);
+        assert!(close(behind.depth, -0.1));
+
+        // The overlay's X axis now points along -Z / オーバーレイの X 軸は -Z 方向
+        let side = plane.project([0.0, 1.0, -1.2]);
+        assert!(close(side.u, 0.7) && close(side.depth, 0.0));
+        assert!(side.inside);
+    }
+
+    #[test]
+    fn rejects_degenerate_planes() {
+        let mut m = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ];
+        assert!(OverlayPlane::new(&m, 0.0, 1.0).is_err());
+        m[0][0] = 0.0;
+        assert!(OverlayPlane::new(&m, 1.0, 1.0).is_err());
+    }
+
+    // Feed (x, depth, timestamp) samples over facing_plane and collect the events
+    // facing_plane 上の (x, 奥行き, 時刻) サンプルを与えてイベントを集める
+    fn track(tracker: &mut PokeTracker, samples: &[(f64, f64, f64)]) -> Vec<PokeEventKind> {
+        let plane = facing_plane();
+        let config = PokeConfig::default();
+        samples
+            .iter()
+            .filter_map(|&(x, depth, timestamp)| {
+                let projection = plane.project([x, 1.0, -1.0 + depth]);
+                tracker.update(Some(projection), timestamp, &config)
+            })
+            .map(|(kind, _)| kind)
+            .collect()
+    }
+
+    #[test]
+    fn press_and_release_use_separate_depths() {
+        use PokeEventKind::*;
+        let mut tracker = PokeTracker::default();
+        let events = track(
+            &mut tracker,
+            &[
+                (0.0, 0.02, 0.0),
+                // Past press_depth while approaching at 0.5 m/s
+                // 0.5 m/s で近づきながら press_depth を越える
+                (0.0, -0.005, 0.05),
+                // Back in front but short of release_depth: still held
+                // 手前に戻ったが release_depth 未満: 押下継続
+                (0.0, 0.005, 0.1),
+                (0.0, 0.009, 0.15),
+                (0.0, -0.005, 0.2),
+            ],
+        );
+        assert_eq!(events, vec![Hover, Press, Release, Press]);
+    }
+
+    #[test]
+    fn shallow_crossings_do_not_press() {
+        let mut tracker = PokeTracker::default();
+        // Behind the plane but not past press_depth / 平面の奥だが press_depth 未満
+        let events = track(&mut tracker, &[(0.0, 0.01, 0.0), (0.0, -0.003, 0.05)]);
+        assert_eq!(events, vec![PokeEventKind::Hover]);
+    }
+
+    #[test]
+    fn appearing_behind_the_plane_is_not_armed() {
+        let mut tracker = PokeTracker::default();
+        let events = track(&mut tracker, &[(0.0, -0.01, 0.0), (0.0, -0.02, 0.05)]);
+        assert!(events.is_empty());
+
+        // Each crossing arms a single press attempt, successful or not
+        // 通過 1 回につき押下の判定は 1 度だけ (成否を問わず)
+        let mut tracker = PokeTracker::default();
+        let events = track(
+            &mut tracker,
+            &[(0.0, 0.01, 0.0), (0.0, -0.005, 1.0), (0.0, -0.02, 1.05)],
+        );
+        assert_eq!(events, vec![PokeEventKind::Hover]);
+    }
+
+    #[test]
+    fn going_too_far_behind_disarms_until_back_in_front() {
+        use PokeEventKind::*;
+        let mut tracker = PokeTracker::default();
+        let events = track(
+            &mut tracker,
+            &[
+                (0.0, 0.02, 0.0),
+                (0.0, -0.06, 0.05),
+                (0.0, -0.01, 0.1),
+                (0.0, 0.01, 0.15),
+                (0.0, -0.005, 0.2),
+            ],
+        );
+        assert_eq!(events, vec![Hover, Hover, Press]);
+    }
+
+    #[test]
+    fn presses_need_a_fast_mostly_head_on_approach() {
+        // Too slow: 7 mm in one second / 遅すぎる: 1 秒で 7 mm
+        let mut tracker = PokeTracker::default();
+        let events = track(&mut tracker, &[(0.0, 0.002, 0.0), (0.0, -0.005, 1.0)]);
+        assert_eq!(events, vec![PokeEventKind::Hover]);
+
+        // Too sideways: 10 cm across for 2.5 cm of depth / 横移動が大きすぎる
+        let mut tracker = PokeTracker::default();
+        let events = track(&mut tracker, &[(0.0, 0.02, 0.0), (0.1, -0.005, 0.05)]);
+        assert_eq!(events, vec![PokeEventKind::Hover]);
+
+        // Samples without elapsed time cannot prove an approach
+        // 経過時間の無いサンプルでは接近を判定できない
+        let mut tracker = PokeTracker::default();
+        let events = track(&mut tracker, &[(0.0, 0.02, 0.0), (0.0, -0.005, 0.0)]);
+        assert_eq!(events, vec![PokeEventKind::Hover]);
+
+        let mut tracker = PokeTracker::default();
+        let events = track(&mut tracker, &[(0.0, 0.02, 0.0), (0.04, -0.005, 0.05)]);
+        assert_eq!(events, vec![PokeEventKind::Hover, PokeEventKind::Press]);
+    }
+
+    #[test]
+    fn losing_tracking_releases_at_the_last_point() {
+        let mut tracker = PokeTracker::default();
+        track(&mut tracker, &[(0.0, 0.02, 0.0), (0.1, -0.005, 0.05)]);
+        let events = track(&mut tracker, &[(0.0, 0.02, 0.1), (0.0, -0.005, 0.15)]);
+        assert_eq!(events, vec![PokeEventKind::Hover, PokeEventKind::Press]);
+
+        let (kind, projection) = tracker
+            .update(None, 0.2, &PokeConfig::default())
+            .expect("held press is released");
+        assert_eq!(kind, PokeEventKind::Release);
+        assert!(close(projection.depth, -0.005));
+        assert!(tracker.update(None, 0.25, &PokeConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use super::hand::Hand;
+use super::handles::overlay_handle;
+use super::manager::OverlayManager;
+use super::math::{transform_point34, vec3_f32};
+use super::poke::{PlaneProjection, PokeEventKind};
+use super::types::{PokeEvent, PokeSettings};
+use napi_derive::napi;
+
+pub(super) fn poke_event(
+    kind: PokeEventKind,
+    source: &str,
+    hand: Option<Hand>,
+    controller_index: Option<u32>,
+    projection: PlaneProjection,
+) -> PokeEvent {
+    PokeEvent {
+        kind: kind.as_str().to_string(),
+        source: source.to_string(),
+        hand: hand.map(|hand| hand.as_str().to_string()),
+        controllerIndex: controller_index,
+        u: projection.u,
+        v: projection.v,
+        depth: -projection.depth,
+        x: projection.point[0],
+        y: projection.point[1],
+        z: projection.point[2],
+    }
+}
+
+fn non_negative(name: &str, value: Option<f64>, current: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(napi::Error::from_reason(format!(
+            "{name} must be a non-negative number"
+        ))),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update poke thresholds and the controller tip offset; omitted fields keep their value.
+    /// ポークの閾値とコントローラー先端オフセットを更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_poke(&self, settings: PokeSettings) -> napi::Result<()> {
+        let mut poke = self.lock_poke()?;
+        let mut config = poke.config;
+        config.press_depth = non_negative("pressDepth", settings.pressDepth, config.press_depth)?;
+        config.release_depth =
+            non_negative("releaseDepth", settings.releaseDepth, config.release_depth)?;
+        config.hover_distance = non_negative(
+            "hoverDistance",
+            settings.hoverDistance,
+            config.hover_distance,
+        )?;
+        config.max_behind_distance = non_negative(
+            "maxBehindDistance",
+            settings.maxBehindDistance,
+            config.max_behind_distance,
+        )?;
+        config.min_press_speed = non_negative(
+            "minPressSpeed",
+            settings.minPressSpeed,
+            config.min_press_speed,
+        )?;
+        config.max_lateral_ratio = non_negative(
+            "maxLateralRatio",
+            settings.maxLateralRatio,
+            config.max_lateral_ratio,
+        )?;
+
+        if let Some(tip_offset) = settings.tipOffset {
+            let tip_offset = vec3_f32("tipOffset", &tip_offset)?;
+            poke.tip_offset = tip_offset.map(|v| v as f64);
+        }
+        poke.config = config;
+        Ok(())
+    }
+
+    /// "Drumstick" typing: detect controller tips crossing the overlay plane.
+    /// Controllers that disappear release any press they were holding.
+    /// ドラムスティック入力: コントローラー先端がオーバーレイ平面を横切ったかを検出する。
+    /// 消えたコントローラーは押下中であれば解放される。
+    #[napi]
+    pub fn poll_controller_poke(&self, handle: i64) -> napi::Result<Vec<PokeEvent>> {
+        let handle = overlay_handle(handle)?;
+        let plane = self.overlay_plane(handle)?;
+        let controller_ids = self.get_controller_ids()?;
+
+        let mut poke = self.lock_poke()?;
+        let config = poke.config;
+        let tip_offset = poke.tip_offset;
+        let mut events = Vec::new();
+
+        let stale: Vec<u32> = poke
+            .controllers
+            .keys()
+            .copied()
+            .filter(|index| !controller_ids.contains(index))
+            .collect();
+        for index in stale {
+            if let Some(mut tracker) = poke.controllers.remove(&index) {
+                if let Some((kind, projection)) = tracker.reset() {
+                    events.push(poke_event(
+                        kind,
+                        "controller",
+                        None,
+                        Some(index),
+                        projection,
+                    ));
+                }
+            }
+        }
+
+        for index in controller_ids {
+            let pose = self.device_pose(index)?;
+            let timestamp = pose
+                .as_ref()
+                .map(|pose| pose.timestamp)
+                .unwrap_or_else(|| self.now_seconds());
+            let projection =
+                pose.map(|pose| plane.project(transform_point34(&pose.matrix, tip_offset)));
+            let tracker = poke.controllers.entry(index).or_default();
+            if let Some((kind, projection)) = tracker.update(projection, timestamp, &config) {
+                events.push(poke_event(
+                    kind,
+                    "controller",
+                    self.controller_hand(index),
+                    Some(index),
+                    projection,
+                ));
+            }
+        }
+        Ok(events)
+    }
+}
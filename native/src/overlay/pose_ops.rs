@@ -0,0 +1,165 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::controller_ops::DevicePose;
+use super::errors::{input_error, require_fn};
+use super::input_ops::origin_device_index;
+use super::manager::{InputActionCache, OverlayManager};
+use super::math::{hmd_matrix34_to_vec, ray_from_matrix34};
+use super::types::PointerRay;
+
+/// Pointer ray of the pose action for a controller's input sources, or None if untracked.
+/// コントローラーの入力ソースに対するポーズアクションのレイ (未トラッキングなら None)
+pub(super) fn read_pointer_ray(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+    sources: &[vr::VRInputValueHandle_t],
+    predicted_seconds_from_now: Option<f64>,
+) -> napi::Result<Option<PointerRay>> {
+    // Pose actions must be restricted to a hand; "any device" is ambiguous here
+    // ポーズアクションは手の指定が必須 (任意デバイスでは左右が曖昧になる)
+    let source = match sources.first().copied() {
+        Some(source) if source != vr::k_ulInvalidInputValueHandle => source,
+        _ => return Ok(None),
+    };
+
+    let mut data: vr::InputPoseActionData_t = unsafe { std::mem::zeroed() };
+    let data_size = std::mem::size_of::<vr::InputPoseActionData_t>() as u32;
+    let err = unsafe {
+        match predicted_seconds_from_now {
+            Some(seconds) => {
+                let get_pose_fn = require_fn(
+                    input.GetPoseActionDataRelativeToNow,
+                    "GetPoseActionDataRelativeToNow",
+                )?;
+                get_pose_fn(
+                    cache.pointer_action_handle,
+                    vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
+                    seconds as f32,
+                    &mut data,
+                    data_size,
+                    source,
+                )
+            }
+            None => {
+                let get_pose_fn = require_fn(
+                    input.GetPoseActionDataForNextFrame,
+                    "GetPoseActionDataForNextFrame",
+                )?;
+                get_pose_fn(
+                    cache.pointer_action_handle,
+                    vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
+                    &mut data,
+                    data_size,
+                    source,
+                )
+            }
+        }
+    };
+
+    if err == vr::EVRInputError_VRInputError_NoData {
+        return Ok(None);
+    }
+    if err != vr::EVRInputError_VRInputError_None {
+        return Err(input_error("GetPoseActionData", err));
+    }
+    if !data.bActive || !data.pose.bPoseIsValid || !data.pose.bDeviceIsConnected {
+        return Ok(None); // Action unbound or not tracking / アクション未バインド or 未トラッキング
+    }
+
+    let matrix = &data.pose.mDeviceToAbsoluteTracking.m;
+    let (origin, direction) = ray_from_matrix34(matrix);
+    Ok(Some(PointerRay {
+        origin: origin.to_vec(),
+        direction: direction.to_vec(),
+        transform: hmd_matrix34_to_vec(matrix),
+        originDeviceIndex: origin_device_index(input, data.activeOrigin),
+    }))
+}
+
+/// Ray to aim at overlays with: the pointer pose action's ray when it is bound and
+/// tracking, otherwise the device's forward (-Z) axis.
+/// オーバーレイを指すレイ: ポーズアクションがバインド済みでトラッキング中ならそのレイ、
+/// それ以外はデバイスの前方 (-Z) 軸
+pub(super) fn aim_ray(
+    pointer: Option<&PointerRay>,
+    pose: Option<&DevicePose>,
+) -> Option<([f64; 3], [f64; 3])> {
+    let pointer = pointer.and_then(|ray| {
+        Some((
+            <[f64; 3]>::try_from(ray.origin.as_slice()).ok()?,
+            <[f64; 3]>::try_from(ray.direction.as_slice()).ok()?,
+        ))
+    });
+    pointer.or_else(|| pose.map(|pose| ray_from_matrix34(&pose.matrix)))
+}
+
+impl OverlayManager {
+    /// Next-frame pointer ray for aiming, or None when SteamVR Input is not set up.
+    /// While the input thread runs this is the ray from its latest tick.
+    /// 照準用の次フレームのポインターレイ (SteamVR Input 未設定なら None)。
+    /// 入力スレッドの実行中は直近の周期のレイを返す。
+    pub(super) fn controller_pointer_ray(
+        &self,
+        controller_index: u32,
+    ) -> napi::Result<Option<PointerRay>> {
+        let snapshot = self.read_input_snapshot(|snapshot| {
+            snapshot
+                .controllers
+                .get(&controller_index)
+                .and_then(|controller| controller.pointer.clone())
+        })?;
+        if let Some(ray) = snapshot {
+            return Ok(ray);
+        }
+
+        let Ok(input) = self.input() else {
+            return Ok(None);
+        };
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Ok(None);
+        }
+        let sources = self.action_sources_for_controller(controller_index, &cache);
+        read_pointer_ray(input, &cache, &sources, None)
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Pointer ray from the SteamVR Input pose action for the controller's hand.
+    /// Without `predicted_seconds_from_now` the pose is predicted for the next frame.
+    /// While the input thread runs this is the ray from its latest tick and the prediction is ignored.
+    /// コントローラーの手に対応する SteamVR Input のポーズアクションからレイを取得する。
+    /// `predicted_seconds_from_now` 未指定時は次フレーム向けの予測姿勢を使用。
+    /// 入力スレッドの実行中は直近の周期のレイを返し、予測時間は無視する。
+    #[napi]
+    pub fn get_pointer_ray(
+        &self,
+        controller_index: u32,
+        predicted_seconds_from_now: Option<f64>,
+    ) -> napi::Result<Option<PointerRay>> {
+        if controller_index >= vr::k_unMaxTrackedDeviceCount {
+            return Err(napi::Error::from_reason("Invalid device index"));
+        }
+
+        let input = self.input()?;
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+        }
+
+        let snapshot = self.read_input_snapshot(|snapshot| {
+            snapshot
+                .controllers
+                .get(&controller_index)
+                .and_then(|controller| controller.pointer.clone())
+        })?;
+        if let Some(ray) = snapshot {
+            return Ok(ray);
+        }
+
+        let sources = self.action_sources_for_controller(controller_index, &cache);
+        read_pointer_ray(input, &cache, &sources, predicted_seconds_from_now)
+    }
+}
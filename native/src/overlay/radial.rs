@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use super::hand::Hand;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct RadialConfig {
+    pub enabled: bool,
+    /// Stick deflection (0..1) that starts highlighting a sector / セクターの選択を始めるスティックの倒し量 (0..1)
+    pub deadzone: f64,
+    /// Deflection below which the highlight is cleared (hysteresis) / 選択を解除する倒し量 (ヒステリシス)
+    pub release_deadzone: f64,
+    /// How far past a sector edge the stick must move to switch sectors
+    /// セクターを切り替えるのに境界を越えて倒す必要がある角度
+    pub hysteresis_degrees: f64,
+}
+
+impl Default for RadialConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            deadzone: 0.5,
+            release_deadzone: 0.35,
+            hysteresis_degrees: 6.0,
+        }
+    }
+}
+
+/// Characters of each hand's ring, clockwise from straight up
+/// 各手のリングの文字 (真上から時計回り)
+#[derive(Clone, Debug, Default)]
+pub(super) struct RadialLayout {
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+}
+
+impl RadialLayout {
+    fn sectors(&self, hand: Hand) -> &[String] {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum RadialEventKind {
+    Highlighted,
+    Committed,
+}
+
+impl RadialEventKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            RadialEventKind::Highlighted => "highlighted",
+            RadialEventKind::Committed => "committed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct RadialEvent {
+    pub kind: RadialEventKind,
+    pub hand: Hand,
+    /// None on a highlight event = stick back in the deadzone / ハイライトイベントで None はデッドゾーンに戻ったこと
+    pub sector: Option<usize>,
+    pub character: Option<String>,
+    pub timestamp: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct RadialSample {
+    /// Controllers without a hand role have no ring / 手の役割が無いコントローラーにはリングが無い
+    pub hand: Option<Hand>,
+    pub x: f64,
+    pub y: f64,
+    /// Trigger or stick click held / トリガーまたはスティック押し込みを押下中
+    pub commit: bool,
+    pub timestamp: f64,
+}
+
+/// Sector under the stick, keeping `current` until the stick is clearly past its edge.
+/// スティックが指すセクター。境界を明確に越えるまでは `current` を維持する
+fn sector_at(
+    [x, y]: [f64; 2],
+    count: usize,
+    current: Option<usize>,
+    config: &RadialConfig,
+) -> Option<usize> {
+    let deadzone = if current.is_some() {
+        config.release_deadzone
+    } else {
+        config.deadzone
+    };
+    if count == 0 || x.hypot(y) < deadzone {
+        return None;
+    }
+
+    // Clockwise from up; sector 0 is centered on up / 上から時計回り (セクター 0 は真上が中心)
+    let angle = x.atan2(y).rem_euclid(TAU);
+    let width = TAU / count as f64;
+    let nearest = (angle / width).round() as usize % count;
+    match current {
+        Some(current) if current < count && current != nearest => {
+            let offset = (angle - current as f64 * width + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+            if offset.abs() <= width / 2.0 + config.hysteresis_degrees.to_radians() {
+                Some(current)
+            } else {
+                Some(nearest)
+            }
+        }
+        _ => Some(nearest),
+    }
+}
+
+/// Per-controller sector selection and commit edge detection.
+/// コントローラーごとのセクター選択と確定操作のエッジ検出
+#[derive(Default)]
+pub(super) struct RadialSelector {
+    hand: Option<Hand>,
+    sector: Option<usize>,
+    commit_down: bool,
+}
+
+impl RadialSelector {
+    pub(super) fn update(
+        &mut self,
+        sample: RadialSample,
+        layout: &RadialLayout,
+        config: &RadialConfig,
+    ) -> Vec<RadialEvent> {
+        let mut events = Vec::new();
+        if sample.hand != self.hand {
+            // Role swapped: the old ring no longer applies / 役割が変わったら以前のリングは無効
+            events.extend(self.clear(sample.timestamp));
+            self.hand = sample.hand;
+        }
+        let Some(hand) = self.hand else {
+            self.commit_down = sample.commit;
+            return events;
+        };
+
+        let sectors = layout.sectors(hand);
+        let sector = sector_at([sample.x, sample.y], sectors.len(), self.sector, config);
+        if sector != self.sector {
+            self.sector = sector;
+            events.push(RadialEvent {
+                kind: RadialEventKind::Highlighted,
+                hand,
+                sector,
+                character: sector.map(|sector| sectors[sector].clone()),
+                timestamp: sample.timestamp,
+            });
+        }
+
+        if sample.commit && !self.commit_down {
+            if let Some(sector) = self.sector {
+                events.push(RadialEvent {
+                    kind: RadialEventKind::Committed,
+                    hand,
+                    sector: Some(sector),
+                    character: Some(sectors[sector].clone()),
+                    timestamp: sample.timestamp,
+                });
+            }
+        }
+        self.commit_down = sample.commit;
+        events
+    }
+
+    /// Drop the highlight, reporting it if one was shown / ハイライトを消す (表示中なら通知)
+    pub(super) fn clear(&mut self, now: f64) -> Option<RadialEvent> {
+        let hand = self.hand?;
+        self.sector.take().map(|_| RadialEvent {
+            kind: RadialEventKind::Highlighted,
+            hand,
+            sector: None,
+            character: None,
+            timestamp: now,
+        })
+    }
+}
+
+#[derive(Default)]
+pub(super) struct RadialState {
+    pub config: RadialConfig,
+    /// Registered layouts by language / 言語ごとに登録されたレイアウト
+    pub layouts: HashMap<String, RadialLayout>,
+    pub language: Option<String>,
+    pub selectors: HashMap<u32, RadialSelector>,
+    /// Highlights cleared outside update_all, reported by the next update
+    /// update_all 以外で消したハイライト (次の更新で通知)
+    pub pending: Vec<(u32, RadialEvent)>,
+}
+
+impl RadialState {
+    /// Clear every highlight, e.g. when the layout changes / レイアウト変更時などに全ハイライトを消す
+    pub(super) fn reset(&mut self, now: f64) {
+        for (index, mut selector) in self.selectors.drain() {
+            self.pending
+                .extend(selector.clear(now).map(|event| (index, event)));
+        }
+    }
+
+    /// Carry a selection over to a controller's new index after it reconnected.
+    /// 再接続したコントローラーの新しい番号に選択状態を引き継ぐ
+    pub(super) fn remap_controller(&mut self, previous: u32, index: u32) {
+        if let Some(selector) = self.selectors.remove(&previous) {
+            self.selectors.insert(index, selector);
+        }
+    }
+
+    /// Feed one sample per connected controller; controllers without a sample lose their highlight.
+    /// 接続中のコントローラーごとにサンプルを入力し、サンプルの無いコントローラーはハイライトを消す
+    pub(super) fn update_all(
+        &mut self,
+        samples: Vec<(u32, RadialSample)>,
+        now: f64,
+    ) -> Vec<(u32, RadialEvent)> {
+        let config = self.config;
+        let mut events = std::mem::take(&mut self.pending);
+        let layout = match self
+            .language
+            .as_ref()
+            .and_then(|name| self.layouts.get(name))
+        {
+            Some(layout) if config.enabled => layout,
+            _ => {
+                self.reset(now);
+                events.append(&mut self.pending);
+                return events;
+            }
+        };
+
+        let stale: Vec<u32> = self
+            .selectors
+            .keys()
+            .copied()
+            .filter(|index| !samples.iter().any(|(sampled, _)| sampled == index))
+            .collect();
+        for index in stale {
+            if let Some(mut selector) = self.selectors.remove(&index) {
+                events.extend(selector.clear(now).map(|event| (index, event)));
+            }
+        }
+
+        for (index, sample) in samples {
+            let selector = self.selectors.entry(index).or_default();
+            events.extend(
+                selector
+                    .update(sample, layout, &config)
+                    .into_iter()
+                    .map(|event| (index, event)),
+            );
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTORS: usize = 8;
+
+    /// Stick position at `degrees` clockwise from up / 真上から時計回りに `degrees` 度の位置
+    fn stick(degrees: f64, deflection: f64) -> [f64; 2] {
+        let radians = degrees.to_radians();
+        [deflection * radians.sin(), deflection * radians.cos()]
+    }
+
+    fn no_hysteresis() -> RadialConfig {
+        RadialConfig {
+            hysteresis_degrees: 0.0,
+            ..RadialConfig::default()
+        }
+    }
+
+    #[test]
+    fn sectors_split_at_half_width_without_hysteresis() {
+        let config = no_hysteresis();
+        assert_eq!(sector_at(stick(0.0, 1.0), SECTORS, None, &config), Some(0));
+        assert_eq!(sector_at(stick(22.4, 1.0), SECTORS, None, &config), Some(0));
+        assert_eq!(sector_at(stick(22.6, 1.0), SECTORS, None, &config), Some(1));
+        assert_eq!(sector_at(stick(90.0, 1.0), SECTORS, None, &config), Some(2));
+        assert_eq!(
+            sector_at(stick(337.6, 1.0), SECTORS, None, &config),
+            Some(0)
+        );
+        assert_eq!(
+            sector_at(stick(337.4, 1.0), SECTORS, None, &config),
+            Some(7)
+        );
+        assert_eq!(
+            sector_at(stick(22.6, 1.0), SECTORS, Some(0), &config),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn hysteresis_keeps_current_sector_past_its_edge() {
+        let config = RadialConfig::default();
+        assert_eq!(
+            sector_at(stick(28.0, 1.0), SECTORS, Some(0), &config),
+            Some(0)
+        );
+        assert_eq!(
+            sector_at(stick(29.0, 1.0), SECTORS, Some(0), &config),
+            Some(1)
+        );
+        // Also across the wrap-around at up / 真上での一周の折り返しでも同じ
+        assert_eq!(
+            sector_at(stick(332.0, 1.0), SECTORS, Some(0), &config),
+            Some(0)
+        );
+        assert_eq!(
+            sector_at(stick(331.0, 1.0), SECTORS, Some(0), &config),
+            Some(7)
+        );
+        assert_eq!(
+            sector_at(stick(17.0, 1.0), SECTORS, Some(1), &config),
+            Some(1)
+        );
+        // Without a current sector the nearest one wins / 現在のセクターが無ければ最も近いもの
+        assert_eq!(sector_at(stick(28.0, 1.0), SECTORS, None, &config), Some(1));
+    }
+
+    #[test]
+    fn deadzone_entry_and_exit_differ() {
+        let config = RadialConfig::default();
+        assert_eq!(sector_at(stick(0.0, 0.45), SECTORS, None, &config), None);
+        assert_eq!(sector_at(stick(0.0, 0.55), SECTORS, None, &config), Some(0));
+        assert_eq!(
+            sector_at(stick(0.0, 0.4), SECTORS, Some(0), &config),
+            Some(0)
+        );
+        assert_eq!(sector_at(stick(0.0, 0.3), SECTORS, Some(0), &config), None);
+    }
+
+    #[test]
+    fn empty_ring_has_no_sector() {
+        assert_eq!(
+            sector_at(stick(0.0, 1.0), 0, None, &RadialConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn commit_fires_once_per_press_on_highlighted_sector() {
+        let layout = RadialLayout {
+            left: Vec::new(),
+            right: ["a", "b", "c", "d"].map(String::from).to_vec(),
+        };
+        let config = RadialConfig::default();
+        let mut selector = RadialSelector::default();
+        let sample = |[x, y]: [f64; 2], commit, timestamp| RadialSample {
+            hand: Some(Hand::Right),
+            x,
+            y,
+            commit,
+            timestamp,
+        };
+
+        let events = selector.update(sample(stick(90.0, 1.0), false, 0.0), &layout, &config);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, RadialEventKind::Highlighted);
+        assert_eq!(events[0].character.as_deref(), Some("b"));
+
+        let events = selector.update(sample(stick(90.0, 1.0), true, 0.1), &layout, &config);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, RadialEventKind::Committed);
+        assert_eq!(events[0].character.as_deref(), Some("b"));
+        assert!(selector
+            .update(sample(stick(90.0, 1.0), true, 0.2), &layout, &config)
+            .is_empty());
+
+        let events = selector.update(sample([0.0, 0.0], false, 0.3), &layout, &config);
+        assert_eq!(events[0].sector, None);
+    }
+}
@@ -0,0 +1,168 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::controller_ops::{controller_hand_for, read_controller_state};
+use super::manager::{InputActionCache, OverlayManager};
+use super::radial::{RadialEvent, RadialLayout, RadialSample};
+use super::types::{RadialTypingEvent, RadialTypingLayout, RadialTypingSettings};
+
+/// Joystick, commit buttons and hand role of one controller / コントローラーのスティック・確定ボタン・手の役割
+pub(super) fn read_radial_sample(
+    system: &vr::VR_IVRSystem_FnTable,
+    input: Option<&vr::VR_IVRInput_FnTable>,
+    cache: &InputActionCache,
+    controller_index: u32,
+    timestamp: f64,
+) -> napi::Result<RadialSample> {
+    let state = read_controller_state(system, input, cache, controller_index)?;
+    Ok(RadialSample {
+        hand: controller_hand_for(system, controller_index),
+        x: state.joystickX,
+        y: state.joystickY,
+        commit: state.triggerPressed || state.joystickPressed,
+        timestamp,
+    })
+}
+
+pub(super) fn radial_event(controller_index: u32, event: RadialEvent) -> RadialTypingEvent {
+    RadialTypingEvent {
+        kind: event.kind.as_str().to_string(),
+        controllerIndex: controller_index,
+        hand: event.hand.as_str().to_string(),
+        sector: event.sector.map(|sector| sector as u32),
+        character: event.character,
+        timestamp: event.timestamp,
+    }
+}
+
+fn setting(name: &str, value: Option<f64>, current: f64, max: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 || value > max => Err(
+            napi::Error::from_reason(format!("{name} must be between 0 and {max}")),
+        ),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+fn ring(hand: &str, sectors: Vec<String>) -> napi::Result<Vec<String>> {
+    if sectors.iter().any(String::is_empty) {
+        return Err(napi::Error::from_reason(format!(
+            "Radial layout {hand} ring must not contain empty sectors"
+        )));
+    }
+    Ok(sectors)
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update radial typing deadzones; omitted fields keep their value.
+    /// 放射状入力のデッドゾーンを更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_radial_typing(&self, settings: RadialTypingSettings) -> napi::Result<()> {
+        let now = self.now_seconds();
+        let mut radial = self.lock_radial()?;
+        let mut config = radial.config;
+        if let Some(enabled) = settings.enabled {
+            config.enabled = enabled;
+        }
+        config.deadzone = setting("deadzone", settings.deadzone, config.deadzone, 1.0)?;
+        config.release_deadzone = setting(
+            "releaseDeadzone",
+            settings.releaseDeadzone,
+            config.release_deadzone,
+            1.0,
+        )?;
+        if config.release_deadzone > config.deadzone {
+            return Err(napi::Error::from_reason(
+                "releaseDeadzone must not exceed deadzone",
+            ));
+        }
+        config.hysteresis_degrees = setting(
+            "hysteresisDegrees",
+            settings.hysteresisDegrees,
+            config.hysteresis_degrees,
+            90.0,
+        )?;
+        radial.config = config;
+        radial.reset(now);
+        Ok(())
+    }
+
+    /// Register the sector layout of a language, replacing any previous one.
+    /// The first registered layout becomes active.
+    /// 言語のセクター配置を登録する (既存のものは置き換え)。最初に登録したものが有効になる。
+    #[napi]
+    pub fn set_radial_layout(&self, layout: RadialTypingLayout) -> napi::Result<()> {
+        let left = ring("left", layout.left)?;
+        let right = ring("right", layout.right)?;
+        if left.is_empty() && right.is_empty() {
+            return Err(napi::Error::from_reason(format!(
+                "Radial layout {} has no sectors",
+                layout.language
+            )));
+        }
+
+        let now = self.now_seconds();
+        let mut radial = self.lock_radial()?;
+        if radial.language.is_none() {
+            radial.language = Some(layout.language.clone());
+        }
+        if radial.language.as_ref() == Some(&layout.language) {
+            radial.reset(now);
+        }
+        radial
+            .layouts
+            .insert(layout.language, RadialLayout { left, right });
+        Ok(())
+    }
+
+    /// Switch to a registered layout; current highlights are cleared.
+    /// 登録済みの配置に切り替える (現在のハイライトは消える)
+    #[napi]
+    pub fn select_radial_layout(&self, language: String) -> napi::Result<()> {
+        let now = self.now_seconds();
+        let mut radial = self.lock_radial()?;
+        if !radial.layouts.contains_key(&language) {
+            return Err(napi::Error::from_reason(format!(
+                "Unknown radial layout: {}",
+                language
+            )));
+        }
+        if radial.language.as_ref() != Some(&language) {
+            radial.reset(now);
+            radial.language = Some(language);
+        }
+        Ok(())
+    }
+
+    /// Sample every controller's joystick and return highlight and commit events.
+    /// While the input thread runs they are delivered as "radial" events instead.
+    /// 全コントローラーのスティックをサンプリングし、ハイライトと確定のイベントを返す。
+    /// 入力スレッドの実行中は代わりに "radial" イベントとして届く。
+    #[napi]
+    pub fn poll_radial_events(&self) -> napi::Result<Vec<RadialTypingEvent>> {
+        if self.input_thread_running()? {
+            return Ok(vec![]);
+        }
+        let controller_ids = self.get_controller_ids()?;
+        let system = self.system()?;
+        let input = self.input().ok();
+        let cache = self.borrow_input_cache()?;
+        let mut samples = Vec::with_capacity(controller_ids.len());
+        for index in controller_ids {
+            samples.push((
+                index,
+                read_radial_sample(system, input, &cache, index, self.now_seconds())?,
+            ));
+        }
+        drop(cache);
+
+        let now = self.now_seconds();
+        let events = self.lock_radial()?.update_all(samples, now);
+        Ok(events
+            .into_iter()
+            .map(|(index, event)| radial_event(index, event))
+            .collect())
+    }
+}
@@ -0,0 +1,146 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::constants::{BONE_INDEX_FINGER_TIP, FINGER_INDEX, SKELETON_BONE_COUNT};
+use super::hand::Hand;
+use super::handles::overlay_handle;
+use super::manager::{InputActionCache, OverlayManager};
+use super::math::transform_point34;
+use super::poke_ops::poke_event;
+use super::types::PokeEvent;
+
+// Curled index fingers (fist, grab) must not type / 曲げた人差し指 (握り・掴み) では入力しない
+const INDEX_CURL_MAX_FOR_POKE: f32 = 0.5;
+
+fn skeleton_action(cache: &InputActionCache, hand: Hand) -> vr::VRActionHandle_t {
+    match hand {
+        Hand::Left => cache.skeleton_left_action_handle,
+        Hand::Right => cache.skeleton_right_action_handle,
+    }
+}
+
+/// Index fingertip in tracking space / トラッキング空間での人差し指先端
+#[derive(Clone, Copy)]
+pub(super) struct Fingertip {
+    pub position: [f64; 3],
+    /// Extended enough to poke / 突くのに十分伸びている
+    pub extended: bool,
+}
+
+/// Index fingertip of a hand, or None if the skeleton is unavailable.
+/// 手の人差し指先端 (スケルトンが無ければ None)
+pub(super) fn read_index_fingertip(
+    input: &vr::VR_IVRInput_FnTable,
+    cache: &InputActionCache,
+    hand: Hand,
+) -> Option<Fingertip> {
+    let action_handle = skeleton_action(cache, hand);
+    if action_handle == vr::k_ulInvalidActionHandle {
+        return None;
+    }
+    let get_bone_data_fn = input.GetSkeletalBoneData?;
+    let get_pose_fn = input.GetPoseActionDataForNextFrame?;
+
+    unsafe {
+        let extended = input.GetSkeletalSummaryData.is_some_and(|get_summary_fn| {
+            let mut summary: vr::VRSkeletalSummaryData_t = std::mem::zeroed();
+            let err = get_summary_fn(
+                action_handle,
+                vr::EVRSummaryType_VRSummaryType_FromAnimation,
+                &mut summary,
+            );
+            err == vr::EVRInputError_VRInputError_None
+                && summary.flFingerCurl[FINGER_INDEX] <= INDEX_CURL_MAX_FOR_POKE
+        });
+
+        let mut bones: [vr::VRBoneTransform_t; SKELETON_BONE_COUNT as usize] = std::mem::zeroed();
+        let err = get_bone_data_fn(
+            action_handle,
+            vr::EVRSkeletalTransformSpace_VRSkeletalTransformSpace_Model,
+            vr::EVRSkeletalMotionRange_VRSkeletalMotionRange_WithoutController,
+            bones.as_mut_ptr(),
+            SKELETON_BONE_COUNT,
+        );
+        if err != vr::EVRInputError_VRInputError_None {
+            return None;
+        }
+
+        // Model-space bones are relative to the skeleton action's pose
+        // モデル空間のボーンはスケルトンアクションの姿勢を基準とする
+        let mut pose: vr::InputPoseActionData_t = std::mem::zeroed();
+        let err = get_pose_fn(
+            action_handle,
+            vr::ETrackingUniverseOrigin_TrackingUniverseStanding,
+            &mut pose,
+            std::mem::size_of::<vr::InputPoseActionData_t>() as u32,
+            vr::k_ulInvalidInputValueHandle,
+        );
+        if err != vr::EVRInputError_VRInputError_None || !pose.bActive || !pose.pose.bPoseIsValid {
+            return None;
+        }
+
+        let tip = bones[BONE_INDEX_FINGER_TIP].position.v;
+        Some(Fingertip {
+            position: transform_point34(
+                &pose.pose.mDeviceToAbsoluteTracking.m,
+                [tip[0] as f64, tip[1] as f64, tip[2] as f64],
+            ),
+            extended,
+        })
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Index fingertip position ([x, y, z] in standing space) for "left" or "right".
+    /// "left" / "right" の人差し指先端位置 (スタンディング空間の [x, y, z])
+    #[napi]
+    pub fn get_index_fingertip(&self, hand: String) -> napi::Result<Option<Vec<f64>>> {
+        let hand = Hand::from_name(&hand)?;
+        let input = self.input()?;
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+        }
+        let fingertip = match self.read_input_snapshot(|snapshot| snapshot.fingertips)? {
+            Some(fingertips) => fingertips[hand.index()],
+            None => read_index_fingertip(input, &cache, hand),
+        };
+        Ok(fingertip.map(|tip| tip.position.to_vec()))
+    }
+
+    /// Detect fingertips crossing the overlay plane. Call once per input poll,
+    /// after poll_toggle_clicked has updated the action state.
+    /// 指先がオーバーレイ平面を横切ったかを検出する。入力ポーリング毎に
+    /// poll_toggle_clicked でアクション状態を更新した後に呼び出すこと。
+    #[napi]
+    pub fn poll_finger_poke(&self, handle: i64) -> napi::Result<Vec<PokeEvent>> {
+        let handle = overlay_handle(handle)?;
+        let input = self.input()?;
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+        }
+        let fingertips = match self.read_input_snapshot(|snapshot| snapshot.fingertips)? {
+            Some(fingertips) => fingertips,
+            None => Hand::ALL.map(|hand| read_index_fingertip(input, &cache, hand)),
+        };
+
+        let plane = self.overlay_plane(handle)?;
+        let timestamp = self.now_seconds();
+        let mut poke = self.lock_poke()?;
+        let config = poke.config;
+        let mut events = Vec::new();
+        for hand in Hand::ALL {
+            let projection = fingertips[hand.index()]
+                .filter(|tip| tip.extended)
+                .map(|tip| plane.project(tip.position));
+            if let Some((kind, projection)) =
+                poke.fingers[hand.index()].update(projection, timestamp, &config)
+            {
+                events.push(poke_event(kind, "finger", Some(hand), None, projection));
+            }
+        }
+        Ok(events)
+    }
+}
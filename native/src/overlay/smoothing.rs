@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// 1€ filter parameters / 1€ フィルタのパラメータ
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SmoothingConfig {
+    pub enabled: bool,
+    /// Cutoff frequency at rest (Hz) / 静止時のカットオフ周波数 (Hz)
+    pub min_cutoff: f64,
+    /// Cutoff increase per unit of speed / 速度に応じたカットオフの増加量
+    pub beta: f64,
+    /// Cutoff for the speed estimate (Hz) / 速度推定のカットオフ周波数 (Hz)
+    pub derivative_cutoff: f64,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        // Same tuning as the former JS PointerStabilizer(0.1, 5.0, 1.0)
+        // 旧 JS 版 PointerStabilizer(0.1, 5.0, 1.0) と同じ調整値
+        Self {
+            enabled: true,
+            min_cutoff: 0.1,
+            beta: 5.0,
+            derivative_cutoff: 1.0,
+        }
+    }
+}
+
+fn alpha(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+/// N-dimensional 1€ filter; speed is the magnitude of the filtered derivative.
+/// N 次元の 1€ フィルタ。速度はフィルタ後の微分ベクトルの大きさ
+#[derive(Clone, Copy, Debug)]
+pub(super) struct OneEuroFilter<const N: usize> {
+    value: Option<[f64; N]>,
+    derivative: [f64; N],
+    last_timestamp: f64,
+}
+
+impl<const N: usize> OneEuroFilter<N> {
+    pub(super) fn new() -> Self {
+        Self {
+            value: None,
+            derivative: [0.0; N],
+            last_timestamp: 0.0,
+        }
+    }
+
+    /// Feed a raw sample stamped in seconds / 秒単位のタイムスタンプ付きで生の値を入力
+    pub(super) fn update(
+        &mut self,
+        raw: [f64; N],
+        timestamp: f64,
+        config: &SmoothingConfig,
+    ) -> [f64; N] {
+        let Some(value) = self.value else {
+            self.value = Some(raw);
+            self.derivative = [0.0; N];
+            self.last_timestamp = timestamp;
+            return raw;
+        };
+
+        let dt = timestamp - self.last_timestamp;
+        if dt <= 0.0 {
+            // Same pose frame: nothing new to filter / 同一フレーム: フィルタ対象なし
+            return value;
+        }
+        self.last_timestamp = timestamp;
+
+        let lambda_d = alpha(config.derivative_cutoff, dt);
+        let mut speed_squared = 0.0;
+        for i in 0..N {
+            let raw_derivative = (raw[i] - value[i]) / dt;
+            self.derivative[i] += lambda_d * (raw_derivative - self.derivative[i]);
+            speed_squared += self.derivative[i] * self.derivative[i];
+        }
+
+        let cutoff = config.min_cutoff + config.beta * speed_squared.sqrt();
+        let lambda = alpha(cutoff, dt);
+        let mut filtered = value;
+        for i in 0..N {
+            filtered[i] += lambda * (raw[i] - value[i]);
+        }
+        self.value = Some(filtered);
+        filtered
+    }
+
+    pub(super) fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Stabilizer for one controller/overlay pair: UV and hit point filtered separately.
+/// コントローラーとオーバーレイの組ごとの安定化器 (UV とヒット位置を個別にフィルタ)
+pub(super) struct PointerStabilizer {
+    pub uv: OneEuroFilter<2>,
+    pub point: OneEuroFilter<3>,
+}
+
+impl PointerStabilizer {
+    pub(super) fn new() -> Self {
+        Self {
+            uv: OneEuroFilter::new(),
+            point: OneEuroFilter::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct SmoothingState {
+    pub config: SmoothingConfig,
+    /// Keyed by (controller index, overlay handle) / (コントローラー番号, オーバーレイハンドル) がキー
+    pub stabilizers: HashMap<(u32, u64), PointerStabilizer>,
+}
+
+impl SmoothingState {
+    /// Carry filters over to a controller's new index after it reconnected.
+    /// 再接続したコントローラーの新しい番号にフィルタを引き継ぐ
+    pub(super) fn remap_controller(&mut self, previous: u32, index: u32) {
+        let moved: Vec<(u32, u64)> = self
+            .stabilizers
+            .keys()
+            .copied()
+            .filter(|(controller, _)| *controller == previous)
+            .collect();
+        for key in moved {
+            if let Some(stabilizer) = self.stabilizers.remove(&key) {
+                self.stabilizers.insert((index, key.1), stabilizer);
+            }
+        }
+    }
+
+    /// Filter a hit's UV and point, or pass them through when disabled.
+    /// ヒットの UV と位置をフィルタ (無効時はそのまま返す)
+    pub(super) fn filter(
+        &mut self,
+        key: (u32, u64),
+        uv: [f64; 2],
+        point: [f64; 3],
+        timestamp: f64,
+    ) -> ([f64; 2], [f64; 3]) {
+        if !self.config.enabled {
+            return (uv, point);
+        }
+        let config = self.config;
+        let stabilizer = self
+            .stabilizers
+            .entry(key)
+            .or_insert_with(PointerStabilizer::new);
+        (
+            stabilizer.uv.update(uv, timestamp, &config),
+            stabilizer.point.update(point, timestamp, &config),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: SmoothingConfig = SmoothingConfig {
+        enabled: true,
+        min_cutoff: 0.1,
+        beta: 5.0,
+        derivative_cutoff: 1.0,
+    };
+
+    #[test]
+    fn first_sample_passes_through() {
+        let mut filter = OneEuroFilter::<2>::new();
+        assert_eq!(filter.update([0.3, 0.7], 1.0, &CONFIG), [0.3, 0.7]);
+    }
+
+    #[test]
+    fn same_timestamp_keeps_the_previous_value() {
+        let mut filter = OneEuroFilter::<1>::new();
+        filter.update([0.0], 1.0, &CONFIG);
+        assert_eq!(filter.update([1.0], 1.0, &CONFIG), [0.0]);
+        assert_eq!(filter.update([1.0], 0.5, &CONFIG), [0.0]);
+    }
+
+    #[test]
+    fn jitter_at_rest_is_damped() {
+        let mut filter = OneEuroFilter::<1>::new();
+        filter.update([0.5], 0.0, &CONFIG);
+        let mut max_offset: f64 = 0.0;
+        for frame in 1..=90 {
+            let raw = if frame % 2 == 0 { 0.501 } else { 0.499 };
+            let [value] = filter.update([raw], frame as f64 / 90.0, &CONFIG);
+            max_offset = max_offset.max((value - 0.5).abs());
+        }
+        assert!(max_offset < 0.0005, "offset {max_offset}");
+    }
+
+    #[test]
+    fn fast_motion_follows_more_closely_than_slow_motion() {
+        let lag = |speed: f64| {
+            let mut filter = OneEuroFilter::<1>::new();
+            let mut value = 0.0;
+            for frame in 0..=30 {
+                let t = frame as f64 / 90.0;
+                [value] = filter.update([speed * t], t, &CONFIG);
+            }
+            (speed * 30.0 / 90.0 - value) / speed
+        };
+        assert!(lag(2.0) < lag(0.05));
+        assert!(lag(2.0) > 0.0);
+    }
+
+    #[test]
+    fn reset_restarts_from_the_raw_value() {
+        let mut filter = OneEuroFilter::<1>::new();
+        filter.update([0.0], 0.0, &CONFIG);
+        filter.update([1.0], 0.1, &CONFIG);
+        filter.reset();
+        assert_eq!(filter.update([0.8], 0.2, &CONFIG), [0.8]);
+    }
+
+    #[test]
+    fn disabled_state_passes_hits_through() {
+        let mut state = SmoothingState {
+            config: SmoothingConfig {
+                enabled: false,
+                ..CONFIG
+            },
+            ..SmoothingState::default()
+        };
+        state.filter((1, 7), [0.0, 0.0], [0.0; 3], 0.0);
+        assert_eq!(
+            state.filter((1, 7), [1.0, 1.0], [1.0; 3], 0.1),
+            ([1.0, 1.0], [1.0; 3])
+        );
+        assert!(state.stabilizers.is_empty());
+    }
+
+    #[test]
+    fn remap_moves_filters_to_the_new_index() {
+        let mut state = SmoothingState::default();
+        state.filter((3, 7), [0.2, 0.2], [0.0; 3], 0.0);
+        state.remap_controller(3, 5);
+        assert!(state.stabilizers.contains_key(&(5, 7)));
+        assert!(!state.stabilizers.contains_key(&(3, 7)));
+    }
+}
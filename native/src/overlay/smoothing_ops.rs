@@ -0,0 +1,112 @@
+use super::handles::overlay_handle;
+use super::manager::OverlayManager;
+use super::pose_ops::aim_ray;
+use super::smoothing::SmoothingState;
+use super::types::{ControllerIntersection, IntersectionResult, SmoothingSettings};
+use napi_derive::napi;
+
+fn positive(name: &str, value: Option<f64>, current: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value <= 0.0 => Err(napi::Error::from_reason(
+            format!("{name} must be a positive number"),
+        )),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+/// Smooth a raw hit's UV and point under `key` / 生のヒットの UV と位置を `key` のフィルタで平滑化
+pub(super) fn smooth_hit(
+    smoothing: &mut SmoothingState,
+    key: (u32, u64),
+    raw: &IntersectionResult,
+    timestamp: f64,
+) -> IntersectionResult {
+    let ([u, v], [x, y, z]) =
+        smoothing.filter(key, [raw.u, raw.v], [raw.x, raw.y, raw.z], timestamp);
+    IntersectionResult {
+        x,
+        y,
+        z,
+        u,
+        v,
+        distance: raw.distance,
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update 1€ pointer filter parameters; omitted fields keep their value.
+    /// ポインター用 1€ フィルタのパラメータを更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_pointer_smoothing(&self, settings: SmoothingSettings) -> napi::Result<()> {
+        let mut smoothing = self.lock_smoothing()?;
+        let mut config = smoothing.config;
+        if let Some(enabled) = settings.enabled {
+            config.enabled = enabled;
+        }
+        config.min_cutoff = positive("minCutoff", settings.minCutoff, config.min_cutoff)?;
+        if let Some(beta) = settings.beta {
+            if !beta.is_finite() || beta < 0.0 {
+                return Err(napi::Error::from_reason(
+                    "beta must be a non-negative number",
+                ));
+            }
+            config.beta = beta;
+        }
+        config.derivative_cutoff = positive(
+            "derivativeCutoff",
+            settings.derivativeCutoff,
+            config.derivative_cutoff,
+        )?;
+
+        smoothing.config = config;
+        // Filter history is meaningless under new parameters / パラメータ変更後は履歴を破棄
+        smoothing.stabilizers.clear();
+        Ok(())
+    }
+
+    /// Intersect the controller ray with the overlay and return raw and smoothed hits.
+    /// The ray comes from the pointer pose action, or the controller's pose when it is unbound.
+    /// コントローラーのレイとオーバーレイの交点を、生の値と平滑化済みの値で返す。
+    /// レイはポーズアクションから取得し、未バインド時はコントローラーの姿勢を使う。
+    #[napi]
+    pub fn compute_controller_intersection(
+        &self,
+        handle: i64,
+        controller_index: u32,
+    ) -> napi::Result<Option<ControllerIntersection>> {
+        let overlay = overlay_handle(handle)?.as_u64();
+        let key = (controller_index, overlay);
+
+        let pose = self.device_pose(controller_index)?;
+        let pointer = self.controller_pointer_ray(controller_index)?;
+        let Some((origin, direction)) = aim_ray(pointer.as_ref(), pose.as_ref()) else {
+            self.lock_smoothing()?.stabilizers.remove(&key);
+            self.lock_swipe()?.hover(controller_index, overlay, None);
+            return Ok(None);
+        };
+        // Pointer rays are read for the same frame as the device poses
+        // ポインターレイはデバイス姿勢と同じフレーム向けに読み取る
+        let timestamp = pose.map_or_else(|| self.now_seconds(), |pose| pose.timestamp);
+        let Some(raw) =
+            self.compute_overlay_intersection(handle, origin.to_vec(), direction.to_vec())?
+        else {
+            // Restart from the raw position on re-entry / 再進入時は生の位置から再開
+            self.lock_smoothing()?.stabilizers.remove(&key);
+            self.lock_swipe()?.hover(controller_index, overlay, None);
+            return Ok(None);
+        };
+
+        let smoothed = smooth_hit(&mut self.lock_smoothing()?, key, &raw, timestamp);
+        // Extends the swipe trail while the trigger is held / トリガー押下中はスワイプの軌跡を伸ばす
+        self.lock_swipe()?
+            .hover(controller_index, overlay, Some([smoothed.u, smoothed.v]));
+
+        Ok(Some(ControllerIntersection {
+            raw,
+            smoothed,
+            timestamp,
+        }))
+    }
+}
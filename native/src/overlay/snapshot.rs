@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+
+use windows::Win32::Graphics::Direct3D11::ID3D11Texture2D;
+
+/// Where the most recently submitted frame can be read back from
+/// 直近に送信したフレームを読み戻せる場所
+pub(super) enum FramePixels {
+    /// Packed RGBA copied from a SetOverlayRaw upload / SetOverlayRaw でアップロードした詰めた RGBA のコピー
+    Rgba(Vec<u8>),
+    /// Converted frame kept by the CPU path of submit_frame for this front handle
+    /// submit_frame の CPU パスがこの前面ハンドル用に保持している変換済みフレーム
+    History(u64),
+    /// Output ring texture the D3D11 path submitted / D3D11 パスが送信した出力リングのテクスチャ
+    Texture(ID3D11Texture2D),
+}
+
+/// Most recent frame passed to SetOverlayRaw or SetOverlayTexture, after conversion
+/// 変換後の、直近に SetOverlayRaw または SetOverlayTexture に渡したフレーム
+pub(super) struct LastFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: FramePixels,
+}
+
+/// Frame copied for the capture writer / キャプチャの書き出しスレッドに渡すフレームのコピー
+struct CapturedFrame {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// Pending captureNextFrames request. Frames are encoded on its own writer thread, which
+/// finishes the queued files and exits once the capture is dropped.
+/// 実行中の captureNextFrames の要求。フレームは専用の書き出しスレッドでエンコードし、
+/// スレッドはキャプチャの破棄後、待機中のファイルを書き終えて終了する。
+pub(super) struct FrameCapture {
+    dir: PathBuf,
+    /// Frames still to queue / キューに積む残りのフレーム数
+    remaining: u32,
+    /// Number of the next file / 次のファイルの番号
+    next_index: u32,
+    frames: mpsc::Sender<CapturedFrame>,
+    /// Set by the writer when a file could not be written / ファイルを書き出せなかった場合に書き出しスレッドが立てる
+    failed: Arc<AtomicBool>,
+}
+
+impl FrameCapture {
+    pub(super) fn start(dir: PathBuf, count: u32) -> Result<Self, String> {
+        let (frames, queued) = mpsc::channel::<CapturedFrame>();
+        let failed = Arc::new(AtomicBool::new(false));
+        let writer_failed = failed.clone();
+        std::thread::Builder::new()
+            .name("vr-overlay-capture".to_string())
+            .spawn(move || {
+                for frame in queued {
+                    if let Err(e) = write_png(&frame.path, frame.width, frame.height, &frame.rgba) {
+                        eprintln!("[vr-overlay-native] frame capture: {}", e);
+                        writer_failed.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn frame capture writer: {}", e))?;
+        Ok(Self {
+            dir,
+            remaining: count,
+            next_index: 0,
+            frames,
+            failed,
+        })
+    }
+
+    /// Queue a frame for writing; returns false once the capture is over.
+    /// A failed write ends it before the next frame.
+    /// フレームを書き出し待ちに積む (キャプチャが終わったら false)。
+    /// 書き出しに失敗した場合は次のフレームの前に終了する。
+    pub(super) fn push(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> bool {
+        if self.failed.load(Ordering::Relaxed) || self.remaining == 0 {
+            return false;
+        }
+        let path = self.dir.join(format!("frame-{:04}.png", self.next_index));
+        self.next_index += 1;
+        self.remaining -= 1;
+        let frame = CapturedFrame {
+            path,
+            width,
+            height,
+            rgba,
+        };
+        self.frames.send(frame).is_ok() && self.remaining > 0
+    }
+}
+
+/// Write tightly packed 8-bit RGBA pixels to `path` as a PNG / 詰めた 8 ビット RGBA を PNG として `path` に書き出す
+pub(super) fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    let expected = width as usize * height as usize * 4;
+    if rgba.len() != expected {
+        return Err(format!(
+            "Frame is {} bytes, expected {} for {}x{}",
+            rgba.len(),
+            expected,
+            width,
+            height
+        ));
+    }
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let failed = |e: png::EncodingError| format!("Failed to write {}: {}", path.display(), e);
+    let mut writer = encoder.write_header().map_err(failed)?;
+    writer.write_image_data(rgba).map_err(failed)?;
+    writer.finish().map_err(failed)
+}
@@ -0,0 +1,47 @@
+use napi_derive::napi;
+use std::path::{Path, PathBuf};
+
+use super::constants::MAX_CAPTURE_FRAMES;
+use super::manager::OverlayManager;
+use super::snapshot::FrameCapture;
+
+#[napi]
+impl OverlayManager {
+    /// Write the most recent frame submitted by setOverlayRaw, setOverlayTexturesD3D11 or
+    /// submitOverlayFrame to `path` as a PNG, after the conversion applied for upload. On the
+    /// D3D11 path the output texture is read back from the GPU, so the file shows exactly
+    /// what SteamVR was given, channel order included.
+    /// setOverlayRaw、setOverlayTexturesD3D11、submitOverlayFrame で直近に送信したフレームを、
+    /// アップロード用の変換後の状態で PNG として `path` に書き出す。D3D11 パスでは出力テクスチャを
+    /// GPU から読み戻すため、チャネル順も含めて SteamVR に渡した内容そのものになる。
+    #[napi]
+    pub fn save_last_frame(&self, path: String) -> napi::Result<()> {
+        self.lock_pipeline()?.save_last_frame(Path::new(&path))
+    }
+
+    /// Write each of the next `count` submitted frames to `dir` as it is submitted, named
+    /// `frame-0000.png`, `frame-0001.png` and so on. Frames skipped as unchanged are not
+    /// written. Replaces a capture that is still running. Each frame is copied as it is
+    /// submitted and written by a background thread, so files may appear after the call
+    /// that submitted them returns.
+    /// 次に送信する `count` 枚のフレームを、送信のたびに `frame-0000.png`、`frame-0001.png` …
+    /// として `dir` に書き出す。変化が無く省略したフレームは書き出さない。実行中のキャプチャは
+    /// 置き換える。各フレームは送信時にコピーしてバックグラウンドのスレッドで書き出すため、
+    /// ファイルは送信した呼び出しが戻った後に現れることがある。
+    #[napi]
+    pub fn capture_next_frames(&self, count: u32, dir: String) -> napi::Result<()> {
+        if count == 0 || count > MAX_CAPTURE_FRAMES {
+            return Err(napi::Error::from_reason(format!(
+                "count must be between 1 and {}",
+                MAX_CAPTURE_FRAMES
+            )));
+        }
+        let dir = PathBuf::from(dir);
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            napi::Error::from_reason(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+        let capture = FrameCapture::start(dir, count).map_err(napi::Error::from_reason)?;
+        self.lock_pipeline()?.capture = Some(capture);
+        Ok(())
+    }
+}
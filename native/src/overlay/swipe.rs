@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::trigger::{TriggerEdge, TriggerEventKind};
+
+/// Points both paths are resampled to before comparing / 比較前に両方の軌跡を再サンプリングする点数
+const RESAMPLE_POINTS: usize = 32;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SwipeConfig {
+    pub enabled: bool,
+    /// Trail points closer than this (UV) to the previous one are dropped
+    /// 直前の点からこれ (UV) 未満の軌跡の点は捨てる
+    pub min_sample_distance: f64,
+    /// Typical distance (UV) between the trail and a word's key path
+    /// 軌跡と単語のキー経路の典型的なずれ (UV)
+    pub tolerance: f64,
+    pub max_candidates: usize,
+}
+
+impl Default for SwipeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_sample_distance: 0.004,
+            tolerance: 0.05,
+            max_candidates: 5,
+        }
+    }
+}
+
+/// Key centers of an overlay by lowercase character / オーバーレイ上のキー中心 (小文字の文字ごと)
+#[derive(Clone, Debug, Default)]
+pub(super) struct SwipeLayout {
+    keys: HashMap<char, [f64; 2]>,
+    /// Distance between the two closest keys / 最も近い 2 つのキーの間隔
+    pitch: Option<f64>,
+    /// Key paths of the lexicon last decoded against / 直近に復号した語彙のキー経路
+    paths: Option<KeyPaths>,
+}
+
+/// Resampled key paths of a lexicon's words, by first and last letter.
+/// 語彙の単語の再サンプリング済みキー経路 (先頭と末尾の文字ごと)
+#[derive(Clone, Debug)]
+struct KeyPaths {
+    /// Held so a replaced lexicon is never mistaken for this one / 差し替えた語彙と取り違えないよう保持する
+    lexicon: Arc<Lexicon>,
+    by_ends: HashMap<(char, char), Vec<WordPath>>,
+}
+
+#[derive(Clone, Debug)]
+struct WordPath {
+    /// Index into the lexicon's words / 語彙の単語の番号
+    word: usize,
+    /// Key path resampled to RESAMPLE_POINTS / RESAMPLE_POINTS 点に再サンプリングしたキー経路
+    points: Vec<[f64; 2]>,
+}
+
+impl SwipeLayout {
+    /// Only single-character labels take part in decoding / 1 文字のラベルのキーだけを復号に使う
+    pub(super) fn new<'a>(keys: impl IntoIterator<Item = (&'a str, [f64; 2])>) -> Self {
+        let mut layout = Self::default();
+        for (label, center) in keys {
+            let mut chars = label.chars().flat_map(char::to_lowercase);
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                layout.keys.insert(c, center);
+            }
+        }
+        let centers: Vec<[f64; 2]> = layout.keys.values().copied().collect();
+        layout.pitch = centers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| centers[i + 1..].iter().map(|b| distance(*a, *b)))
+            .filter(|pitch| *pitch > 0.0)
+            .min_by(f64::total_cmp);
+        layout
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Centers visited when swiping `word`, or None if a letter has no key.
+    /// `word` をスワイプしたときに通るキー中心 (キーの無い文字があれば None)
+    fn key_path(&self, word: &str) -> Option<Vec<[f64; 2]>> {
+        let mut path: Vec<[f64; 2]> = Vec::new();
+        for c in word.chars() {
+            let center = *self.keys.get(&c)?;
+            // Double letters do not move the pointer / 同じ文字の連続ではポインターは動かない
+            if path.last() != Some(&center) {
+                path.push(center);
+            }
+        }
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Key paths for `lexicon`, resampled once per layout and lexicon.
+    /// `lexicon` のキー経路 (レイアウトと語彙の組ごとに一度だけ再サンプリングする)
+    fn key_paths(&mut self, lexicon: &Arc<Lexicon>) -> &KeyPaths {
+        let cached = self
+            .paths
+            .take()
+            .filter(|paths| Arc::ptr_eq(&paths.lexicon, lexicon));
+        let paths = cached.unwrap_or_else(|| {
+            let mut by_ends: HashMap<(char, char), Vec<WordPath>> = HashMap::new();
+            for (word_index, (word, _)) in lexicon.words.iter().enumerate() {
+                let (Some(first), Some(last)) = (word.chars().next(), word.chars().last()) else {
+                    continue;
+                };
+                if let Some(path) = self.key_path(word) {
+                    by_ends.entry((first, last)).or_default().push(WordPath {
+                        word: word_index,
+                        points: resample(&path, RESAMPLE_POINTS),
+                    });
+                }
+            }
+            KeyPaths {
+                lexicon: Arc::clone(lexicon),
+                by_ends,
+            }
+        });
+        self.paths.insert(paths)
+    }
+
+    /// Letters whose key is within `reach` of `point` / `point` から `reach` 以内にあるキーの文字
+    fn letters_near(&self, point: [f64; 2], reach: f64) -> Vec<char> {
+        self.keys
+            .iter()
+            .filter(|(_, center)| distance(**center, point) <= reach)
+            .map(|(c, _)| *c)
+            .collect()
+    }
+
+    /// Whether the trail travels further than one key pitch; shorter ones are taps.
+    /// 軌跡がキー 1 つ分の間隔より長く動いたか (短いものはタップ)
+    fn is_swipe(&self, points: &[[f64; 2]]) -> bool {
+        let length: f64 = points.windows(2).map(|w| distance(w[0], w[1])).sum();
+        self.pitch.is_some_and(|pitch| length > pitch)
+    }
+}
+
+/// Words with their relative frequency / 単語と相対頻度
+#[derive(Debug, Default)]
+pub(super) struct Lexicon {
+    pub words: Vec<(String, f64)>,
+}
+
+impl Lexicon {
+    /// Parse one `word [frequency]` per line; `#` starts a comment and a missing frequency is 1.
+    /// 1 行に `単語 [頻度]` を読み取る。`#` 以降はコメントで、頻度の省略は 1 とみなす
+    pub(super) fn parse(text: &str) -> Result<Self, String> {
+        let mut frequencies: HashMap<String, f64> = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut fields = line.split_whitespace();
+            let Some(word) = fields.next() else {
+                continue;
+            };
+            let frequency = match fields.next() {
+                Some(field) => field
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite() && *value > 0.0)
+                    .ok_or_else(|| format!("line {}: invalid frequency {:?}", number + 1, field))?,
+                None => 1.0,
+            };
+            if fields.next().is_some() {
+                return Err(format!("line {}: expected `word [frequency]`", number + 1));
+            }
+            *frequencies.entry(word.to_lowercase()).or_default() += frequency;
+        }
+
+        let mut words: Vec<(String, f64)> = frequencies.into_iter().collect();
+        words.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(Self { words })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct SwipeTrail {
+    pub overlay: u64,
+    pub points: Vec<[f64; 2]>,
+    pub started_at: f64,
+    pub ended_at: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct SwipeCandidate {
+    pub word: String,
+    /// Share of the likelihood among the scored words (0..1) / 評価した単語の中での尤度の割合 (0..1)
+    pub score: f64,
+}
+
+fn distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+/// `count` points evenly spaced along the polyline / 折れ線に沿って等間隔に並べた `count` 個の点
+fn resample(points: &[[f64; 2]], count: usize) -> Vec<[f64; 2]> {
+    let length: f64 = points.windows(2).map(|w| distance(w[0], w[1])).sum();
+    if points.len() < 2 || length <= 0.0 {
+        return vec![points.first().copied().unwrap_or_default(); count];
+    }
+
+    let step = length / (count - 1) as f64;
+    let mut result = Vec::with_capacity(count);
+    result.push(points[0]);
+    let mut walked = 0.0;
+    let mut next = step;
+    for w in points.windows(2) {
+        let segment = distance(w[0], w[1]);
+        while segment > 0.0 && next <= walked + segment && result.len() < count - 1 {
+            let t = (next - walked) / segment;
+            result.push([
+                w[0][0] + (w[1][0] - w[0][0]) * t,
+                w[0][1] + (w[1][1] - w[0][1]) * t,
+            ]);
+            next += step;
+        }
+        walked += segment;
+    }
+    result.push(points[points.len() - 1]);
+    result
+}
+
+fn mean_distance(a: &[[f64; 2]], b: &[[f64; 2]]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| distance(*a, *b)).sum::<f64>() / a.len() as f64
+}
+
+/// Rank lexicon words by how well their key path matches the trail, weighted by frequency.
+/// 単語のキー経路と軌跡の一致度を頻度で重み付けし、語彙を順位付けする
+pub(super) fn decode_trail(
+    trail: &[[f64; 2]],
+    layout: &mut SwipeLayout,
+    lexicon: &Arc<Lexicon>,
+    config: &SwipeConfig,
+) -> Vec<SwipeCandidate> {
+    let (Some(&first), Some(&last)) = (trail.first(), trail.last()) else {
+        return Vec::new();
+    };
+    let tolerance = config.tolerance.max(f64::EPSILON);
+    // Words must start and end near where the swipe did / 単語の始点と終点はスワイプの始点・終点の近くにあること
+    let reach = tolerance * 3.0;
+    let starts = layout.letters_near(first, reach);
+    let ends = layout.letters_near(last, reach);
+    let trail = resample(trail, RESAMPLE_POINTS);
+    let max_frequency = lexicon
+        .words
+        .first()
+        .map_or(1.0, |(_, frequency)| *frequency);
+
+    let paths = layout.key_paths(lexicon);
+    let mut scored: Vec<(usize, f64)> = Vec::new();
+    for start in &starts {
+        for end in &ends {
+            let Some(words) = paths.by_ends.get(&(*start, *end)) else {
+                continue;
+            };
+            for path in words {
+                let shape = mean_distance(&trail, &path.points) / tolerance;
+                let frequency = lexicon.words[path.word].1;
+                scored.push((
+                    path.word,
+                    -0.5 * shape * shape + (frequency / max_frequency).ln(),
+                ));
+            }
+        }
+    }
+
+    let best = scored
+        .iter()
+        .map(|(_, score)| *score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let total: f64 = scored.iter().map(|(_, score)| (score - best).exp()).sum();
+    // Ties keep lexicon order / 同点は語彙の順序を保つ
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+        .into_iter()
+        .take(config.max_candidates)
+        .map(|(index, score)| SwipeCandidate {
+            word: lexicon.words[index].0.clone(),
+            score: (score - best).exp() / total,
+        })
+        .collect()
+}
+
+/// Trails being recorded per controller, started by a trigger press over a keyboard.
+/// キーボード上でのトリガー押下で始まる、コントローラーごとの記録中の軌跡
+#[derive(Default)]
+pub(super) struct SwipeRecorder {
+    /// Last pointer position per controller / コントローラーごとの直近のポインター位置
+    hovering: HashMap<u32, (u64, [f64; 2])>,
+    trails: HashMap<u32, SwipeTrail>,
+}
+
+impl SwipeRecorder {
+    /// Feed the controller's pointer on `overlay` (None = not on it).
+    /// `overlay` 上のコントローラーのポインターを入力 (None は当たっていない)
+    pub(super) fn hover(
+        &mut self,
+        index: u32,
+        overlay: u64,
+        uv: Option<[f64; 2]>,
+        config: &SwipeConfig,
+    ) {
+        let Some(uv) = uv else {
+            if self
+                .hovering
+                .get(&index)
+                .is_some_and(|(on, _)| *on == overlay)
+            {
+                self.hovering.remove(&index);
+            }
+            return;
+        };
+        self.hovering.insert(index, (overlay, uv));
+        // Off-keyboard stretches just leave a gap / キーボード外に出た区間は単に途切れる
+        if let Some(trail) = self.trails.get_mut(&index) {
+            if trail.overlay == overlay
+                && trail
+                    .points
+                    .last()
+                    .is_none_or(|last| distance(*last, uv) >= config.min_sample_distance)
+            {
+                trail.points.push(uv);
+            }
+        }
+    }
+
+    /// Trigger pressed: start a trail if the pointer is on an overlay in `keyboards`.
+    /// トリガー押下: ポインターが `keyboards` のオーバーレイ上にあれば軌跡を開始
+    pub(super) fn press(&mut self, index: u32, timestamp: f64, keyboards: impl Fn(u64) -> bool) {
+        let Some(&(overlay, uv)) = self.hovering.get(&index) else {
+            return;
+        };
+        if keyboards(overlay) {
+            self.trails.insert(
+                index,
+                SwipeTrail {
+                    overlay,
+                    points: vec![uv],
+                    started_at: timestamp,
+                    ended_at: timestamp,
+                },
+            );
+        }
+    }
+
+    /// Trigger released: the finished trail, if one was recording / トリガー解放: 記録中だった軌跡
+    pub(super) fn release(&mut self, index: u32, timestamp: f64) -> Option<SwipeTrail> {
+        let mut trail = self.trails.remove(&index)?;
+        trail.ended_at = timestamp;
+        Some(trail)
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.hovering.clear();
+        self.trails.clear();
+    }
+}
+
+#[derive(Default)]
+pub(super) struct SwipeState {
+    pub config: SwipeConfig,
+    /// Key layouts per overlay handle / オーバーレイハンドルごとのキー配置
+    pub layouts: HashMap<u64, SwipeLayout>,
+    /// Shared with the input thread without copying / 入力スレッドとコピー無しで共有
+    pub lexicon: Arc<Lexicon>,
+    pub recorder: SwipeRecorder,
+    /// Decoded swipes waiting for poll_swipe_results / poll_swipe_results 待ちの復号済みスワイプ
+    pub finished: Vec<(u32, SwipeTrail, Vec<SwipeCandidate>)>,
+}
+
+impl SwipeState {
+    fn press(&mut self, index: u32, timestamp: f64) {
+        if !self.config.enabled {
+            return;
+        }
+        let layouts = &self.layouts;
+        self.recorder
+            .press(index, timestamp, |overlay| layouts.contains_key(&overlay));
+    }
+
+    pub(super) fn hover(&mut self, index: u32, overlay: u64, uv: Option<[f64; 2]>) {
+        if self.config.enabled {
+            self.recorder.hover(index, overlay, uv, &self.config);
+        }
+    }
+
+    /// Start or finish a trail on a trigger edge; returns the decoded swipe on release.
+    /// トリガーのエッジで軌跡を開始・終了する (解放時は復号結果を返す)
+    pub(super) fn trigger_edge(
+        &mut self,
+        index: u32,
+        edge: &TriggerEdge,
+    ) -> Option<(SwipeTrail, Vec<SwipeCandidate>)> {
+        match edge.kind {
+            TriggerEventKind::Pressed => {
+                self.press(index, edge.timestamp);
+                None
+            }
+            TriggerEventKind::Released => self.release(index, edge.timestamp),
+            _ => None,
+        }
+    }
+
+    /// Finish the controller's trail and decode it; taps shorter than a key pitch give None.
+    /// コントローラーの軌跡を終了して復号する (キー 1 つ分より短いタップは None)
+    fn release(&mut self, index: u32, timestamp: f64) -> Option<(SwipeTrail, Vec<SwipeCandidate>)> {
+        let trail = self.recorder.release(index, timestamp)?;
+        let layout = self.layouts.get_mut(&trail.overlay)?;
+        if !layout.is_swipe(&trail.points) {
+            return None;
+        }
+        let candidates = decode_trail(&trail.points, layout, &self.lexicon, &self.config);
+        Some((trail, candidates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One row of keys one pitch (0.1) apart / 0.1 間隔で並んだ 1 列のキー
+    fn row_layout(labels: &str) -> SwipeLayout {
+        let labels: Vec<String> = labels.chars().map(String::from).collect();
+        SwipeLayout::new(
+            labels
+                .iter()
+                .enumerate()
+                .map(|(i, label)| (label.as_str(), [0.1 + 0.1 * i as f64, 0.5])),
+        )
+    }
+
+    fn shared_lexicon(text: &str) -> Arc<Lexicon> {
+        Arc::new(Lexicon::parse(text).unwrap())
+    }
+
+    fn close(a: [f64; 2], b: [f64; 2]) -> bool {
+        distance(a, b) < 1e-9
+    }
+
+    fn edge(kind: TriggerEventKind, timestamp: f64) -> TriggerEdge {
+        TriggerEdge {
+            kind,
+            timestamp,
+            value: 1.0,
+        }
+    }
+
+    fn swipe_state(labels: &str, lexicon: &str) -> SwipeState {
+        let mut state = SwipeState {
+            config: SwipeConfig {
+                enabled: true,
+                ..SwipeConfig::default()
+            },
+            lexicon: shared_lexicon(lexicon),
+            ..SwipeState::default()
+        };
+        state.layouts.insert(1, row_layout(labels));
+        state
+    }
+
+    /// Press at the first point, hover the rest and release / 最初の点で押し、残りをなぞって離す
+    fn swipe(state: &mut SwipeState, points: &[[f64; 2]]) -> Option<Vec<SwipeCandidate>> {
+        state.hover(3, 1, Some(points[0]));
+        state.trigger_edge(3, &edge(TriggerEventKind::Pressed, 0.0));
+        for point in &points[1..] {
+            state.hover(3, 1, Some(*point));
+        }
+        state
+            .trigger_edge(3, &edge(TriggerEventKind::Released, 1.0))
+            .map(|(_, candidates)| candidates)
+    }
+
+    #[test]
+    fn lexicon_merges_words_and_sorts_by_frequency() {
+        let lexicon = Lexicon::parse("# words\nthe 5\nAnd 2 # comment\n\nto\nand 4\n").unwrap();
+        assert_eq!(
+            lexicon.words,
+            vec![
+                ("and".to_string(), 6.0),
+                ("the".to_string(), 5.0),
+                ("to".to_string(), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexicon_rejects_bad_lines() {
+        assert!(Lexicon::parse("word 0").unwrap_err().contains("line 1"));
+        assert!(Lexicon::parse("ok\nword x").unwrap_err().contains("line 2"));
+        assert!(Lexicon::parse("word 1 2").unwrap_err().contains("line 1"));
+    }
+
+    #[test]
+    fn resample_spaces_points_evenly() {
+        let points = resample(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]], 5);
+        let expected = [[0.0, 0.0], [0.5, 0.0], [1.0, 0.0], [1.0, 0.5], [1.0, 1.0]];
+        assert_eq!(points.len(), expected.len());
+        for (point, expected) in points.iter().zip(expected) {
+            assert!(close(*point, expected), "{:?} != {:?}", point, expected);
+        }
+    }
+
+    #[test]
+    fn resample_repeats_a_single_point() {
+        assert_eq!(resample(&[[0.3, 0.4]], 3), vec![[0.3, 0.4]; 3]);
+        assert_eq!(resample(&[], 2), vec![[0.0, 0.0]; 2]);
+    }
+
+    #[test]
+    fn decode_ranks_the_word_matching_the_trail_first() {
+        let mut layout = row_layout("abcd");
+        let lexicon = shared_lexicon("ad\nab\ncd");
+        let trail = [[0.1, 0.5], [0.25, 0.51], [0.4, 0.5]];
+        let candidates = decode_trail(&trail, &mut layout, &lexicon, &SwipeConfig::default());
+        assert_eq!(candidates[0].word, "ad");
+        // Words not starting near the trail's start are skipped / 始点が離れた単語は除外
+        assert!(candidates.iter().all(|c| c.word != "cd"));
+        let total: f64 = candidates.iter().map(|c| c.score).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decode_returns_nothing_for_empty_trail() {
+        let lexicon = shared_lexicon("ab");
+        let mut layout = row_layout("ab");
+        assert!(decode_trail(&[], &mut layout, &lexicon, &SwipeConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn tap_is_not_decoded_as_swipe() {
+        let mut state = swipe_state("abcd", "a\nab");
+        assert_eq!(swipe(&mut state, &[[0.1, 0.5], [0.12, 0.51]]), None);
+    }
+
+    #[test]
+    fn trail_longer_than_a_key_pitch_is_decoded() {
+        let mut state = swipe_state("abcd", "ad\nab");
+        let candidates = swipe(&mut state, &[[0.1, 0.5], [0.25, 0.5], [0.4, 0.5]]).unwrap();
+        assert_eq!(candidates[0].word, "ad");
+    }
+
+    #[test]
+    fn layout_keeps_single_letter_keys_and_their_pitch() {
+        let layout = SwipeLayout::new([
+            ("A", [0.1, 0.5]),
+            ("b", [0.3, 0.5]),
+            ("c", [0.35, 0.5]),
+            ("Shift", [0.9, 0.9]),
+        ]);
+        assert_eq!(layout.keys.len(), 3);
+        assert!(layout.keys.contains_key(&'a'));
+        assert!((layout.pitch.unwrap() - 0.05).abs() < 1e-9);
+        assert_eq!(SwipeLayout::new([("a", [0.1, 0.5])]).pitch, None);
+    }
+
+    #[test]
+    fn key_paths_are_cached_until_the_lexicon_changes() {
+        let mut layout = row_layout("abcd");
+        let first = shared_lexicon("ad\nab\nax");
+        let paths = layout.key_paths(&first);
+        // Words with a letter missing from the layout are left out / レイアウトに無い文字を含む単語は除外
+        assert_eq!(paths.by_ends.len(), 2);
+        assert_eq!(paths.by_ends[&('a', 'd')][0].points.len(), RESAMPLE_POINTS);
+        let cached = paths as *const KeyPaths;
+        assert!(std::ptr::eq(layout.key_paths(&first), cached));
+
+        let second = shared_lexicon("cd");
+        let paths = layout.key_paths(&second);
+        assert_eq!(paths.by_ends.keys().collect::<Vec<_>>(), vec![&('c', 'd')]);
+    }
+}
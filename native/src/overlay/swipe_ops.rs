@@ -0,0 +1,137 @@
+use napi_derive::napi;
+use std::sync::Arc;
+
+use super::handles::overlay_handle;
+use super::manager::OverlayManager;
+use super::swipe::{Lexicon, SwipeCandidate, SwipeLayout, SwipeTrail};
+use super::types::{SwipeKey, SwipeResult, SwipeSettings, SwipeWord};
+
+pub(super) fn swipe_result(
+    controller_index: u32,
+    trail: SwipeTrail,
+    candidates: Vec<SwipeCandidate>,
+) -> SwipeResult {
+    SwipeResult {
+        controllerIndex: controller_index,
+        overlayHandle: trail.overlay as i64,
+        startedAt: trail.started_at,
+        endedAt: trail.ended_at,
+        pointCount: trail.points.len() as u32,
+        candidates: candidates
+            .into_iter()
+            .map(|candidate| SwipeWord {
+                word: candidate.word,
+                score: candidate.score,
+            })
+            .collect(),
+    }
+}
+
+fn key_center(key: &SwipeKey) -> napi::Result<[f64; 2]> {
+    let bounds = [key.u, key.v, key.width, key.height];
+    if bounds.iter().any(|value| !value.is_finite()) || key.width < 0.0 || key.height < 0.0 {
+        return Err(napi::Error::from_reason(format!(
+            "Swipe key {} must have finite bounds and a non-negative size",
+            key.label
+        )));
+    }
+    Ok([key.u + key.width / 2.0, key.v + key.height / 2.0])
+}
+
+fn non_negative(name: &str, value: Option<f64>, current: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(napi::Error::from_reason(format!(
+            "{name} must be a non-negative number"
+        ))),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update swipe typing settings; omitted fields keep their value.
+    /// スワイプ入力の設定を更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_swipe(&self, settings: SwipeSettings) -> napi::Result<()> {
+        let mut swipe = self.lock_swipe()?;
+        let mut config = swipe.config;
+        if let Some(enabled) = settings.enabled {
+            config.enabled = enabled;
+        }
+        config.min_sample_distance = non_negative(
+            "minSampleDistance",
+            settings.minSampleDistance,
+            config.min_sample_distance,
+        )?;
+        config.tolerance = non_negative("tolerance", settings.tolerance, config.tolerance)?;
+        if config.tolerance <= 0.0 {
+            return Err(napi::Error::from_reason("tolerance must be positive"));
+        }
+        if let Some(max_candidates) = settings.maxCandidates {
+            config.max_candidates = max_candidates as usize;
+        }
+        swipe.config = config;
+        // Trails recorded under the old settings are dropped / 以前の設定で記録中の軌跡は破棄
+        swipe.recorder.clear();
+        Ok(())
+    }
+
+    /// Set the keys of a keyboard overlay in UV space; an empty list stops swiping on it.
+    /// Only keys with a single-character label are used for decoding.
+    /// キーボードオーバーレイのキーを UV 空間で設定する (空のリストでスワイプを無効化)。
+    /// 復号には 1 文字のラベルのキーだけを使う。
+    #[napi]
+    pub fn set_swipe_layout(&self, handle: i64, keys: Vec<SwipeKey>) -> napi::Result<()> {
+        let handle = overlay_handle(handle)?.as_u64();
+        let centers = keys
+            .iter()
+            .map(key_center)
+            .collect::<napi::Result<Vec<_>>>()?;
+        let layout = SwipeLayout::new(
+            keys.iter()
+                .zip(centers)
+                .map(|(key, center)| (key.label.as_str(), center)),
+        );
+        let mut swipe = self.lock_swipe()?;
+        if layout.is_empty() {
+            swipe.layouts.remove(&handle);
+        } else {
+            swipe.layouts.insert(handle, layout);
+        }
+        Ok(())
+    }
+
+    /// Load the word list from a plain-text file of `word [frequency]` lines.
+    /// Returns the number of distinct words.
+    /// `単語 [頻度]` の行からなるテキストファイルから単語リストを読み込む。
+    /// 異なる単語の数を返す。
+    #[napi]
+    pub fn load_swipe_lexicon(&self, path: String) -> napi::Result<u32> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| napi::Error::from_reason(format!("Failed to read {}: {}", path, e)))?;
+        let lexicon = Lexicon::parse(&text)
+            .map_err(|e| napi::Error::from_reason(format!("Failed to parse {}: {}", path, e)))?;
+        let count = lexicon.words.len() as u32;
+        self.lock_swipe()?.lexicon = Arc::new(lexicon);
+        Ok(count)
+    }
+
+    /// Swipes finished since the last call, with ranked candidate words.
+    /// Trails shorter than one key pitch are taps and are not reported.
+    /// Trails are fed by compute_controller_intersection and poll_trigger_events;
+    /// while the input thread runs they are delivered as "swipe" events instead.
+    /// 前回の呼び出し以降に終了したスワイプと、順位付けした候補の単語。
+    /// キー 1 つ分の間隔より短い軌跡はタップとみなし、報告しない。
+    /// 軌跡は compute_controller_intersection と poll_trigger_events から入力され、
+    /// 入力スレッドの実行中は代わりに "swipe" イベントとして届く。
+    #[napi]
+    pub fn poll_swipe_results(&self) -> napi::Result<Vec<SwipeResult>> {
+        Ok(self
+            .lock_swipe()?
+            .finished
+            .drain(..)
+            .map(|(index, trail, candidates)| swipe_result(index, trail, candidates))
+            .collect())
+    }
+}
@@ -1,15 +1,267 @@
-use napi::bindgen_prelude::Buffer;
+use napi::bindgen_prelude::{AsyncTask, Buffer};
 use napi_derive::napi;
 use openvr_sys as vr;
 use std::ffi::{c_char, CString};
 
-use windows::core::Interface;
-
-use super::buffers::{expected_rgba_size, row_pitch_bytes};
-use super::constants::BYTES_PER_PIXEL;
+use super::buffers::row_pitch_bytes;
+use super::constants::{BYTES_PER_PIXEL, MAX_OUTPUT_TEXTURES};
+use super::dirty::{FrameDiff, FrameHistory};
 use super::errors::{overlay_error, overlay_error_message, require_fn};
-use super::handles::overlay_handle;
+use super::handles::{overlay_handle, OverlayHandle};
 use super::manager::OverlayManager;
+use super::pixels::{
+    convert_region, convert_to_rgba, AlphaConversion, AlphaMode, ColorSpace, PixelFormat,
+    SourceLayout, TextureOutput,
+};
+use super::texture_pipeline::FrameUpload;
+use super::texture_stats::RollingDurations;
+use super::types::{
+    DurationStats, PixelRect, TextureDescriptor, TextureOutputSettings, TextureStats, TextureUpdate,
+};
+
+pub(super) fn set_raw_rgba(
+    overlay: &vr::VR_IVROverlay_FnTable,
+    handle: OverlayHandle,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+) -> napi::Result<()> {
+    let set_raw_fn = require_fn(overlay.SetOverlayRaw, "SetOverlayRaw")?;
+    let err = unsafe {
+        set_raw_fn(
+            handle.as_u64(),
+            pixels.as_ptr() as *mut std::ffi::c_void,
+            width,
+            height,
+            BYTES_PER_PIXEL,
+        )
+    };
+    if err != vr::EVROverlayError_VROverlayError_None {
+        return Err(overlay_error("SetOverlayRaw", overlay, err));
+    }
+    Ok(())
+}
+
+/// Pixels to upload: the descriptor's source rectangle within a buffer, already validated
+/// アップロードするピクセル: バッファ内の記述子のソース矩形 (検証済み)
+pub(super) struct TextureSource {
+    /// Byte offset of the rectangle's first pixel / 矩形の最初のピクセルのバイトオフセット
+    pub offset: usize,
+    /// Layout of the rectangle alone / 矩形のみのレイアウト
+    pub layout: SourceLayout,
+    pub alpha: AlphaConversion,
+}
+
+impl TextureSource {
+    pub(super) fn pixels<'a>(&self, buffer: &'a [u8]) -> &'a [u8] {
+        &buffer[self.offset..]
+    }
+}
+
+/// Validate `descriptor` against a `width` x `height` image in `len` bytes and resolve the
+/// rectangle to upload; `default_format` applies when the descriptor names none.
+/// `len` バイトの `width` x `height` の画像に対して `descriptor` を検証し、アップロードする矩形を求める
+/// (形式の指定が無ければ `default_format`)
+pub(super) fn texture_source(
+    len: usize,
+    width: u32,
+    height: u32,
+    descriptor: Option<TextureDescriptor>,
+    default_format: PixelFormat,
+    output: TextureOutput,
+) -> napi::Result<TextureSource> {
+    let descriptor = descriptor.unwrap_or_default();
+    let format = match descriptor.format.as_deref() {
+        Some(name) => PixelFormat::from_name(name)
+            .ok_or_else(|| napi::Error::from_reason(format!("Unknown pixel format: {}", name)))?,
+        None => default_format,
+    };
+    let alpha = match descriptor.alpha.as_deref() {
+        Some(name) => AlphaMode::from_name(name)
+            .ok_or_else(|| napi::Error::from_reason(format!("Unknown alpha mode: {}", name)))?,
+        None => AlphaMode::Straight,
+    };
+    let row_pitch = match descriptor.rowPitch {
+        Some(pitch) => pitch as usize,
+        None => row_pitch_bytes(width)?,
+    };
+    let image = SourceLayout {
+        width: width as usize,
+        height: height as usize,
+        row_pitch,
+        format,
+    };
+    image.validate(len).map_err(napi::Error::from_reason)?;
+
+    let rect = descriptor.sourceRect.unwrap_or(PixelRect {
+        x: 0,
+        y: 0,
+        width,
+        height,
+    });
+    let inside = |start: u32, size: u32, limit: u32| {
+        size > 0 && start.checked_add(size).is_some_and(|end| end <= limit)
+    };
+    if !inside(rect.x, rect.width, width) || !inside(rect.y, rect.height, height) {
+        return Err(napi::Error::from_reason(format!(
+            "sourceRect {}x{}+{}+{} is empty or outside the {}x{} image",
+            rect.width, rect.height, rect.x, rect.y, width, height
+        )));
+    }
+
+    Ok(TextureSource {
+        offset: rect.y as usize * row_pitch + rect.x as usize * BYTES_PER_PIXEL as usize,
+        layout: SourceLayout {
+            width: rect.width as usize,
+            height: rect.height as usize,
+            ..image
+        },
+        alpha: AlphaConversion {
+            mode: alpha.for_source(format, descriptor.premultiplied.unwrap_or(false)),
+            srgb: output.srgb,
+        },
+    })
+}
+
+/// Convert the dirty regions of `pixels` into the frame's kept RGBA copy.
+/// Everything is converted when the kept copy does not match the frame size.
+/// `pixels` の変更領域をフレームの保持している RGBA コピーに変換する。
+/// 保持コピーがフレームサイズと合わなければ全体を変換する。
+pub(super) fn stage_regions(
+    frame: &mut FrameHistory,
+    pixels: &[u8],
+    source: &TextureSource,
+    diff: FrameDiff,
+) -> FrameDiff {
+    let layout = &source.layout;
+    let frame_bytes = layout.row_bytes() * layout.height;
+    let diff = if frame.converted.len() == frame_bytes {
+        diff
+    } else {
+        frame.converted.resize(frame_bytes, 0);
+        FrameDiff::full(layout.width, layout.height)
+    };
+    for rect in &diff.rects {
+        convert_region(
+            pixels,
+            layout,
+            source.alpha,
+            [rect.x, rect.y, rect.width, rect.height],
+            &mut frame.converted,
+            PixelFormat::Rgba8,
+        );
+    }
+    diff
+}
+
+fn duration_stats(durations: &RollingDurations) -> DurationStats {
+    let [p50, p95, p99, max] = durations.percentiles([50.0, 95.0, 99.0, 100.0]);
+    DurationStats {
+        samples: durations.count() as u32,
+        p50,
+        p95,
+        p99,
+        max,
+    }
+}
+
+pub(super) fn texture_update(diff: &FrameDiff, submitted: bool) -> TextureUpdate {
+    TextureUpdate {
+        submitted,
+        dirtyRatio: diff.dirty_ratio(),
+        dirtyRects: diff.rects.len() as u32,
+    }
+}
+
+impl OverlayManager {
+    /// Upload through SetOverlayRaw, converting on the CPU unless `layout` is already packed RGBA.
+    /// SetOverlayRaw でアップロードする (`layout` が詰めた RGBA でなければ CPU で変換)
+    pub(super) fn upload_raw_pixels(
+        &self,
+        handles: &[OverlayHandle],
+        src: &[u8],
+        layout: SourceLayout,
+        alpha: AlphaConversion,
+    ) -> napi::Result<()> {
+        let width = u32::try_from(layout.width)
+            .map_err(|_| napi::Error::from_reason("width is too large"))?;
+        let height = u32::try_from(layout.height)
+            .map_err(|_| napi::Error::from_reason("height is too large"))?;
+
+        if layout.is_packed_rgba() && alpha.mode == AlphaMode::Straight {
+            return self
+                .lock_pipeline()?
+                .upload_raw(handles, src, width, height);
+        }
+
+        let mut converted = self.borrow_pixel_scratch_mut()?;
+        convert_to_rgba(src, &layout, alpha, &mut converted).map_err(napi::Error::from_reason)?;
+        self.lock_pipeline()?
+            .upload_raw(handles, &converted, width, height)
+    }
+
+    /// Drop queued frames for `handle` and make its next frame a full upload, as another
+    /// path has replaced its texture.
+    /// 別の経路でテクスチャが置き換わったため、`handle` の待機中のフレームを破棄し、
+    /// 次のフレームを全体のアップロードにする。
+    fn replace_overlay_frames(&self, handle: OverlayHandle) -> napi::Result<()> {
+        self.upload_queue().enqueue(handle.as_u64());
+        self.lock_pipeline()?.forget_overlay(handle.as_u64());
+        Ok(())
+    }
+
+    /// setOverlayRaw for a buffer described by `descriptor` / `descriptor` で記述したバッファの setOverlayRaw
+    pub(super) fn set_raw_frame(
+        &self,
+        handle: OverlayHandle,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        descriptor: Option<TextureDescriptor>,
+    ) -> napi::Result<()> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let output = self.lock_pipeline()?.output;
+        let source = texture_source(
+            buffer.len(),
+            width,
+            height,
+            descriptor,
+            PixelFormat::Rgba8,
+            output,
+        )?;
+        // The next set_overlay_textures_d3d11 frame must not be skipped as unchanged
+        // 次の set_overlay_textures_d3d11 のフレームを変化無しとして省略させない
+        self.replace_overlay_frames(handle)?;
+        self.upload_raw_pixels(
+            &[handle],
+            source.pixels(buffer),
+            source.layout,
+            source.alpha,
+        )
+    }
+
+    pub(super) fn texture_handles(
+        &self,
+        front_handle: i64,
+        back_handle: i64,
+    ) -> napi::Result<Vec<OverlayHandle>> {
+        let front_handle = overlay_handle(front_handle)?;
+        let mut handles = vec![front_handle];
+        let back_handle = overlay_handle(back_handle)?;
+        if back_handle.as_u64() != vr::k_ulOverlayHandleInvalid {
+            handles.push(back_handle);
+        }
+        // Overlays showing a region of the front texture / 前面テクスチャの一部を表示するオーバーレイ
+        for member in self.borrow_atlas_mut()?.members(front_handle.as_u64()) {
+            if handles.iter().all(|handle| handle.as_u64() != *member) {
+                handles.push(OverlayHandle::from_u64(*member));
+            }
+        }
+        Ok(handles)
+    }
+}
 
 #[napi]
 impl OverlayManager {
@@ -35,9 +287,14 @@ impl OverlayManager {
                 )));
             }
         }
-        Ok(())
+        self.replace_overlay_frames(handle)
     }
 
+    /// Upload pixels with SetOverlayRaw. `descriptor` describes the buffer (format, row pitch,
+    /// premultiplied alpha) and the rectangle to upload; formats other than packed RGBA8 are
+    /// converted on the CPU.
+    /// SetOverlayRaw でピクセルをアップロードする。`descriptor` はバッファ (形式、行ピッチ、
+    /// 乗算済みアルファ) とアップロードする矩形を表す。詰めた RGBA8 以外は CPU で変換する。
     #[napi]
     pub fn set_overlay_raw(
         &self,
@@ -45,129 +302,138 @@ impl OverlayManager {
         buffer: Buffer,
         width: u32,
         height: u32,
+        descriptor: Option<TextureDescriptor>,
     ) -> napi::Result<()> {
-        // NOTE: This method expects RGBA-ordered buffer. If the source is BGRA
-        // (e.g. Electron capturePage().toBitmap() on Windows), colors will be
-        // inverted. Use set_overlay_textures_d3d11() for automatic conversion.
-        // 注意: このメソッドはRGBA順序のバッファを期待する。ソースがBGRAの場合
-        // (例: WindowsのElectron capturePage().toBitmap())、色が反転する。
-        // 自動変換にはset_overlay_textures_d3d11()を使用すること。
-        if width == 0 || height == 0 {
-            return Ok(());
-        }
-        let overlay = self.overlay()?;
-        let set_raw_fn = require_fn(overlay.SetOverlayRaw, "SetOverlayRaw")?;
-        let handle = overlay_handle(handle)?;
-        unsafe {
-            let expected_size = expected_rgba_size(width, height)?;
-
-            if buffer.len() != expected_size {
-                return Err(napi::Error::from_reason(format!(
-                    "Buffer size mismatch: expected {} bytes ({}x{}x4), got {} bytes",
-                    expected_size,
-                    width,
-                    height,
-                    buffer.len()
-                )));
-            }
-
-            let err = set_raw_fn(
-                handle.as_u64(),
-                buffer.as_ptr() as *mut std::ffi::c_void,
-                width,
-                height,
-                BYTES_PER_PIXEL,
-            );
-
-            if err != vr::EVROverlayError_VROverlayError_None {
-                return Err(overlay_error("SetOverlayRaw", overlay, err));
-            }
-        }
-        Ok(())
+        self.set_raw_frame(overlay_handle(handle)?, &buffer, width, height, descriptor)
     }
 
+    /// Upload a frame to the front (and back) overlay through the D3D11 conversion pass,
+    /// or on the CPU when D3D11 is unavailable. `descriptor` is as for setOverlayRaw, except
+    /// that the format defaults to BGRA8. Only tiles that changed since the previous frame
+    /// are uploaded, and nothing is submitted when the frame is unchanged. Overlays registered
+    /// with registerAtlasOverlay on the front overlay get the same texture.
+    /// フレームを D3D11 の変換パス (D3D11 が使えない場合は CPU) で前面 (と背面) の
+    /// オーバーレイにアップロードする。`descriptor` は setOverlayRaw と同じだが、形式の既定は BGRA8。
+    /// 前フレームから変わったタイルだけをアップロードし、変化が無ければ何も送信しない。
+    /// registerAtlasOverlay で前面オーバーレイに登録したオーバーレイにも同じテクスチャを設定する。
     #[napi]
     pub fn set_overlay_textures_d3d11(
-        &mut self,
+        &self,
         front_handle: i64,
         back_handle: i64,
         buffer: Buffer,
         width: u32,
         height: u32,
-    ) -> napi::Result<()> {
+        descriptor: Option<TextureDescriptor>,
+    ) -> napi::Result<TextureUpdate> {
         // Set overlay texture using D3D11 shared texture / D3D11共有テクスチャを使用してオーバーレイテクスチャを設定
         // This bypasses file I/O completely and uses GPU memory / ファイルI/Oを完全にバイパスし、GPUメモリを使用
-        if width == 0 || height == 0 {
-            return Ok(());
-        }
-        let overlay_ptr = self.overlay_ptr()?;
-        let set_texture_fn = {
-            let overlay = unsafe { overlay_ptr.as_ref() };
-            require_fn(overlay.SetOverlayTexture, "SetOverlayTexture")?
+        let handles = self.texture_handles(front_handle, back_handle)?;
+        // Queued frames are older than this one / 待機中のフレームはこれより古い
+        self.upload_queue().enqueue(handles[0].as_u64());
+        self.lock_pipeline()?
+            .submit_frame(&handles, &buffer, width, height, descriptor)
+    }
+
+    /// Queue a frame for setOverlayTexturesD3D11's conversion and submission on a worker
+    /// thread. A frame still waiting when a newer one for the same front overlay is queued
+    /// is dropped; the promise reports which happened and the running totals.
+    /// The buffer must not be modified until the promise settles.
+    /// setOverlayTexturesD3D11 と同じ変換と送信をワーカースレッドで行うようにフレームを積む。
+    /// 同じ前面オーバーレイに新しいフレームが積まれた時点で待機中のフレームは破棄する。
+    /// Promise はどちらになったかと累計を返す。Promise が確定するまでバッファを変更しないこと。
+    #[napi]
+    pub fn submit_overlay_frame(
+        &self,
+        front_handle: i64,
+        back_handle: i64,
+        buffer: Buffer,
+        width: u32,
+        height: u32,
+        descriptor: Option<TextureDescriptor>,
+    ) -> napi::Result<AsyncTask<FrameUpload>> {
+        let handles = self.texture_handles(front_handle, back_handle)?;
+        let sequence = self.upload_queue().enqueue(handles[0].as_u64());
+        Ok(AsyncTask::new(FrameUpload {
+            pipeline: self.texture_pipeline(),
+            queue: self.upload_queue().clone(),
+            handles,
+            sequence,
+            buffer,
+            width,
+            height,
+            descriptor,
+        }))
+    }
+
+    /// Update how textures are submitted; omitted fields keep their value. `colorSpace` only
+    /// applies to the D3D11 path, as SetOverlayRaw takes no color space. With `srgb`, the D3D11
+    /// path uses `_SRGB` formats and both paths apply alpha in linear light. `outputTextures`
+    /// sets how many output textures the D3D11 path rotates through. Each front overlay keeps
+    /// showing its last one, and a texture whose previous pass is still running on the GPU is
+    /// skipped; a frame finding none ready is dropped and counted in `busyFrames`.
+    /// テクスチャの送信方法を更新 (省略した項目は現在値を維持)。SetOverlayRaw は色空間を受け取らないため、
+    /// `colorSpace` は D3D11 パスにのみ適用される。`srgb` では D3D11 パスが `_SRGB` 形式を使い、
+    /// 両パスとも線形光でアルファを適用する。`outputTextures` は D3D11 パスが順に使う出力テクスチャの枚数。
+    /// 各前面オーバーレイは最後の出力を表示し続け、前回のパスが GPU で実行中の出力は飛ばす。
+    /// 使える出力が無いフレームは破棄し、`busyFrames` に数える。
+    #[napi]
+    pub fn configure_texture_output(&self, settings: TextureOutputSettings) -> napi::Result<()> {
+        let color_space = match settings.colorSpace.as_deref() {
+            Some(name) => Some(ColorSpace::from_name(name).ok_or_else(|| {
+                napi::Error::from_reason(format!("Unknown color space: {}", name))
+            })?),
+            None => None,
         };
-        let front_handle = overlay_handle(front_handle)?;
-        let back_handle = overlay_handle(back_handle)?;
-        let d3d11 = self
-            .d3d11_mut()
-            .ok_or_else(|| napi::Error::from_reason("D3D11 context not initialized"))?;
-        unsafe {
-            // Buffer is typically BGRA on Windows from Electron capturePage().toBitmap()
-            // WindowsのElectron capturePage().toBitmap()は通常BGRA
-            let expected_size = expected_rgba_size(width, height)?;
-            if buffer.len() != expected_size {
+        if let Some(count) = settings.outputTextures {
+            if !(1..=MAX_OUTPUT_TEXTURES).contains(&count) {
                 return Err(napi::Error::from_reason(format!(
-                    "Buffer size mismatch: expected {} bytes ({}x{}x4), got {} bytes",
-                    expected_size,
-                    width,
-                    height,
-                    buffer.len()
+                    "outputTextures must be between 1 and {}",
+                    MAX_OUTPUT_TEXTURES
                 )));
             }
+        }
 
-            let row_pitch = row_pitch_bytes(width)?;
-
-            // Recreate texture/pipeline resources if size changed / サイズが変わった場合はリソースを再作成
-            d3d11.ensure_resources(width, height)?;
-            let texture = d3d11
-                .output_texture()
-                .ok_or_else(|| napi::Error::from_reason("D3D11 output texture not available"))?
-                .clone();
-
-            // Upload BGRA bytes and run GPU conversion pass
-            d3d11.upload_bgra_buffer(buffer.as_ptr(), buffer.len(), row_pitch, width, height)?;
-            d3d11.convert_bgra_to_rgba(width, height)?;
-
-            // Set overlay texture using SetOverlayTexture / SetOverlayTextureを使用してオーバーレイテクスチャを設定
-            // Get raw pointer for OpenVR / OpenVR用の生ポインタを取得
-            let texture_ptr = texture.as_raw();
-
-            let mut vr_texture = vr::Texture_t {
-                handle: texture_ptr,
-                eType: vr::ETextureType_TextureType_DirectX,
-                eColorSpace: vr::EColorSpace_ColorSpace_Auto,
-            };
-
-            let err = set_texture_fn(front_handle.as_u64(), &mut vr_texture);
-
-            if err != vr::EVROverlayError_VROverlayError_None {
-                return Err(overlay_error(
-                    "SetOverlayTexture",
-                    overlay_ptr.as_ref(),
-                    err,
-                ));
-            }
-
-            if back_handle.as_u64() != vr::k_ulOverlayHandleInvalid {
-                let err = set_texture_fn(back_handle.as_u64(), &mut vr_texture);
-                if err != vr::EVROverlayError_VROverlayError_None {
-                    return Err(overlay_error(
-                        "SetOverlayTexture(back)",
-                        overlay_ptr.as_ref(),
-                        err,
-                    ));
-                }
-            }
+        let mut pipeline = self.lock_pipeline()?;
+        if let Some(color_space) = color_space {
+            pipeline.output.color_space = color_space;
+        }
+        if let Some(srgb) = settings.srgb {
+            pipeline.output.srgb = srgb;
+        }
+        if let Some(count) = settings.outputTextures {
+            pipeline.output_textures = count as usize;
         }
+        // Unchanged frames must still be resubmitted with the new settings
+        // 変化の無いフレームも新しい設定で送り直す
+        pipeline.frames.clear();
         Ok(())
     }
+
+    /// Rolling timings (over the last 240 frames) and counters of texture submission, for
+    /// diagnosing CPU and GPU cost.
+    /// テクスチャ送信の直近 240 フレームの所要時間とカウンター (CPU・GPU 負荷の診断用)
+    #[napi]
+    pub fn get_texture_stats(&self) -> napi::Result<TextureStats> {
+        let pipeline = self.lock_pipeline()?;
+        let stats = &pipeline.stats;
+        let d3d11 = pipeline.d3d11.as_ref();
+        Ok(TextureStats {
+            d3d11: d3d11.is_some(),
+            swapRedBlue: d3d11
+                .filter(|ctx| ctx.channel_probe_done)
+                .map(|ctx| ctx.swap_rb_required),
+            outputTextures: pipeline.output_textures as u32,
+            frame: duration_stats(&stats.frame),
+            upload: duration_stats(&stats.upload),
+            convert: duration_stats(&stats.convert),
+            submit: duration_stats(&stats.submit),
+            bytesUploaded: stats.bytes_uploaded as f64,
+            submittedFrames: stats.submitted_frames,
+            skippedFrames: stats.skipped_frames,
+            busyFrames: stats.busy_frames,
+            droppedFrames: self.upload_queue().dropped_frames(),
+            resourceRecreations: stats.recreations,
+        })
+    }
 }
@@ -0,0 +1,520 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Env, Task};
+use openvr_sys as vr;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use windows::core::Interface;
+
+use super::constants::{BYTES_PER_PIXEL, DEFAULT_OUTPUT_TEXTURES};
+use super::d3d11::D3D11Context;
+use super::dirty::{FrameDiff, FrameHistory};
+use super::errors::{overlay_error, require_fn};
+use super::handles::OverlayHandle;
+use super::pixels::{ColorSpace, PixelFormat, TextureOutput};
+use super::snapshot::{write_png, FrameCapture, FramePixels, LastFrame};
+use super::texture_ops::{
+    set_raw_rgba, stage_regions, texture_source, texture_update, TextureSource,
+};
+use super::texture_stats::PipelineStats;
+use super::types::{FrameSubmission, TextureDescriptor, TextureUpdate};
+
+/// Texture upload state shared by the JS thread and asynchronous upload workers.
+/// JS スレッドと非同期アップロードのワーカーで共有するテクスチャアップロードの状態
+pub(super) struct TexturePipeline {
+    /// Cleared by OverlayManager's Drop before VR shutdown / VR シャットダウン前に OverlayManager の Drop でクリア
+    overlay: Option<NonNull<vr::VR_IVROverlay_FnTable>>,
+    pub d3d11: Option<D3D11Context>,
+    /// Previous frame per front overlay handle / 前面オーバーレイハンドルごとの直前のフレーム
+    pub frames: HashMap<u64, FrameHistory>,
+    pub output: TextureOutput,
+    /// Size of the D3D11 output texture ring / D3D11 の出力テクスチャリングの枚数
+    pub output_textures: usize,
+    pub stats: PipelineStats,
+    /// For saveLastFrame / saveLastFrame 用
+    pub last_frame: Option<LastFrame>,
+    pub capture: Option<FrameCapture>,
+}
+
+// SAFETY: the overlay function table is process-wide and the D3D11 device is free-threaded;
+// the immediate context is only used while holding the pipeline lock. OverlayManager clears
+// both under the lock in Drop before shutting VR down.
+// オーバーレイ関数テーブルはプロセス全体で共有され、D3D11 デバイスはフリースレッド。
+// イミディエイトコンテキストはパイプラインのロック中にのみ使う。OverlayManager は Drop で
+// VR をシャットダウンする前に、ロック中に両方をクリアする。
+unsafe impl Send for TexturePipeline {}
+
+impl TexturePipeline {
+    pub(super) fn new(
+        overlay: NonNull<vr::VR_IVROverlay_FnTable>,
+        d3d11: Option<D3D11Context>,
+    ) -> Self {
+        Self {
+            overlay: Some(overlay),
+            d3d11,
+            frames: HashMap::new(),
+            output: TextureOutput::default(),
+            output_textures: DEFAULT_OUTPUT_TEXTURES as usize,
+            stats: PipelineStats::default(),
+            last_frame: None,
+            capture: None,
+        }
+    }
+
+    /// Release GPU resources and stop later uploads from reaching VR.
+    /// GPU リソースを解放し、以降のアップロードが VR に届かないようにする
+    pub(super) fn close(&mut self) {
+        self.overlay = None;
+        self.d3d11 = None;
+        self.frames.clear();
+        self.last_frame = None;
+        self.capture = None;
+    }
+
+    /// Drop what is kept for `front`, as its texture was replaced or it was destroyed; its
+    /// next frame is a full upload and its output texture is free for other overlays.
+    /// テクスチャの置き換えや破棄に伴い `front` について保持しているものを捨てる。
+    /// 次のフレームは全体のアップロードになり、出力テクスチャは他のオーバーレイが使えるようになる。
+    pub(super) fn forget_overlay(&mut self, front: u64) {
+        self.frames.remove(&front);
+        if let Some(d3d11) = self.d3d11.as_mut() {
+            d3d11.forget_output(front);
+        }
+    }
+
+    fn overlay_ptr(&self) -> napi::Result<NonNull<vr::VR_IVROverlay_FnTable>> {
+        self.overlay
+            .ok_or_else(|| napi::Error::from_reason("Overlay interface is null"))
+    }
+
+    /// Convert and submit a frame to `handles` (front first) through the D3D11 conversion
+    /// pass, or on the CPU when D3D11 is unavailable. Only tiles that changed since the
+    /// previous frame are uploaded, and nothing is submitted when the frame is unchanged.
+    /// フレームを変換し、D3D11 の変換パス (使えない場合は CPU) で `handles` (先頭が前面) に送信する。
+    /// 前フレームから変わったタイルだけをアップロードし、変化が無ければ何も送信しない。
+    pub(super) fn submit_frame(
+        &mut self,
+        handles: &[OverlayHandle],
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        descriptor: Option<TextureDescriptor>,
+    ) -> napi::Result<TextureUpdate> {
+        let started = Instant::now();
+        let update = self.convert_and_submit(handles, buffer, width, height, descriptor)?;
+        self.stats.frame.record(started.elapsed());
+        if update.submitted {
+            self.stats.submitted_frames += 1;
+            self.capture_frame();
+        } else if update.dirtyRects > 0 {
+            // Changed, but every output texture was still being drawn / 変化したが出力テクスチャがすべて描画中
+            self.stats.busy_frames += 1;
+        } else {
+            self.stats.skipped_frames += 1;
+        }
+        Ok(update)
+    }
+
+    fn convert_and_submit(
+        &mut self,
+        handles: &[OverlayHandle],
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        descriptor: Option<TextureDescriptor>,
+    ) -> napi::Result<TextureUpdate> {
+        if width == 0 || height == 0 {
+            return Ok(texture_update(&FrameDiff::default(), false));
+        }
+        // Buffer is typically BGRA on Windows from Electron capturePage().toBitmap()
+        // WindowsのElectron capturePage().toBitmap()は通常BGRA
+        let output = self.output;
+        let source = texture_source(
+            buffer.len(),
+            width,
+            height,
+            descriptor,
+            PixelFormat::Bgra8,
+            output,
+        )?;
+        let pixels = source.pixels(buffer);
+        let layout = source.layout;
+        let front = handles[0].as_u64();
+        let diff = {
+            let frame = self.frames.entry(front).or_default();
+            // A different conversion changes every pixel / 変換が変われば全ピクセルが変わる
+            let conversion = (layout.format, source.alpha);
+            if frame.conversion != Some(conversion) {
+                frame.invalidate();
+                frame.conversion = Some(conversion);
+            }
+            frame.diff(pixels, layout.width, layout.height, layout.row_pitch)
+        };
+
+        let Some(d3d11) = self.d3d11.as_ref() else {
+            // No GPU path: swizzle on the CPU and upload with SetOverlayRaw
+            // GPU パスが無い場合は CPU で並べ替えて SetOverlayRaw でアップロード
+            return self.upload_frame_cpu(handles, pixels, &source, diff);
+        };
+        // The input texture only holds this overlay's previous frame if it was the last upload
+        // 入力テクスチャがこのオーバーレイの前フレームを保持しているのは直前のアップロードの場合のみ
+        let diff = if d3d11.content_owner == Some(front) {
+            diff
+        } else {
+            FrameDiff::full(layout.width, layout.height)
+        };
+        if diff.is_empty() {
+            return Ok(texture_update(&diff, false));
+        }
+        let submitted = self.submit_d3d11(handles, pixels, &source, &diff, output)?;
+        Ok(texture_update(&diff, submitted))
+    }
+
+    /// CPU path of submit_frame: convert only the dirty regions into the overlay's kept
+    /// RGBA frame, then submit it with SetOverlayRaw.
+    /// submit_frame の CPU パス: 変更領域だけを保持している RGBA フレームに変換し、
+    /// SetOverlayRaw で送信する。
+    fn upload_frame_cpu(
+        &mut self,
+        handles: &[OverlayHandle],
+        pixels: &[u8],
+        source: &TextureSource,
+        diff: FrameDiff,
+    ) -> napi::Result<TextureUpdate> {
+        let overlay = unsafe { self.overlay_ptr()?.as_ref() };
+        let frame = self.frames.entry(handles[0].as_u64()).or_default();
+        let started = Instant::now();
+        let diff = stage_regions(frame, pixels, source, diff);
+        if diff.is_empty() {
+            return Ok(texture_update(&diff, false));
+        }
+        self.stats.convert.record(started.elapsed());
+
+        let started = Instant::now();
+        for handle in handles {
+            if let Err(e) = set_raw_rgba(
+                overlay,
+                *handle,
+                &frame.converted,
+                source.layout.width as u32,
+                source.layout.height as u32,
+            ) {
+                // Resubmit everything next time / 次回は全体を送り直す
+                frame.invalidate();
+                return Err(e);
+            }
+        }
+        self.stats.submit.record(started.elapsed());
+        // SetOverlayRaw always sends the whole frame / SetOverlayRaw は常にフレーム全体を送る
+        self.stats.bytes_uploaded += (frame.converted.len() * handles.len()) as u64;
+        self.last_frame = Some(LastFrame {
+            width: source.layout.width as u32,
+            height: source.layout.height as u32,
+            pixels: FramePixels::History(handles[0].as_u64()),
+        });
+        Ok(texture_update(&diff, true))
+    }
+
+    /// GPU path of submit_frame: copy the dirty regions of the frame into the input
+    /// texture, convert format and alpha in the shader and set the output on every handle.
+    /// Returns false when every output texture was still being drawn and the frame was dropped.
+    /// submit_frame の GPU パス: フレームの変更領域を入力テクスチャにコピーし、
+    /// 形式とアルファをシェーダで変換して出力を全ハンドルに設定する。
+    /// すべての出力テクスチャが描画中でフレームを破棄した場合は false を返す。
+    fn submit_d3d11(
+        &mut self,
+        handles: &[OverlayHandle],
+        pixels: &[u8],
+        source: &TextureSource,
+        diff: &FrameDiff,
+        output: TextureOutput,
+    ) -> napi::Result<bool> {
+        let layout = source.layout;
+        let (width, height) = (layout.width as u32, layout.height as u32);
+        let overlay_ptr = self.overlay_ptr()?;
+        let set_texture_fn = {
+            let overlay = unsafe { overlay_ptr.as_ref() };
+            require_fn(overlay.SetOverlayTexture, "SetOverlayTexture")?
+        };
+        let d3d11 = self
+            .d3d11
+            .as_mut()
+            .ok_or_else(|| napi::Error::from_reason("D3D11 context not initialized"))?;
+        let stats = &mut self.stats;
+
+        // Recreate texture/pipeline resources if size changed / サイズが変わった場合はリソースを再作成
+        if d3d11.ensure_resources(width, height, output.srgb, self.output_textures)? {
+            stats.recreations += 1;
+        }
+        let front = handles[0].as_u64();
+        let Some(output_index) = d3d11.acquire_output(front)? else {
+            // The next frame resends everything, as this one never reaches SteamVR
+            // このフレームは SteamVR に届かないため、次のフレームで全体を送り直す
+            if let Some(frame) = self.frames.get_mut(&front) {
+                frame.invalidate();
+            }
+            return Ok(false);
+        };
+
+        // Upload changed regions and run GPU conversion pass into the next ring texture
+        // 変更された領域をアップロードし、リングの次のテクスチャへ GPU 変換パスを実行
+        d3d11.content_owner = None;
+        let started = Instant::now();
+        for rect in &diff.rects {
+            d3d11.upload_bgra_region(
+                pixels,
+                layout.row_pitch,
+                [rect.x, rect.y, rect.width, rect.height].map(|v| v as u32),
+            )?;
+            stats.bytes_uploaded += (rect.width * rect.height) as u64 * BYTES_PER_PIXEL as u64;
+        }
+        stats.upload.record(started.elapsed());
+        let started = Instant::now();
+        let rgb_source = matches!(layout.format, PixelFormat::Rgba8 | PixelFormat::Rgbx8);
+        let texture = d3d11.convert_bgra_to_rgba(
+            width,
+            height,
+            rgb_source,
+            source.alpha.mode,
+            front,
+            output_index,
+        )?;
+        stats.convert.record(started.elapsed());
+
+        let started = Instant::now();
+        unsafe {
+            // Set overlay texture using SetOverlayTexture / SetOverlayTextureを使用してオーバーレイテクスチャを設定
+            // Get raw pointer for OpenVR / OpenVR用の生ポインタを取得
+            let texture_ptr = texture.as_raw();
+
+            let mut vr_texture = vr::Texture_t {
+                handle: texture_ptr,
+                eType: vr::ETextureType_TextureType_DirectX,
+                eColorSpace: match output.color_space {
+                    ColorSpace::Auto => vr::EColorSpace_ColorSpace_Auto,
+                    ColorSpace::Gamma => vr::EColorSpace_ColorSpace_Gamma,
+                    ColorSpace::Linear => vr::EColorSpace_ColorSpace_Linear,
+                },
+            };
+
+            for (index, handle) in handles.iter().enumerate() {
+                let err = set_texture_fn(handle.as_u64(), &mut vr_texture);
+                if err != vr::EVROverlayError_VROverlayError_None {
+                    let action = match index {
+                        0 => "SetOverlayTexture".to_string(),
+                        _ => format!("SetOverlayTexture({})", handle.as_u64()),
+                    };
+                    return Err(overlay_error(&action, overlay_ptr.as_ref(), err));
+                }
+            }
+        }
+        stats.submit.record(started.elapsed());
+        d3d11.content_owner = Some(handles[0].as_u64());
+        self.last_frame = Some(LastFrame {
+            width,
+            height,
+            pixels: FramePixels::Texture(texture),
+        });
+        Ok(true)
+    }
+
+    /// SetOverlayRaw packed RGBA8 on every handle and keep it as the last frame.
+    /// 詰めた RGBA8 を全ハンドルに SetOverlayRaw し、直近のフレームとして記録する
+    pub(super) fn upload_raw(
+        &mut self,
+        handles: &[OverlayHandle],
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+    ) -> napi::Result<()> {
+        let overlay = unsafe { self.overlay_ptr()?.as_ref() };
+        for handle in handles {
+            set_raw_rgba(overlay, *handle, rgba, width, height)?;
+        }
+        self.record_raw_frame(width, height, rgba);
+        Ok(())
+    }
+
+    /// Remember RGBA pixels just uploaded with SetOverlayRaw as the last frame.
+    /// SetOverlayRaw でアップロードした RGBA を直近のフレームとして記録する
+    fn record_raw_frame(&mut self, width: u32, height: u32, rgba: &[u8]) {
+        // Reuse the previous copy's allocation / 前回のコピーの領域を再利用する
+        let mut kept = match self.last_frame.take() {
+            Some(LastFrame {
+                pixels: FramePixels::Rgba(kept),
+                ..
+            }) => kept,
+            _ => Vec::new(),
+        };
+        kept.clear();
+        kept.extend_from_slice(rgba);
+        self.last_frame = Some(LastFrame {
+            width,
+            height,
+            pixels: FramePixels::Rgba(kept),
+        });
+        self.capture_frame();
+    }
+
+    /// The last submitted frame as packed RGBA, read back from the GPU on the D3D11 path.
+    /// 直近に送信したフレームを詰めた RGBA で返す (D3D11 パスでは GPU から読み戻す)
+    fn last_frame_rgba(&self) -> napi::Result<(u32, u32, Cow<'_, [u8]>)> {
+        let frame = self
+            .last_frame
+            .as_ref()
+            .ok_or_else(|| napi::Error::from_reason("No frame has been submitted yet"))?;
+        let rgba = match &frame.pixels {
+            FramePixels::Rgba(rgba) => Cow::Borrowed(rgba.as_slice()),
+            FramePixels::History(handle) => self
+                .frames
+                .get(handle)
+                .map(|history| Cow::Borrowed(history.converted.as_slice()))
+                .ok_or_else(|| {
+                    napi::Error::from_reason("The last frame has been replaced on the overlay")
+                })?,
+            FramePixels::Texture(texture) => {
+                let d3d11 = self
+                    .d3d11
+                    .as_ref()
+                    .ok_or_else(|| napi::Error::from_reason("D3D11 context not initialized"))?;
+                Cow::Owned(d3d11.read_output_rgba(texture)?)
+            }
+        };
+        Ok((frame.width, frame.height, rgba))
+    }
+
+    /// Write the last submitted frame to `path` as a PNG / 直近に送信したフレームを PNG として `path` に書き出す
+    pub(super) fn save_last_frame(&self, path: &Path) -> napi::Result<()> {
+        let (width, height, rgba) = self.last_frame_rgba()?;
+        write_png(path, width, height, &rgba).map_err(napi::Error::from_reason)
+    }
+
+    /// Copy the frame just submitted for the captureNextFrames writer; the PNG is encoded off
+    /// the pipeline lock. A failure ends the capture rather than failing a submission that
+    /// already reached VR.
+    /// captureNextFrames の書き出しスレッドに、送信したフレームのコピーを渡す (PNG のエンコードは
+    /// パイプラインのロック外で行う)。失敗した場合は、既に VR に届いた送信を失敗させず、キャプチャを終了する。
+    fn capture_frame(&mut self) {
+        if self.capture.is_none() {
+            return;
+        }
+        let frame = self
+            .last_frame_rgba()
+            .map(|(width, height, rgba)| (width, height, rgba.into_owned()));
+        let capturing = match (frame, self.capture.as_mut()) {
+            (Ok((width, height, rgba)), Some(capture)) => capture.push(width, height, rgba),
+            (Err(e), _) => {
+                eprintln!("[vr-overlay-native] frame capture: {}", e.reason);
+                false
+            }
+            (_, None) => false,
+        };
+        if !capturing {
+            self.capture = None;
+        }
+    }
+}
+
+pub(super) fn lock_pipeline(
+    pipeline: &Mutex<TexturePipeline>,
+) -> napi::Result<MutexGuard<'_, TexturePipeline>> {
+    pipeline
+        .lock()
+        .map_err(|_| napi::Error::from_reason("texture pipeline lock poisoned"))
+}
+
+/// Latest-frame-wins mailbox: a queued frame is dropped once a newer one for the same
+/// front overlay has been queued.
+/// 最新フレーム優先のメールボックス: 同じ前面オーバーレイに新しいフレームが積まれたら、
+/// 待機中のフレームは破棄する。
+#[derive(Default)]
+pub(super) struct UploadQueue {
+    /// Sequence of the newest frame per front overlay handle / 前面オーバーレイハンドルごとの最新フレームの連番
+    latest: Mutex<HashMap<u64, u64>>,
+    submitted: AtomicU32,
+    dropped: AtomicU32,
+}
+
+impl UploadQueue {
+    /// Make `front`'s newest frame the one with the returned sequence / 返す連番のフレームを `front` の最新にする
+    pub(super) fn enqueue(&self, front: u64) -> u64 {
+        let mut latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        let sequence = latest.entry(front).or_default();
+        *sequence += 1;
+        *sequence
+    }
+
+    pub(super) fn dropped_frames(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn is_latest(&self, front: u64, sequence: u64) -> bool {
+        let latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        latest.get(&front) == Some(&sequence)
+    }
+
+    fn submission(&self, dropped: bool, update: Option<TextureUpdate>) -> FrameSubmission {
+        let counter = if dropped {
+            &self.dropped
+        } else {
+            &self.submitted
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        FrameSubmission {
+            dropped,
+            submitted: update.as_ref().is_some_and(|update| update.submitted),
+            dirtyRatio: update.as_ref().map_or(0.0, |update| update.dirtyRatio),
+            dirtyRects: update.as_ref().map_or(0, |update| update.dirtyRects),
+            submittedFrames: self.submitted.load(Ordering::Relaxed),
+            droppedFrames: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Frame handed over by submit_overlay_frame, converted and submitted on a libuv worker.
+/// submit_overlay_frame から渡され、libuv のワーカーで変換・送信されるフレーム
+pub struct FrameUpload {
+    pub(super) pipeline: Arc<Mutex<TexturePipeline>>,
+    pub(super) queue: Arc<UploadQueue>,
+    pub(super) handles: Vec<OverlayHandle>,
+    pub(super) sequence: u64,
+    pub(super) buffer: Buffer,
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) descriptor: Option<TextureDescriptor>,
+}
+
+impl Task for FrameUpload {
+    type Output = FrameSubmission;
+    type JsValue = FrameSubmission;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let front = self.handles[0].as_u64();
+        if !self.queue.is_latest(front, self.sequence) {
+            return Ok(self.queue.submission(true, None));
+        }
+        let mut pipeline = lock_pipeline(&self.pipeline)?;
+        // A newer frame may have arrived while another upload held the lock
+        // 別のアップロードがロック中に新しいフレームが届いている場合がある
+        if !self.queue.is_latest(front, self.sequence) {
+            return Ok(self.queue.submission(true, None));
+        }
+        let update = pipeline.submit_frame(
+            &self.handles,
+            &self.buffer,
+            self.width,
+            self.height,
+            self.descriptor.take(),
+        )?;
+        Ok(self.queue.submission(false, Some(update)))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
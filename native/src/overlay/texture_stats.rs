@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Frames kept for the rolling percentiles (about 4 s at 60 FPS) / ローリングパーセンタイルに使うフレーム数 (60 FPS で約 4 秒)
+pub(super) const STATS_WINDOW: usize = 240;
+
+/// Durations of the most recent frames, in milliseconds / 直近のフレームの所要時間 (ミリ秒)
+#[derive(Debug, Default)]
+pub(super) struct RollingDurations {
+    samples: VecDeque<f64>,
+}
+
+impl RollingDurations {
+    pub(super) fn record(&mut self, duration: Duration) {
+        if self.samples.len() == STATS_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration.as_secs_f64() * 1000.0);
+    }
+
+    pub(super) fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Nearest-rank percentiles for each `p` in 0..=100, or zeros when empty.
+    /// 各 `p` (0..=100) の最近順位法によるパーセンタイル (空なら 0)
+    pub(super) fn percentiles<const N: usize>(&self, ps: [f64; N]) -> [f64; N] {
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        if sorted.is_empty() {
+            return [0.0; N];
+        }
+        sorted.sort_by(f64::total_cmp);
+        ps.map(|p| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        })
+    }
+}
+
+/// Timings and counters of the texture pipeline / テクスチャパイプラインの所要時間とカウンター
+#[derive(Debug, Default)]
+pub(super) struct PipelineStats {
+    /// Whole submit_frame call, including the frame diff / フレーム差分を含む submit_frame 全体
+    pub frame: RollingDurations,
+    /// Copying dirty regions into the D3D11 input texture / D3D11 入力テクスチャへの変更領域のコピー
+    pub upload: RollingDurations,
+    /// Shader pass, or CPU conversion on the raw path / シェーダパス (raw パスでは CPU 変換)
+    pub convert: RollingDurations,
+    /// SetOverlayTexture / SetOverlayRaw calls / SetOverlayTexture / SetOverlayRaw の呼び出し
+    pub submit: RollingDurations,
+    pub bytes_uploaded: u64,
+    pub submitted_frames: u32,
+    /// Frames not submitted because nothing changed / 変化が無く送信しなかったフレーム
+    pub skipped_frames: u32,
+    /// Changed frames dropped because every D3D11 output texture was still being drawn
+    /// D3D11 の出力テクスチャがすべて描画中だったため破棄した、変化のあるフレーム
+    pub busy_frames: u32,
+    /// Times the D3D11 textures were rebuilt / D3D11 テクスチャを作り直した回数
+    pub recreations: u32,
+}
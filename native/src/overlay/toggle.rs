@@ -0,0 +1,326 @@
+/// Gestures recognized on the toggle action / トグルアクションで認識するジェスチャー
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ToggleGestureKind {
+    Click,
+    DoubleClick,
+    LongPress,
+    HoldStart,
+    HoldEnd,
+}
+
+impl ToggleGestureKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            ToggleGestureKind::Click => "click",
+            ToggleGestureKind::DoubleClick => "doubleClick",
+            ToggleGestureKind::LongPress => "longPress",
+            ToggleGestureKind::HoldStart => "holdStart",
+            ToggleGestureKind::HoldEnd => "holdEnd",
+        }
+    }
+}
+
+/// Which gesture drives overlay visibility / どのジェスチャーでオーバーレイの表示を切り替えるか
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ToggleMode {
+    Click,
+    DoubleClick,
+    LongPress,
+    /// Show while held, hide on release / 押している間だけ表示
+    Hold,
+}
+
+impl ToggleMode {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "click" => Some(ToggleMode::Click),
+            "doubleClick" => Some(ToggleMode::DoubleClick),
+            "longPress" => Some(ToggleMode::LongPress),
+            "hold" => Some(ToggleMode::Hold),
+            _ => None,
+        }
+    }
+}
+
+/// Visibility change requested by a gesture / ジェスチャーが要求する表示の変更
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ToggleAction {
+    Toggle,
+    Show,
+    Hide,
+}
+
+impl ToggleAction {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            ToggleAction::Toggle => "toggle",
+            ToggleAction::Show => "show",
+            ToggleAction::Hide => "hide",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ToggleConfig {
+    pub mode: ToggleMode,
+    /// Release must last this long to count; SteamVR briefly drops the action
+    /// during overlay visibility changes.
+    /// この時間続いた解放のみ有効。オーバーレイ表示切替時に SteamVR が一瞬アクションを落とすため
+    pub release_debounce_seconds: f64,
+    /// Max gap between a click release and the next press / クリック解除から次の押下までの最大間隔
+    pub double_click_seconds: f64,
+    pub long_press_seconds: f64,
+}
+
+impl Default for ToggleConfig {
+    fn default() -> Self {
+        Self {
+            mode: ToggleMode::Click,
+            release_debounce_seconds: 0.05,
+            double_click_seconds: 0.35,
+            long_press_seconds: 0.8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ToggleGesture {
+    pub kind: ToggleGestureKind,
+    pub timestamp: f64,
+    pub action: Option<ToggleAction>,
+}
+
+#[derive(Default)]
+pub(super) struct ToggleRecognizer {
+    pressed_at: Option<f64>,
+    released_at: Option<f64>,
+    /// Press already reported as long press or double click / 長押しかダブルクリックとして報告済み
+    consumed: bool,
+    last_click: Option<f64>,
+}
+
+impl ToggleRecognizer {
+    fn gesture(kind: ToggleGestureKind, timestamp: f64, mode: ToggleMode) -> ToggleGesture {
+        let action = match (mode, kind) {
+            (ToggleMode::Click, ToggleGestureKind::Click)
+            | (ToggleMode::DoubleClick, ToggleGestureKind::DoubleClick)
+            | (ToggleMode::LongPress, ToggleGestureKind::LongPress) => Some(ToggleAction::Toggle),
+            (ToggleMode::Hold, ToggleGestureKind::HoldStart) => Some(ToggleAction::Show),
+            (ToggleMode::Hold, ToggleGestureKind::HoldEnd) => Some(ToggleAction::Hide),
+            _ => None,
+        };
+        ToggleGesture {
+            kind,
+            timestamp,
+            action,
+        }
+    }
+
+    /// Feed the action state sampled at `now` / `now` 時点のアクション状態を入力
+    pub(super) fn update(
+        &mut self,
+        held: bool,
+        now: f64,
+        config: &ToggleConfig,
+    ) -> Vec<ToggleGesture> {
+        let mode = config.mode;
+        let mut gestures = Vec::new();
+
+        match self.pressed_at {
+            None if held => {
+                self.pressed_at = Some(now);
+                self.released_at = None;
+                self.consumed = false;
+                gestures.push(Self::gesture(ToggleGestureKind::HoldStart, now, mode));
+                // Click mode toggles on press so long holds and double taps still toggle
+                // クリックモードは押下で切り替えるため、長押しやダブルタップでも切り替わる
+                if mode == ToggleMode::Click {
+                    gestures.push(Self::gesture(ToggleGestureKind::Click, now, mode));
+                }
+                if let Some(last_click) = self.last_click.take() {
+                    if now - last_click <= config.double_click_seconds {
+                        self.consumed = true;
+                        gestures.push(Self::gesture(ToggleGestureKind::DoubleClick, now, mode));
+                    }
+                }
+            }
+            None => {}
+            Some(_) if held => self.released_at = None,
+            Some(_) if self.released_at.is_none() => self.released_at = Some(now),
+            Some(_) => {}
+        }
+
+        let Some(pressed_at) = self.pressed_at else {
+            return gestures;
+        };
+
+        let long_press_at = pressed_at + config.long_press_seconds;
+        let held_until = self.released_at.unwrap_or(now);
+        if !self.consumed && held_until >= long_press_at {
+            self.consumed = true;
+            gestures.push(Self::gesture(
+                ToggleGestureKind::LongPress,
+                long_press_at,
+                mode,
+            ));
+        }
+
+        if let Some(released_at) = self.released_at {
+            if now - released_at >= config.release_debounce_seconds {
+                gestures.push(Self::gesture(ToggleGestureKind::HoldEnd, released_at, mode));
+                if self.consumed {
+                    self.last_click = None;
+                } else {
+                    if mode != ToggleMode::Click {
+                        gestures.push(Self::gesture(ToggleGestureKind::Click, released_at, mode));
+                    }
+                    self.last_click = Some(released_at);
+                }
+                self.pressed_at = None;
+                self.released_at = None;
+            }
+        }
+        gestures
+    }
+
+    pub(super) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Default)]
+pub(super) struct ToggleState {
+    pub config: ToggleConfig,
+    pub recognizer: ToggleRecognizer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ToggleGestureKind::*;
+    use super::*;
+
+    fn config(mode: ToggleMode) -> ToggleConfig {
+        ToggleConfig {
+            mode,
+            ..ToggleConfig::default()
+        }
+    }
+
+    /// Feed `(held, now)` samples and collect every gesture / サンプルを入力して全ジェスチャーを集める
+    fn run(config: &ToggleConfig, samples: &[(bool, f64)]) -> Vec<ToggleGesture> {
+        let mut recognizer = ToggleRecognizer::default();
+        samples
+            .iter()
+            .flat_map(|&(held, now)| recognizer.update(held, now, config))
+            .collect()
+    }
+
+    fn kinds(gestures: &[ToggleGesture]) -> Vec<ToggleGestureKind> {
+        gestures.iter().map(|g| g.kind).collect()
+    }
+
+    fn toggles(gestures: &[ToggleGesture]) -> Vec<f64> {
+        gestures
+            .iter()
+            .filter(|g| g.action == Some(ToggleAction::Toggle))
+            .map(|g| g.timestamp)
+            .collect()
+    }
+
+    #[test]
+    fn click_mode_toggles_on_press() {
+        let gestures = run(&config(ToggleMode::Click), &[(true, 1.0), (false, 1.1)]);
+        assert_eq!(toggles(&gestures), vec![1.0]);
+        assert_eq!(kinds(&gestures), vec![HoldStart, Click]);
+    }
+
+    #[test]
+    fn click_mode_toggles_on_long_hold() {
+        let gestures = run(
+            &config(ToggleMode::Click),
+            &[(true, 0.0), (true, 1.0), (false, 1.2), (false, 1.3)],
+        );
+        assert_eq!(toggles(&gestures), vec![0.0]);
+        assert_eq!(kinds(&gestures), vec![HoldStart, Click, LongPress, HoldEnd]);
+    }
+
+    #[test]
+    fn click_mode_toggles_on_both_taps_of_double_tap() {
+        let gestures = run(
+            &config(ToggleMode::Click),
+            &[
+                (true, 0.0),
+                (false, 0.1),
+                (false, 0.2),
+                (true, 0.3),
+                (false, 0.4),
+                (false, 0.5),
+            ],
+        );
+        assert_eq!(toggles(&gestures), vec![0.0, 0.3]);
+        assert!(kinds(&gestures).contains(&DoubleClick));
+    }
+
+    #[test]
+    fn click_mode_ignores_release_shorter_than_debounce() {
+        let gestures = run(
+            &config(ToggleMode::Click),
+            &[
+                (true, 0.0),
+                (false, 0.1),
+                (true, 0.12),
+                (false, 0.3),
+                (false, 0.4),
+            ],
+        );
+        assert_eq!(toggles(&gestures), vec![0.0]);
+    }
+
+    #[test]
+    fn double_click_mode_toggles_on_second_press_only() {
+        let gestures = run(
+            &config(ToggleMode::DoubleClick),
+            &[
+                (true, 0.0),
+                (false, 0.1),
+                (false, 0.2),
+                (true, 0.3),
+                (false, 0.4),
+                (false, 0.5),
+            ],
+        );
+        assert_eq!(toggles(&gestures), vec![0.3]);
+        assert_eq!(
+            kinds(&gestures),
+            vec![HoldStart, HoldEnd, Click, HoldStart, DoubleClick, HoldEnd]
+        );
+    }
+
+    #[test]
+    fn long_press_mode_toggles_once_and_suppresses_click() {
+        let gestures = run(
+            &config(ToggleMode::LongPress),
+            &[
+                (true, 0.0),
+                (true, 0.5),
+                (true, 0.9),
+                (true, 1.5),
+                (false, 2.0),
+                (false, 2.1),
+            ],
+        );
+        assert_eq!(toggles(&gestures), vec![0.8]);
+        assert!(!kinds(&gestures).contains(&Click));
+    }
+
+    #[test]
+    fn hold_mode_shows_and_hides() {
+        let gestures = run(
+            &config(ToggleMode::Hold),
+            &[(true, 0.0), (true, 0.5), (false, 0.6), (false, 0.7)],
+        );
+        let actions: Vec<_> = gestures.iter().filter_map(|g| g.action).collect();
+        assert_eq!(actions, vec![ToggleAction::Show, ToggleAction::Hide]);
+        assert_eq!(gestures.last().map(|g| g.timestamp), Some(0.6));
+    }
+}
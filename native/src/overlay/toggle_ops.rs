@@ -0,0 +1,96 @@
+use napi_derive::napi;
+
+use super::input_ops::{read_toggle_state, update_action_state};
+use super::manager::OverlayManager;
+use super::toggle::{ToggleGesture, ToggleMode};
+use super::types::{ToggleGestureEvent, ToggleGestureSettings};
+
+pub(super) fn toggle_gesture_event(gesture: ToggleGesture) -> ToggleGestureEvent {
+    ToggleGestureEvent {
+        kind: gesture.kind.as_str().to_string(),
+        timestamp: gesture.timestamp,
+        action: gesture.action.map(|action| action.as_str().to_string()),
+    }
+}
+
+fn seconds(name: &str, value: Option<f64>, current: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(napi::Error::from_reason(format!(
+            "{name} must be a non-negative number"
+        ))),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+impl OverlayManager {
+    /// Feed the toggle action state into the gesture recognizer / トグルアクションの状態をジェスチャー認識に入力
+    pub(super) fn recognize_toggle(&self, held: bool) -> napi::Result<Vec<ToggleGesture>> {
+        let now = self.now_seconds();
+        let mut toggle = self.lock_toggle()?;
+        let config = toggle.config;
+        Ok(toggle.recognizer.update(held, now, &config))
+    }
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update toggle gesture mode and timings; omitted fields keep their value.
+    /// トグルのジェスチャーモードと時間を更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_toggle_gestures(&self, settings: ToggleGestureSettings) -> napi::Result<()> {
+        let mut toggle = self.lock_toggle()?;
+        let mut config = toggle.config;
+        if let Some(mode) = settings.mode {
+            config.mode = ToggleMode::from_name(&mode).ok_or_else(|| {
+                napi::Error::from_reason(format!("Unknown toggle mode: {}", mode))
+            })?;
+        }
+        config.release_debounce_seconds = seconds(
+            "releaseDebounceSeconds",
+            settings.releaseDebounceSeconds,
+            config.release_debounce_seconds,
+        )?;
+        config.double_click_seconds = seconds(
+            "doubleClickSeconds",
+            settings.doubleClickSeconds,
+            config.double_click_seconds,
+        )?;
+        config.long_press_seconds = seconds(
+            "longPressSeconds",
+            settings.longPressSeconds,
+            config.long_press_seconds,
+        )?;
+        toggle.config = config;
+        // Do not carry a half-recognized gesture into the new mode / 認識途中のジェスチャーは持ち越さない
+        toggle.recognizer.reset();
+        Ok(())
+    }
+
+    /// Update SteamVR Input and return toggle gestures recognized since the last poll.
+    /// Use instead of poll_toggle_clicked, not alongside it.
+    /// SteamVR Input を更新し、前回のポーリング以降に認識したトグルジェスチャーを返す。
+    /// poll_toggle_clicked の代わりに使い、併用しないこと。
+    #[napi]
+    pub fn poll_toggle_gestures(&self) -> napi::Result<Vec<ToggleGestureEvent>> {
+        if self.input_thread_running()? {
+            // The input thread reports gestures itself / 入力スレッドがジェスチャーを通知する
+            return Ok(vec![]);
+        }
+
+        let input = self.input()?;
+        let cache = self.borrow_input_cache()?;
+        if !cache.initialized {
+            return Err(napi::Error::from_reason("SteamVR input is not initialized"));
+        }
+        update_action_state(input, &cache)?;
+        let held = read_toggle_state(input, &cache)?;
+        drop(cache);
+
+        Ok(self
+            .recognize_toggle(held)?
+            .into_iter()
+            .map(toggle_gesture_event)
+            .collect())
+    }
+}
@@ -3,11 +3,83 @@ use openvr_sys as vr;
 
 use super::constants::HMD_DEVICE_INDEX;
 use super::errors::{overlay_error, require_fn};
-use super::handles::overlay_handle;
+use super::handles::{overlay_handle, OverlayHandle};
 use super::manager::OverlayManager;
 use super::math::{hmd_matrix34_to_vec, validate_matrix};
+use super::poke::OverlayPlane;
 use super::types::OverlayRelativeTransform;
 
+impl OverlayManager {
+    /// Absolute overlay quad (transform, width and texture aspect) for plane tests.
+    /// 平面判定用のオーバーレイ矩形 (絶対変換・幅・テクスチャのアスペクト比)
+    pub(super) fn overlay_plane(&self, handle: OverlayHandle) -> napi::Result<OverlayPlane> {
+        let overlay = self.overlay()?;
+        let get_transform_fn = require_fn(
+            overlay.GetOverlayTransformAbsolute,
+            "GetOverlayTransformAbsolute",
+        )?;
+        let get_width_fn = require_fn(overlay.GetOverlayWidthInMeters, "GetOverlayWidthInMeters")?;
+        let get_bounds_fn = require_fn(overlay.GetOverlayTextureBounds, "GetOverlayTextureBounds")?;
+
+        let mut origin = vr::ETrackingUniverseOrigin_TrackingUniverseStanding;
+        let mut transform = vr::HmdMatrix34_t { m: [[0.0; 4]; 3] };
+        let mut width_meters: f32 = 0.0;
+        let mut bounds = vr::VRTextureBounds_t {
+            uMin: 0.0,
+            vMin: 0.0,
+            uMax: 1.0,
+            vMax: 1.0,
+        };
+        let mut texture_width: u32 = 0;
+        let mut texture_height: u32 = 0;
+
+        unsafe {
+            let err = get_transform_fn(handle.as_u64(), &mut origin, &mut transform);
+            if err != vr::EVROverlayError_VROverlayError_None {
+                return Err(overlay_error("GetOverlayTransformAbsolute", overlay, err));
+            }
+            let err = get_width_fn(handle.as_u64(), &mut width_meters);
+            if err != vr::EVROverlayError_VROverlayError_None {
+                return Err(overlay_error("GetOverlayWidthInMeters", overlay, err));
+            }
+            let err = get_bounds_fn(handle.as_u64(), &mut bounds);
+            if err != vr::EVROverlayError_VROverlayError_None {
+                return Err(overlay_error("GetOverlayTextureBounds", overlay, err));
+            }
+
+            // Texture size may be unavailable for shared textures; fall back to D3D11 size
+            // 共有テクスチャではサイズを取得できない場合があるため D3D11 のサイズで補完
+            let size_known = match overlay.GetOverlayTextureSize {
+                Some(get_size_fn) => {
+                    get_size_fn(handle.as_u64(), &mut texture_width, &mut texture_height)
+                        == vr::EVROverlayError_VROverlayError_None
+                        && texture_width > 0
+                        && texture_height > 0
+                }
+                None => false,
+            };
+            if !size_known {
+                let pipeline = self.lock_pipeline()?;
+                let d3d11 = pipeline
+                    .d3d11
+                    .as_ref()
+                    .filter(|ctx| ctx.texture_width > 0 && ctx.texture_height > 0)
+                    .ok_or_else(|| napi::Error::from_reason("Overlay texture size is unknown"))?;
+                texture_width = d3d11.texture_width;
+                texture_height = d3d11.texture_height;
+            }
+        }
+
+        let visible_width = texture_width as f64 * (bounds.uMax - bounds.uMin).abs() as f64;
+        let visible_height = texture_height as f64 * (bounds.vMax - bounds.vMin).abs() as f64;
+        if visible_width <= 0.0 {
+            return Err(napi::Error::from_reason("Overlay texture bounds are empty"));
+        }
+        let width = width_meters as f64;
+        OverlayPlane::new(&transform.m, width, width * visible_height / visible_width)
+    }
+}
+
 #[napi]
 impl OverlayManager {
     #[napi]
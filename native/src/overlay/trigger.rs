@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+
+/// Edge events emitted by the trigger state machine / トリガー状態機械が出すエッジイベント
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TriggerEventKind {
+    Pressed,
+    Released,
+    LongPress,
+    DoubleClick,
+}
+
+impl TriggerEventKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            TriggerEventKind::Pressed => "pressed",
+            TriggerEventKind::Released => "released",
+            TriggerEventKind::LongPress => "longPress",
+            TriggerEventKind::DoubleClick => "doubleClick",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct TriggerConfig {
+    /// Analog value that starts a press / 押下とみなすアナログ値
+    pub press_threshold: f64,
+    /// Analog value below which a press ends (hysteresis) / 押下解除とみなすアナログ値 (ヒステリシス)
+    pub release_threshold: f64,
+    /// Shortest reported press; earlier releases are deferred and bounces merged
+    /// 報告する最短の押下時間。これより早い解除は遅延させ、チャタリングは統合する
+    pub min_hold_seconds: f64,
+    pub long_press_seconds: f64,
+    /// Max gap between a click release and the next press / クリック解除から次の押下までの最大間隔
+    pub double_click_seconds: f64,
+}
+
+impl Default for TriggerConfig {
+    fn default() -> Self {
+        Self {
+            press_threshold: 0.55,
+            release_threshold: 0.35,
+            min_hold_seconds: 0.03,
+            long_press_seconds: 0.6,
+            double_click_seconds: 0.35,
+        }
+    }
+}
+
+/// SteamVR Input digital trigger action / SteamVR Input のデジタルトリガーアクション
+#[derive(Clone, Copy, Debug)]
+pub(super) struct DigitalTrigger {
+    pub pressed: bool,
+    /// When the state changed, if it changed since the last action update
+    /// 前回のアクション更新以降に変化した場合、その時刻
+    pub changed_at: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct TriggerSample {
+    pub value: f64,
+    /// Trigger click of the controller state (the action's state once SteamVR Input is
+    /// initialized); used until the analog axis first moves
+    /// コントローラー状態のトリガークリック (SteamVR Input 初期化後はアクションの状態)。
+    /// アナログ軸が初めて動くまで使う
+    pub button: bool,
+    /// Digital trigger action; replaces `button` until the analog axis first moves
+    /// デジタルトリガーアクション。アナログ軸が初めて動くまで `button` の代わりに使う
+    pub digital: Option<DigitalTrigger>,
+    pub timestamp: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct TriggerEdge {
+    pub kind: TriggerEventKind,
+    pub timestamp: f64,
+    pub value: f64,
+}
+
+/// Per-controller trigger state machine; edges queue up until drained.
+/// コントローラーごとのトリガー状態機械。エッジは取り出されるまでキューに溜まる
+#[derive(Default)]
+pub(super) struct TriggerMachine {
+    input_down: bool,
+    /// The analog axis has moved, so its thresholds alone decide presses
+    /// アナログ軸が動いたため、押下はその閾値だけで判定する
+    analog_seen: bool,
+    pressed_at: Option<f64>,
+    release_at: Option<f64>,
+    long_press_fired: bool,
+    double_click_fired: bool,
+    last_click_release: Option<f64>,
+    last_value: f64,
+    queue: Vec<TriggerEdge>,
+}
+
+impl TriggerMachine {
+    fn sample_down(&mut self, sample: &TriggerSample, config: &TriggerConfig) -> (bool, f64) {
+        // The click closes near the press threshold, so mixing it in would bypass the
+        // analog hysteresis; it only drives triggers without an analog reading
+        // クリックは押下閾値付近で閉じるため、混ぜるとアナログのヒステリシスが効かなくなる。
+        // クリックはアナログ値の無いトリガーでのみ使う
+        self.analog_seen |= sample.value > 0.0;
+        if self.analog_seen {
+            let down = if self.input_down {
+                sample.value > config.release_threshold
+            } else {
+                sample.value >= config.press_threshold
+            };
+            return (down, sample.timestamp);
+        }
+
+        match sample.digital {
+            // Dated to when the action changed / アクションが変化した時刻を使う
+            Some(DigitalTrigger {
+                pressed,
+                changed_at,
+            }) => (
+                pressed,
+                changed_at.map_or(sample.timestamp, |at| at.min(sample.timestamp)),
+            ),
+            None => (sample.button, sample.timestamp),
+        }
+    }
+
+    fn push(&mut self, kind: TriggerEventKind, timestamp: f64) {
+        self.queue.push(TriggerEdge {
+            kind,
+            timestamp,
+            value: self.last_value,
+        });
+    }
+
+    pub(super) fn update(&mut self, sample: TriggerSample, config: &TriggerConfig) {
+        let (down, at) = self.sample_down(&sample, config);
+        self.input_down = down;
+        self.last_value = sample.value;
+
+        match self.pressed_at {
+            None if down => {
+                self.pressed_at = Some(at);
+                self.release_at = None;
+                self.long_press_fired = false;
+                self.double_click_fired = false;
+                self.push(TriggerEventKind::Pressed, at);
+                if let Some(last_release) = self.last_click_release.take() {
+                    if at - last_release <= config.double_click_seconds {
+                        self.double_click_fired = true;
+                        self.push(TriggerEventKind::DoubleClick, at);
+                    }
+                }
+            }
+            None => {}
+            // Pressed again before the deferred release: treat as bounce
+            // 遅延中の解除より前に再押下: チャタリングとして扱う
+            Some(_) if down => self.release_at = None,
+            Some(pressed_at) if self.release_at.is_none() => {
+                self.release_at = Some(at.max(pressed_at));
+            }
+            Some(_) => {}
+        }
+        self.advance(sample.timestamp, config);
+    }
+
+    fn advance(&mut self, now: f64, config: &TriggerConfig) {
+        let Some(pressed_at) = self.pressed_at else {
+            return;
+        };
+        let long_press_at = pressed_at + config.long_press_seconds;
+
+        if let Some(release_at) = self.release_at {
+            let release_at = release_at.max(pressed_at + config.min_hold_seconds);
+            if now < release_at {
+                return;
+            }
+            if !self.long_press_fired && release_at >= long_press_at {
+                self.long_press_fired = true;
+                self.push(TriggerEventKind::LongPress, long_press_at);
+            }
+            self.push(TriggerEventKind::Released, release_at);
+            // Long presses and double clicks do not start another double click
+            // 長押しとダブルクリックは次のダブルクリックの起点にしない
+            self.last_click_release = if self.long_press_fired || self.double_click_fired {
+                None
+            } else {
+                Some(release_at)
+            };
+            self.pressed_at = None;
+            self.release_at = None;
+            return;
+        }
+
+        if !self.long_press_fired && now >= long_press_at {
+            self.long_press_fired = true;
+            self.push(TriggerEventKind::LongPress, long_press_at);
+        }
+    }
+
+    /// Release a held trigger (e.g. controller lost) / 押下中のトリガーを解放 (コントローラー消失時など)
+    pub(super) fn reset(&mut self, now: f64) {
+        if self.pressed_at.is_some() {
+            self.push(TriggerEventKind::Released, now);
+        }
+        let queue = std::mem::take(&mut self.queue);
+        *self = Self {
+            queue,
+            ..Self::default()
+        };
+    }
+
+    pub(super) fn drain(&mut self) -> Vec<TriggerEdge> {
+        std::mem::take(&mut self.queue)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct TriggerState {
+    pub config: TriggerConfig,
+    pub machines: HashMap<u32, TriggerMachine>,
+}
+
+impl TriggerState {
+    /// Carry a trigger over to a controller's new index after it reconnected.
+    /// 再接続したコントローラーの新しい番号にトリガー状態を引き継ぐ
+    pub(super) fn remap_controller(&mut self, previous: u32, index: u32) {
+        if let Some(machine) = self.machines.remove(&previous) {
+            self.machines.insert(index, machine);
+        }
+    }
+
+    /// Feed one sample per connected controller; controllers without a sample are released.
+    /// 接続中のコントローラーごとにサンプルを入力し、サンプルの無いコントローラーは解放する
+    pub(super) fn update_all(
+        &mut self,
+        samples: Vec<(u32, TriggerSample)>,
+        now: f64,
+    ) -> Vec<(u32, TriggerEdge)> {
+        let config = self.config;
+        let mut edges = Vec::new();
+
+        let stale: Vec<u32> = self
+            .machines
+            .keys()
+            .copied()
+            .filter(|index| !samples.iter().any(|(sampled, _)| sampled == index))
+            .collect();
+        for index in stale {
+            if let Some(mut machine) = self.machines.remove(&index) {
+                machine.reset(now);
+                edges.extend(machine.drain().into_iter().map(|edge| (index, edge)));
+            }
+        }
+
+        for (index, sample) in samples {
+            let machine = self.machines.entry(index).or_default();
+            machine.update(sample, &config);
+            edges.extend(machine.drain().into_iter().map(|edge| (index, edge)));
+        }
+        edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TriggerEventKind::*;
+    use super::*;
+
+    fn analog(value: f64, timestamp: f64) -> TriggerSample {
+        TriggerSample {
+            value,
+            button: false,
+            digital: None,
+            timestamp,
+        }
+    }
+
+    fn run(samples: &[TriggerSample]) -> Vec<(TriggerEventKind, f64)> {
+        let config = TriggerConfig::default();
+        let mut machine = TriggerMachine::default();
+        let mut edges = Vec::new();
+        for sample in samples {
+            machine.update(*sample, &config);
+            edges.extend(machine.drain().into_iter().map(|e| (e.kind, e.timestamp)));
+        }
+        edges
+    }
+
+    fn kinds(edges: &[(TriggerEventKind, f64)]) -> Vec<TriggerEventKind> {
+        edges.iter().map(|(kind, _)| *kind).collect()
+    }
+
+    #[test]
+    fn hysteresis_holds_between_thresholds() {
+        let edges = run(&[
+            analog(0.5, 0.0),
+            analog(0.6, 0.1),
+            analog(0.4, 0.2),
+            analog(0.3, 0.3),
+            analog(0.3, 0.4),
+        ]);
+        assert_eq!(edges, vec![(Pressed, 0.1), (Released, 0.3)]);
+    }
+
+    #[test]
+    fn short_release_is_merged_as_bounce() {
+        let edges = run(&[
+            analog(0.9, 0.0),
+            analog(0.9, 0.01),
+            analog(0.0, 0.015),
+            analog(0.9, 0.02),
+            analog(0.0, 0.2),
+            analog(0.0, 0.25),
+        ]);
+        assert_eq!(edges, vec![(Pressed, 0.0), (Released, 0.2)]);
+    }
+
+    #[test]
+    fn early_release_is_deferred_to_min_hold() {
+        let edges = run(&[analog(0.9, 0.0), analog(0.0, 0.01), analog(0.0, 0.05)]);
+        assert_eq!(edges, vec![(Pressed, 0.0), (Released, 0.03)]);
+    }
+
+    #[test]
+    fn long_press_fires_once_while_held() {
+        let edges = run(&[
+            analog(0.9, 0.0),
+            analog(0.9, 0.5),
+            analog(0.9, 0.7),
+            analog(0.9, 0.9),
+            analog(0.0, 1.0),
+        ]);
+        assert_eq!(
+            edges,
+            vec![(Pressed, 0.0), (LongPress, 0.6), (Released, 1.0)]
+        );
+    }
+
+    #[test]
+    fn double_click_needs_a_quick_second_press() {
+        let quick = run(&[
+            analog(0.9, 0.0),
+            analog(0.0, 0.1),
+            analog(0.9, 0.3),
+            analog(0.0, 0.4),
+            // A third press does not chain off the double click
+            analog(0.9, 0.5),
+        ]);
+        assert_eq!(
+            kinds(&quick),
+            vec![Pressed, Released, Pressed, DoubleClick, Released, Pressed]
+        );
+
+        let slow = run(&[analog(0.9, 0.0), analog(0.0, 0.1), analog(0.9, 0.6)]);
+        assert_eq!(kinds(&slow), vec![Pressed, Released, Pressed]);
+    }
+
+    #[test]
+    fn long_press_does_not_start_a_double_click() {
+        let edges = run(&[analog(0.9, 0.0), analog(0.0, 0.7), analog(0.9, 0.8)]);
+        assert_eq!(kinds(&edges), vec![Pressed, LongPress, Released, Pressed]);
+    }
+
+    fn digital(
+        pressed: bool,
+        changed_at: Option<f64>,
+        value: f64,
+        timestamp: f64,
+    ) -> TriggerSample {
+        TriggerSample {
+            button: pressed,
+            digital: Some(DigitalTrigger {
+                pressed,
+                changed_at,
+            }),
+            ..analog(value, timestamp)
+        }
+    }
+
+    #[test]
+    fn digital_action_drives_triggers_without_analog_travel() {
+        let edges = run(&[
+            // The action press is dated to when it changed
+            digital(true, Some(0.08), 0.0, 0.1),
+            digital(true, None, 0.0, 0.2),
+            digital(false, Some(0.29), 0.0, 0.3),
+            digital(false, None, 0.0, 0.4),
+        ]);
+        assert_eq!(edges, vec![(Pressed, 0.08), (Released, 0.29)]);
+    }
+
+    #[test]
+    fn analog_axis_overrides_the_click_once_it_moves() {
+        let edges = run(&[
+            // The click closes before the axis reaches the press threshold
+            digital(true, Some(0.09), 0.4, 0.1),
+            digital(true, None, 0.6, 0.2),
+            // The click opens, but the axis is still past the release threshold
+            digital(false, Some(0.29), 0.5, 0.3),
+            digital(false, None, 0.3, 0.4),
+            digital(false, None, 0.0, 0.5),
+            // A click without axis travel no longer presses
+            digital(true, Some(0.59), 0.0, 0.6),
+        ]);
+        assert_eq!(edges, vec![(Pressed, 0.2), (Released, 0.4)]);
+    }
+
+    #[test]
+    fn missing_controllers_are_released() {
+        let mut state = TriggerState::default();
+        state.update_all(vec![(3, analog(0.9, 0.0))], 0.0);
+        let edges = state.update_all(Vec::new(), 0.2);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].0, 3);
+        assert_eq!(edges[0].1.kind, Released);
+        assert!(state.machines.is_empty());
+    }
+
+    #[test]
+    fn remap_keeps_a_held_trigger() {
+        let mut state = TriggerState::default();
+        state.update_all(vec![(3, analog(0.9, 0.0))], 0.0);
+        state.remap_controller(3, 5);
+        let edges = state.update_all(vec![(5, analog(0.9, 0.1))], 0.1);
+        assert!(edges.is_empty());
+    }
+}
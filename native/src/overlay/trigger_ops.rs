@@ -0,0 +1,152 @@
+use napi_derive::napi;
+use openvr_sys as vr;
+
+use super::controller_ops::{action_sources, read_controller_state};
+use super::input_ops::read_digital_action;
+use super::manager::{InputActionCache, OverlayManager};
+use super::trigger::{DigitalTrigger, TriggerEdge, TriggerSample};
+use super::types::{TriggerEvent, TriggerSettings};
+
+pub(super) fn trigger_event(controller_index: u32, edge: TriggerEdge) -> TriggerEvent {
+    TriggerEvent {
+        kind: edge.kind.as_str().to_string(),
+        controllerIndex: controller_index,
+        timestamp: edge.timestamp,
+        value: edge.value,
+    }
+}
+
+fn setting(name: &str, value: Option<f64>, current: f64, max: f64) -> napi::Result<f64> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 || value > max => Err(
+            napi::Error::from_reason(format!("{name} must be between 0 and {max}")),
+        ),
+        Some(value) => Ok(value),
+        None => Ok(current),
+    }
+}
+
+/// Merge the analog axis, legacy button and SteamVR Input digital action into one sample.
+/// アナログ軸・レガシーボタン・SteamVR Input のデジタルアクションを 1 つのサンプルにまとめる
+pub(super) fn read_trigger_sample(
+    system: &vr::VR_IVRSystem_FnTable,
+    input: Option<&vr::VR_IVRInput_FnTable>,
+    cache: &InputActionCache,
+    controller_index: u32,
+    timestamp: f64,
+) -> napi::Result<TriggerSample> {
+    let state = read_controller_state(system, input, cache, controller_index)?;
+
+    let digital = match input {
+        Some(input) if cache.initialized => {
+            let sources = action_sources(system, controller_index, cache);
+            read_digital_action(input, cache.trigger_action_handle, &sources).map(|data| {
+                DigitalTrigger {
+                    pressed: data.bState,
+                    // fUpdateTime is relative to now (negative) / fUpdateTime は現在からの相対時間 (負)
+                    changed_at: data
+                        .bChanged
+                        .then(|| timestamp + (data.fUpdateTime as f64).min(0.0)),
+                }
+            })
+        }
+        _ => None,
+    };
+
+    Ok(TriggerSample {
+        value: state.triggerValue,
+        button: state.triggerPressed,
+        digital,
+        timestamp,
+    })
+}
+
+#[napi]
+impl OverlayManager {
+    /// Update trigger thresholds and timings; omitted fields keep their value.
+    /// トリガーの閾値と時間を更新 (省略した項目は現在値を維持)
+    #[napi]
+    pub fn configure_trigger(&self, settings: TriggerSettings) -> napi::Result<()> {
+        let mut trigger = self.lock_trigger()?;
+        let mut config = trigger.config;
+        config.press_threshold = setting(
+            "pressThreshold",
+            settings.pressThreshold,
+            config.press_threshold,
+            1.0,
+        )?;
+        config.release_threshold = setting(
+            "releaseThreshold",
+            settings.releaseThreshold,
+            config.release_threshold,
+            1.0,
+        )?;
+        if config.release_threshold >= config.press_threshold {
+            return Err(napi::Error::from_reason(
+                "releaseThreshold must be lower than pressThreshold",
+            ));
+        }
+        config.min_hold_seconds = setting(
+            "minHoldSeconds",
+            settings.minHoldSeconds,
+            config.min_hold_seconds,
+            f64::MAX,
+        )?;
+        config.long_press_seconds = setting(
+            "longPressSeconds",
+            settings.longPressSeconds,
+            config.long_press_seconds,
+            f64::MAX,
+        )?;
+        config.double_click_seconds = setting(
+            "doubleClickSeconds",
+            settings.doubleClickSeconds,
+            config.double_click_seconds,
+            f64::MAX,
+        )?;
+        trigger.config = config;
+        Ok(())
+    }
+
+    /// Sample every controller's trigger and return queued edge events.
+    /// Controllers that disappeared are released. Edges also start and finish swipe trails.
+    /// 全コントローラーのトリガーをサンプリングし、溜まったエッジイベントを返す。
+    /// 消えたコントローラーは解放される。エッジはスワイプの軌跡の開始・終了にも使われる。
+    /// While the input thread runs they are delivered as "trigger" events and this returns nothing.
+    /// 入力スレッドの実行中は "trigger" イベントとして届き、この関数は何も返さない。
+    #[napi]
+    pub fn poll_trigger_events(&self) -> napi::Result<Vec<TriggerEvent>> {
+        if self.input_thread_running()? {
+            // The input thread samples triggers and reports edges itself
+            // 入力スレッドがトリガーをサンプリングし、エッジも通知する
+            return Ok(Vec::new());
+        }
+        self.play_due_haptics()?;
+        let controller_ids = self.get_controller_ids()?;
+        let system = self.system()?;
+        let input = self.input().ok();
+        let cache = self.borrow_input_cache()?;
+        let mut samples = Vec::with_capacity(controller_ids.len());
+        for index in controller_ids {
+            samples.push((
+                index,
+                read_trigger_sample(system, input, &cache, index, self.now_seconds())?,
+            ));
+        }
+        drop(cache);
+
+        let now = self.now_seconds();
+        let edges = self.lock_trigger()?.update_all(samples, now);
+        let mut swipe = self.lock_swipe()?;
+        for (index, edge) in &edges {
+            if let Some((trail, candidates)) = swipe.trigger_edge(*index, edge) {
+                swipe.finished.push((*index, trail, candidates));
+            }
+        }
+        drop(swipe);
+        Ok(edges
+            .into_iter()
+            .map(|(index, edge)| trigger_event(index, edge))
+            .collect())
+    }
+}
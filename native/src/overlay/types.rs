@@ -2,6 +2,7 @@
 use napi_derive::napi;
 
 #[napi(object)]
+#[derive(Clone)]
 pub struct IntersectionResult {
     pub x: f64,
     pub y: f64,
@@ -12,6 +13,7 @@ pub struct IntersectionResult {
 }
 
 #[napi(object)]
+#[derive(Clone)]
 pub struct ControllerState {
     pub triggerPressed: bool,
     pub triggerValue: f64,
@@ -24,18 +26,366 @@ pub struct ControllerState {
     pub joystickY: f64,
 }
 
+#[napi(object)]
+#[derive(Clone)]
+pub struct AnalogActionData {
+    pub active: bool,
+    pub x: f64,
+    pub y: f64,
+    pub deltaX: f64,
+    pub deltaY: f64,
+    pub originDeviceIndex: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct AnalogActionState {
+    pub trigger: AnalogActionData,
+    pub joystick: AnalogActionData,
+}
+
+#[napi(object)]
+pub struct PokeSettings {
+    pub tipOffset: Option<Vec<f64>>, // controller-space [x, y, z]
+    pub pressDepth: Option<f64>,
+    pub releaseDepth: Option<f64>,
+    pub hoverDistance: Option<f64>,
+    pub maxBehindDistance: Option<f64>,
+    pub minPressSpeed: Option<f64>,
+    pub maxLateralRatio: Option<f64>,
+}
+
+#[napi(object)]
+pub struct PokeEvent {
+    pub kind: String,         // "hover" | "press" | "release"
+    pub source: String,       // "finger" | "controller"
+    pub hand: Option<String>, // "left" | "right"
+    pub controllerIndex: Option<u32>,
+    pub u: f64,
+    pub v: f64,
+    pub depth: f64, // meters past the overlay plane (positive = pressed in)
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct PointerRay {
+    pub origin: Vec<f64>,
+    pub direction: Vec<f64>,
+    pub transform: Vec<f64>, // 4x4 flattened
+    pub originDeviceIndex: Option<u32>,
+}
+
+#[napi(object)]
+pub struct HapticOptions {
+    pub amplitude: Option<f64>, // 0..1 scale of the pattern amplitude
+    pub frequency: Option<f64>, // Hz, replaces the pattern frequency
+}
+
 #[napi(object)]
 pub struct OverlayRelativeTransform {
     pub trackedDeviceIndex: u32,
     pub transform: Vec<f64>, // 4x4 flattened
 }
 
+#[napi(object)]
+pub struct BindingInfo {
+    pub devicePath: String, // e.g. "/user/hand/right"
+    pub inputPath: String,  // e.g. "/input/b"
+    pub mode: String,       // e.g. "button"
+    pub slot: String,       // e.g. "click"
+    pub sourceType: String,
+}
+
 #[napi(object)]
 pub struct CurrentBindings {
     pub initialized: bool,
-    pub toggleOverlay: Vec<String>,
-    pub triggerBindings: Vec<String>,
-    pub gripBindings: Vec<String>,
+    pub toggleOverlay: Vec<BindingInfo>,
+    pub triggerBindings: Vec<BindingInfo>,
+    pub gripBindings: Vec<BindingInfo>,
+    /// Localized origin names from GetOriginLocalizedName / GetOriginLocalizedName による表示名
+    pub toggleOverlayOrigins: Vec<String>,
+    pub triggerOrigins: Vec<String>,
+    pub gripOrigins: Vec<String>,
     pub triggerBound: bool,
     pub gripBound: bool,
 }
+
+#[napi(object)]
+pub struct SmoothingSettings {
+    pub enabled: Option<bool>,
+    pub minCutoff: Option<f64>,
+    pub beta: Option<f64>,
+    pub derivativeCutoff: Option<f64>,
+}
+
+#[napi(object)]
+pub struct ControllerIntersection {
+    pub raw: IntersectionResult,
+    pub smoothed: IntersectionResult,
+    /// Pose snapshot time in seconds / ポーズスナップショットの時刻 (秒)
+    pub timestamp: f64,
+}
+
+#[napi(object)]
+pub struct TriggerSettings {
+    pub pressThreshold: Option<f64>,
+    pub releaseThreshold: Option<f64>,
+    pub minHoldSeconds: Option<f64>,
+    pub longPressSeconds: Option<f64>,
+    pub doubleClickSeconds: Option<f64>,
+}
+
+#[napi(object)]
+pub struct TriggerEvent {
+    pub kind: String, // "pressed" | "released" | "longPress" | "doubleClick"
+    pub controllerIndex: u32,
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+    pub value: f64,
+}
+
+#[napi(object)]
+pub struct InputThreadOptions {
+    pub rateHz: Option<f64>, // capped at the HMD refresh rate
+    pub overlayHandle: Option<i64>,
+}
+
+#[napi(object)]
+pub struct InputThreadEvent {
+    pub kind: String, // "toggle" | "trigger" | "hit" | "devices" | "device" | "gaze" | "radial" | "swipe"
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+    pub controllerIndex: Option<u32>,
+    pub trigger: Option<TriggerEvent>,
+    pub gesture: Option<ToggleGestureEvent>,
+    pub hit: Option<IntersectionResult>, // absent on a "hit" event = ray left the overlay
+    pub controllerIds: Option<Vec<u32>>,
+    pub device: Option<DeviceEvent>,
+    pub gaze: Option<GazeUpdate>,
+    pub radial: Option<RadialTypingEvent>,
+    pub swipe: Option<SwipeResult>,
+}
+
+#[napi(object)]
+pub struct ToggleGestureSettings {
+    pub mode: Option<String>, // "click" | "doubleClick" | "longPress" | "hold"
+    pub releaseDebounceSeconds: Option<f64>,
+    pub doubleClickSeconds: Option<f64>,
+    pub longPressSeconds: Option<f64>,
+}
+
+#[napi(object)]
+pub struct ToggleGestureEvent {
+    pub kind: String, // "click" | "doubleClick" | "longPress" | "holdStart" | "holdEnd"
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+    pub action: Option<String>, // "toggle" | "show" | "hide" when the configured mode acts on it
+}
+
+#[napi(object)]
+pub struct ActionManifestFiles {
+    pub actionsPath: String,
+    pub bindingPaths: Vec<String>,
+}
+
+#[napi(object)]
+pub struct ActionManifestIssue {
+    pub severity: String, // "error" | "warning"
+    pub message: String,
+}
+
+#[napi(object)]
+pub struct TrackedDeviceInfo {
+    pub index: u32,
+    pub deviceClass: String, // "hmd" | "controller" | "tracker" | "trackingReference" | "other"
+    pub role: Option<String>, // "left" | "right"
+    pub serial: String,
+    pub battery: Option<f64>, // 0..1, absent when the device has no battery
+    pub charging: Option<bool>,
+}
+
+#[napi(object)]
+pub struct DeviceEvent {
+    pub kind: String, // "connected" | "disconnected" | "roleChanged" | "batteryChanged"
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+    pub index: u32,
+    pub previousIndex: Option<u32>, // set on "connected" when the device reconnected under a new index
+    pub device: TrackedDeviceInfo,
+}
+
+#[napi(object)]
+pub struct GazeSettings {
+    pub enabled: Option<bool>,
+    pub dwellSeconds: Option<f64>,
+    pub exitGraceSeconds: Option<f64>,
+}
+
+#[napi(object)]
+pub struct GazeRegion {
+    pub id: String,
+    pub u: f64, // top-left corner in overlay UV
+    pub v: f64,
+    pub width: f64,
+    pub height: f64,
+    pub dwellSeconds: Option<f64>, // overrides GazeSettings.dwellSeconds for this key
+}
+
+#[napi(object)]
+pub struct GazeUpdate {
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+    pub hit: Option<IntersectionResult>,
+    pub regionId: Option<String>,
+    pub progress: f64, // 0..1 toward a click on regionId
+    pub clicks: Vec<TriggerEvent>,
+}
+
+#[napi(object)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct TextureDescriptor {
+    pub format: Option<String>,        // "rgba8" | "bgra8" | "rgbx8" | "bgrx8"
+    pub rowPitch: Option<u32>,         // bytes per source row; defaults to width * 4
+    pub sourceRect: Option<PixelRect>, // region of the buffer to upload; defaults to all of it
+    pub premultiplied: Option<bool>,   // source color is already multiplied by alpha
+    pub alpha: Option<String>,         // output: "straight" (default) | "premultiply" | "opaque"
+}
+
+#[napi(object)]
+pub struct TextureOutputSettings {
+    pub colorSpace: Option<String>, // "auto" (default) | "gamma" | "linear"
+    pub srgb: Option<bool>,         // sRGB texture formats; alpha applied in linear light
+    pub outputTextures: Option<u32>, // D3D11 output texture ring size (1..=8, default 3)
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct ImageOptions {
+    pub width: Option<u32>, // texture width; keeps the aspect ratio without height
+    pub height: Option<u32>, // texture height; keeps the aspect ratio without width
+    pub alpha: Option<String>, // output alpha, as TextureDescriptor.alpha
+}
+
+#[napi(object)]
+pub struct ImageInfo {
+    pub format: String,   // "png" | "jpeg" | "webp" | "svg"
+    pub sourceWidth: u32, // size of the image in the file
+    pub sourceHeight: u32,
+    pub width: u32, // size of the submitted texture
+    pub height: u32,
+}
+
+#[napi(object)]
+pub struct TextureUpdate {
+    pub submitted: bool, // false when the frame was unchanged or every output texture was busy
+    pub dirtyRatio: f64, // share of the frame uploaded (0..1)
+    pub dirtyRects: u32,
+}
+
+#[napi(object)]
+pub struct DurationStats {
+    pub samples: u32,
+    pub p50: f64, // milliseconds
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[napi(object)]
+pub struct TextureStats {
+    pub d3d11: bool,               // false when frames go through SetOverlayRaw
+    pub swapRedBlue: Option<bool>, // channel probe result; unset until the probe has run
+    pub outputTextures: u32,
+    pub frame: DurationStats, // whole submission, including the frame diff
+    pub upload: DurationStats,
+    pub convert: DurationStats,
+    pub submit: DurationStats,
+    pub bytesUploaded: f64,
+    pub submittedFrames: u32,
+    pub skippedFrames: u32, // unchanged frames
+    pub busyFrames: u32,    // dropped while every D3D11 output texture was still being drawn
+    pub droppedFrames: u32, // replaced in the submitOverlayFrame queue
+    pub resourceRecreations: u32,
+}
+
+#[napi(object)]
+pub struct FrameSubmission {
+    pub dropped: bool,   // replaced by a newer frame before it was uploaded
+    pub submitted: bool, // false when dropped or unchanged
+    pub dirtyRatio: f64,
+    pub dirtyRects: u32,
+    pub submittedFrames: u32, // frames processed by submitOverlayFrame so far
+    pub droppedFrames: u32,
+}
+
+#[napi(object)]
+pub struct SwipeSettings {
+    pub enabled: Option<bool>,
+    pub minSampleDistance: Option<f64>, // UV distance between recorded trail points
+    pub tolerance: Option<f64>,         // typical UV deviation from a word's key path
+    pub maxCandidates: Option<u32>,
+}
+
+#[napi(object)]
+pub struct SwipeKey {
+    pub label: String,
+    pub u: f64, // top-left corner in overlay UV
+    pub v: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[napi(object)]
+pub struct SwipeWord {
+    pub word: String,
+    pub score: f64, // 0..1 share of the likelihood
+}
+
+#[napi(object)]
+pub struct SwipeResult {
+    pub controllerIndex: u32,
+    pub overlayHandle: i64,
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub startedAt: f64,
+    pub endedAt: f64,
+    pub pointCount: u32,
+    pub candidates: Vec<SwipeWord>, // best first
+}
+
+#[napi(object)]
+pub struct RadialTypingSettings {
+    pub enabled: Option<bool>,
+    pub deadzone: Option<f64>, // stick deflection 0..1 that starts a highlight
+    pub releaseDeadzone: Option<f64>, // deflection below which it clears; <= deadzone
+    pub hysteresisDegrees: Option<f64>,
+}
+
+#[napi(object)]
+pub struct RadialTypingLayout {
+    pub language: String,
+    /// Sector characters clockwise from straight up / 真上から時計回りのセクターの文字
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+}
+
+#[napi(object)]
+pub struct RadialTypingEvent {
+    pub kind: String, // "highlighted" | "committed"
+    pub controllerIndex: u32,
+    pub hand: String,
+    pub sector: Option<u32>, // absent on "highlighted" = stick back in the deadzone
+    pub character: Option<String>,
+    /// Seconds on the manager clock / マネージャー時計での秒
+    pub timestamp: f64,
+}
@@ -27,6 +27,15 @@ interface SteamVrAutoLaunchResult {
   error?: string;
 }
 
+// Structured SteamVR binding entry / 構造化された SteamVR 割り当て
+interface SteamVrBindingInfo {
+  devicePath: string;
+  inputPath: string;
+  mode: string;
+  slot: string;
+  sourceType: string;
+}
+
 interface ElectronAPI {
   updateOscPort: (port: number) => Promise<UpdateOscPortResult>;
   getOscPort: () => Promise<GetOscPortResult>;
@@ -55,9 +64,12 @@ interface ElectronAPI {
     success: boolean;
     bindings?: {
       initialized: boolean;
-      toggleOverlay: string[];
-      triggerBindings: string[];
-      gripBindings: string[];
+      toggleOverlay: SteamVrBindingInfo[];
+      triggerBindings: SteamVrBindingInfo[];
+      gripBindings: SteamVrBindingInfo[];
+      toggleOverlayOrigins: string[];
+      triggerOrigins: string[];
+      gripOrigins: string[];
       triggerBound: boolean;
       gripBound: boolean;
     };